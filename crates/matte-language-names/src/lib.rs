@@ -31,6 +31,20 @@ fn lookup_by_extension<'a, 'b>(token: &'a str, assets: &'b HighlightingAssets) -
     Some(&syntax.name)
 }
 
+/// The modifiers that follow the language in a markdown
+/// language specifier, e.g. `no_run` for `rust,no_run`.
+pub fn modifiers(token: &str) -> impl Iterator<Item = &str> {
+    token.split(',').skip(1).map(str::trim)
+}
+
+/// The language of the changed code in a `diff-<language>`
+/// specifier, e.g. `rust` for `diff-rust,ignore`.
+pub fn diff_language(token: &str) -> Option<&str> {
+    let language = without_modifier(token).unwrap_or(token);
+    let (diff, language) = language.split_at_checked(5)?;
+    Some(language).filter(|l| diff.eq_ignore_ascii_case("diff-") && !l.is_empty())
+}
+
 fn without_modifier(language: &str) -> Option<&str> {
     // Some languages can have modifiers
     // after a comma e.g. `rust,no_run`
@@ -71,6 +85,22 @@ mod tests {
         assert_eq!(Some("Rust"), lookup_bat_language("rust,no_run", &assets));
     }
 
+    #[test]
+    fn splits_modifiers() {
+        assert_eq!(vec!["run"], modifiers("sh,run").collect::<Vec<_>>());
+        assert_eq!(vec!["a", "b"], modifiers("sh, a, b").collect::<Vec<_>>());
+        assert_eq!(None, modifiers("sh").next());
+    }
+
+    #[test]
+    fn finds_diff_language() {
+        assert_eq!(Some("rust"), diff_language("diff-rust"));
+        assert_eq!(Some("rust"), diff_language("Diff-rust,ignore"));
+        assert_eq!(None, diff_language("diff"));
+        assert_eq!(None, diff_language("diff-"));
+        assert_eq!(None, diff_language("rust"));
+    }
+
     #[test]
     fn finds_linguist_language() {
         let assets = HighlightingAssets::from_binary();
@@ -0,0 +1,40 @@
+//! Renders a document with many highlighted code blocks.
+//!
+//! Run with `cargo bench -p matte --bench code_blocks`.
+
+use matte::pulldown_cmark::Parser;
+use matte::{render, supported_parser_options, Options};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const CODE_BLOCKS: u32 = 200;
+const ITERATIONS: u32 = 5;
+
+fn main() {
+    let snippet = "Some text before the snippet.\n\n```rust\nfn main() {\n    println!(\"hello world\");\n}\n```\n\n";
+    let document = snippet.repeat(CODE_BLOCKS as usize);
+
+    let duration = measure(|| render_markdown(&document));
+
+    eprintln!(
+        "{CODE_BLOCKS} code blocks: {duration:?} ({:?} per block)",
+        duration / CODE_BLOCKS
+    );
+}
+
+fn render_markdown(markdown: &str) {
+    let events = Parser::new_ext(markdown, supported_parser_options());
+    let mut output = Vec::new();
+    render(events, &mut output, Options::plain_text(80)).unwrap();
+    black_box(output);
+}
+
+/// The average duration of `f` over a few iterations.
+fn measure(mut f: impl FnMut()) -> Duration {
+    f(); // warm up
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
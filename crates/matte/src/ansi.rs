@@ -0,0 +1,75 @@
+//! Helpers for text that has already been rendered
+//! and contains ANSI escape sequences (styles, hyperlinks).
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+/// Removes all escape sequences from `s`.
+pub(crate) fn strip(s: &str) -> String {
+    segments(s)
+        .filter_map(|segment| match segment {
+            Segment::Escape(_) => None,
+            Segment::Char(c) => Some(c),
+        })
+        .collect()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Segment<'a> {
+    Escape(&'a str),
+    Char(char),
+}
+
+fn segments(s: &str) -> impl Iterator<Item = Segment<'_>> {
+    let mut rest = s;
+    std::iter::from_fn(move || {
+        let c = rest.chars().next()?;
+        let len = if c == ESC as char {
+            escape_len(rest)
+        } else {
+            c.len_utf8()
+        };
+        let (head, tail) = rest.split_at(len);
+        rest = tail;
+        if c == ESC as char {
+            Some(Segment::Escape(head))
+        } else {
+            Some(Segment::Char(c))
+        }
+    })
+}
+
+/// Length in bytes of the escape sequence at the start of `s`.
+fn escape_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    match bytes.get(1) {
+        // CSI: `ESC [`, followed by parameters and a final byte.
+        Some(b'[') => bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map_or(bytes.len(), |i| i + 3),
+        // OSC: `ESC ]`, terminated by either ST (`ESC \`) or BEL.
+        Some(b']') => (2..bytes.len())
+            .find_map(|i| match bytes[i] {
+                BEL => Some(i + 1),
+                ESC if bytes.get(i + 1) == Some(&b'\\') => Some(i + 2),
+                _ => None,
+            })
+            .unwrap_or(bytes.len()),
+        Some(_) => 1 + s[1..].chars().next().map_or(0, char::len_utf8),
+        None => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STYLED: &str =
+        "\x1b[1mbold\x1b[0m \x1b]8;id=1;https://example.com\x1b\\link\x1b]8;;\x1b\\";
+
+    #[test]
+    fn strip_removes_escapes() {
+        assert_eq!("bold link", strip(STYLED));
+    }
+}
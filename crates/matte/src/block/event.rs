@@ -1,10 +1,11 @@
 use crate::block::render_block as render;
-use crate::block_quote::BlockQuote;
+use crate::block_quote::{peek_admonition, BlockQuote};
 use crate::context::Context;
 use crate::heading::Heading;
+use crate::html::HtmlBlock;
 use crate::list::List;
-use crate::{CodeBlock, Events, FootnoteDef, Paragraph, Rule, Table};
-use pulldown_cmark::{Event, Tag, TagEnd};
+use crate::{CodeBlock, Events, FootnoteDef, MetadataBlock, Paragraph, Rule, Table};
+use pulldown_cmark::{Event, MetadataBlockKind, Tag};
 use std::io;
 
 pub(crate) fn render_block_from_event<'e>(
@@ -27,17 +28,20 @@ pub(crate) fn try_render_block_from_event<'e>(
 ) -> io::Result<Option<Event<'e>>> {
     use Event::Start;
     match event {
-        Start(Tag::Paragraph) => render(Paragraph, events, ctx, w)?,
+        Start(Tag::Paragraph) => match peek_admonition(events) {
+            Some(admonition) => render(admonition, events, ctx, w)?,
+            None => render(Paragraph, events, ctx, w)?,
+        },
         Start(Tag::Heading { level, .. }) => render(Heading { level }, events, ctx, w)?,
         Start(Tag::BlockQuote(kind)) => render(BlockQuote { kind }, events, ctx, w)?,
         Start(Tag::CodeBlock(kind)) => render(CodeBlock { kind }, events, ctx, w)?,
-        Start(Tag::HtmlBlock) => html_block(events)?,
+        Start(Tag::HtmlBlock) => html_block(events, ctx, w)?,
         Start(Tag::List(first_item_number)) => render(List { first_item_number }, events, ctx, w)?,
         Start(Tag::FootnoteDefinition(reference)) => {
             render(FootnoteDef { reference }, events, ctx, w)?
         }
         Start(Tag::Table(alignments)) => render(Table { alignments }, events, ctx, w)?,
-        Start(Tag::MetadataBlock(_)) => metadata_block(events)?,
+        Start(Tag::MetadataBlock(kind)) => metadata_block(kind, events, ctx, w)?,
         Event::Rule => render(Rule, events, ctx, w)?,
         event => return Ok(Some(event)),
     }
@@ -45,12 +49,50 @@ pub(crate) fn try_render_block_from_event<'e>(
     Ok(None)
 }
 
-fn metadata_block<'e>(events: &mut impl Events<'e>) -> io::Result<()> {
-    terminated!(events, Event::End(TagEnd::MetadataBlock(..))).for_each(|_event| {});
+/// Whether the event starts a block that is rendered by [`try_render_block_from_event`].
+pub(crate) fn is_block_start(event: &Event<'_>) -> bool {
+    matches!(
+        event,
+        Event::Start(
+            Tag::Paragraph
+                | Tag::Heading { .. }
+                | Tag::BlockQuote(_)
+                | Tag::CodeBlock(_)
+                | Tag::HtmlBlock
+                | Tag::List(_)
+                | Tag::FootnoteDefinition(_)
+                | Tag::Table(_)
+                | Tag::MetadataBlock(_)
+        ) | Event::Rule
+    )
+}
+
+fn metadata_block<'e>(
+    kind: MetadataBlockKind,
+    events: &mut impl Events<'e>,
+    ctx: &Context<'_, 'e, '_>,
+    w: &mut impl io::Write,
+) -> io::Result<()> {
+    use crate::block::Block as _;
+    let block = MetadataBlock::collect(kind, events);
+    // Hidden metadata must not affect the margin of the first block.
+    if !block.is_blank(ctx) {
+        render(block, events, ctx, w)?;
+    }
     Ok(())
 }
 
-fn html_block<'e>(events: &mut impl Events<'e>) -> io::Result<()> {
-    terminated!(events, Event::End(TagEnd::HtmlBlock)).for_each(|_event| {});
+fn html_block<'e>(
+    events: &mut impl Events<'e>,
+    ctx: &Context<'_, 'e, '_>,
+    w: &mut impl io::Write,
+) -> io::Result<()> {
+    use crate::block::Block as _;
+    let block = HtmlBlock::collect(events);
+    // Blank blocks, e.g. comments, are skipped entirely
+    // so that they don't affect the margins of the surrounding blocks.
+    if !block.is_blank(ctx) {
+        render(block, events, ctx, w)?;
+    }
     Ok(())
 }
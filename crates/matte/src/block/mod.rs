@@ -4,9 +4,10 @@ use pulldown_cmark::HeadingLevel;
 mod event;
 pub(crate) use event::*;
 
-#[derive(Debug, Copy, Clone)]
+/// The kinds of blocks that a theme puts margins between.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[non_exhaustive]
-pub(crate) enum BlockKind {
+pub enum BlockKind {
     Heading(HeadingLevel),
     Paragraph,
     CodeBlock,
@@ -15,6 +16,8 @@ pub(crate) enum BlockKind {
     Rule,
     Table,
     FootnoteDefinition,
+    Html,
+    MetadataBlock,
 }
 
 /// Useful imports when implementing a [`Block`]
@@ -36,9 +39,15 @@ pub(crate) fn render_block<'e, B: Block>(
 ) -> io::Result<()> {
     let kind = block.kind();
     if !is_blank(&block, kind, events, ctx) {
-        writer.write_block_start(ctx)?;
+        let leading = if block.has_nested_blocks() {
+            leading_nested_blocks(events)
+        } else {
+            Vec::new()
+        };
+        writer.write_block_start(ctx, kind, &leading)?;
     }
     ctx.set_current_block(kind);
+    ctx.open_elements().clear();
     block.render(events, ctx, writer)?;
     ctx.set_previous_block(kind);
     Ok(())
@@ -57,6 +66,40 @@ pub(crate) trait Block {
     fn is_blank(&self, _ctx: &Context<'_, '_, '_>) -> bool {
         false
     }
+
+    /// Whether the block's events contain other blocks, e.g. the paragraphs of a block quote.
+    fn has_nested_blocks(&self) -> bool {
+        false
+    }
+}
+
+/// The first block inside of a block, followed by the first block inside of that block and so on.
+/// Their margins collapse with the margin of the outer block.
+fn leading_nested_blocks<'e>(events: &mut impl Events<'e>) -> Vec<BlockKind> {
+    let mut kinds = Vec::new();
+    for event in events.lookahead() {
+        let kind = match event {
+            Event::Start(Tag::Item) => continue,
+            Event::Start(Tag::Paragraph) => BlockKind::Paragraph,
+            Event::Start(Tag::Heading { level, .. }) => BlockKind::Heading(level),
+            Event::Start(Tag::BlockQuote(_)) => BlockKind::BlockQuote,
+            Event::Start(Tag::CodeBlock(_)) => BlockKind::CodeBlock,
+            Event::Start(Tag::HtmlBlock) => BlockKind::Html,
+            Event::Start(Tag::List(_)) => BlockKind::List,
+            Event::Start(Tag::FootnoteDefinition(_)) => BlockKind::FootnoteDefinition,
+            Event::Start(Tag::Table(_)) => BlockKind::Table,
+            Event::Rule => BlockKind::Rule,
+            _ => break,
+        };
+        kinds.push(kind);
+        if !matches!(
+            kind,
+            BlockKind::BlockQuote | BlockKind::List | BlockKind::FootnoteDefinition
+        ) {
+            break;
+        }
+    }
+    kinds
 }
 
 fn is_blank<'e>(
@@ -71,5 +114,9 @@ fn is_blank<'e>(
 }
 
 fn has_start_and_end_tag(kind: BlockKind) -> bool {
-    !matches!(kind, BlockKind::Rule)
+    // HTML and metadata blocks are collected before rendering to find out if they're blank.
+    !matches!(
+        kind,
+        BlockKind::Rule | BlockKind::Html | BlockKind::MetadataBlock
+    )
 }
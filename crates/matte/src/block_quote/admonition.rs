@@ -0,0 +1,272 @@
+//! Admonitions from documentation generators, which are plain paragraphs to CommonMark:
+//!
+//! * MkDocs: `!!! note "Title"` followed by indented content.
+//! * Docusaurus and VitePress: `:::note Title` up to a closing `:::`.
+
+use super::classification::{first_line, parse_fenced_admonition, parse_mkdocs_admonition, Header};
+use super::{write_title, Kind};
+use crate::block::prelude::*;
+use crate::block::{is_block_start, render_block, render_block_from_event};
+use crate::lookahead::Lookaheadable;
+use crate::prefix::Prefix;
+use crate::themes::ThemeProvider as _;
+use crate::{supported_parser_options, Paragraph};
+use pulldown_cmark::{CodeBlockKind, CowStr, Parser};
+use std::vec;
+
+pub(crate) struct Admonition {
+    header: Header,
+    syntax: Syntax,
+    /// Whether the paragraph with the header continues after it.
+    continues_paragraph: bool,
+}
+
+enum Syntax {
+    Indented,
+    /// Ends with the given fence, e.g. `:::`.
+    Fenced(String),
+}
+
+/// Looks for the header of an admonition on the first line of a paragraph,
+/// i.e. right after its start tag, and consumes it.
+pub(crate) fn peek_admonition<'e>(events: &mut impl Events<'e>) -> Option<Admonition> {
+    let mut events = events.lookahead();
+    let (line, continues_paragraph) = first_line(&mut events)?;
+    let (syntax, header) = match parse_mkdocs_admonition(&line) {
+        Some(header) => (Syntax::Indented, header),
+        None => {
+            let (fence, header) = parse_fenced_admonition(&line)?;
+            (Syntax::Fenced(fence.to_owned()), header)
+        }
+    };
+    _ = events.commit();
+    Some(Admonition {
+        header,
+        syntax,
+        continues_paragraph,
+    })
+}
+
+impl Block for Admonition {
+    fn kind(&self) -> BlockKind {
+        BlockKind::BlockQuote
+    }
+
+    fn render<'e>(
+        self,
+        events: &mut impl Events<'e>,
+        outer: &Context<'_, 'e, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        let kind = Some(Kind::Markup(self.header.kind));
+        let theme_ctx = outer.theme_context();
+        let prefix = outer
+            .theme()
+            .block_quote_prefix(Some(self.header.kind), &theme_ctx);
+        let style = outer
+            .theme()
+            .block_quote_style(Some(self.header.kind), &theme_ctx);
+        let ctx = outer.block(Prefix::from(prefix), style);
+
+        write_title(kind, Some(&self.header.title), &ctx, w)?;
+        match self.syntax {
+            Syntax::Indented => {
+                if self.continues_paragraph {
+                    render_block(Paragraph, events, &ctx, w)?;
+                }
+                write_indented_content(events, &ctx, w)
+            }
+            Syntax::Fenced(fence) => {
+                let rest = write_fenced_content(&fence, self.continues_paragraph, events, &ctx, w)?;
+                if rest.is_empty() {
+                    return Ok(());
+                }
+                // Text right after the closing fence is a paragraph of its own.
+                outer.set_previous_block(BlockKind::BlockQuote);
+                render_block(Paragraph, &mut paragraph(rest), outer, w)
+            }
+        }
+    }
+}
+
+/// Content that is separated from the header by a blank line
+/// is parsed as an indented code block, so we parse it again as markdown.
+fn write_indented_content<'e>(
+    events: &mut impl Events<'e>,
+    ctx: &Context<'_, 'e, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let mut lookahead = events.lookahead();
+    if !matches!(
+        lookahead.next(),
+        Some(Event::Start(Tag::CodeBlock(CodeBlockKind::Indented)))
+    ) {
+        return Ok(());
+    }
+    _ = lookahead.commit();
+
+    let mut content = String::new();
+    for event in terminated!(events, Event::End(TagEnd::CodeBlock)) {
+        if let Event::Text(text) = event {
+            content.push_str(&text);
+        }
+    }
+    // The events have to outlive the content since they end up in the context,
+    // e.g. as footnote definitions. They are collected so that the type of
+    // the events is the same for nested admonitions.
+    let events: Vec<Event<'e>> = Parser::new_ext(&content, supported_parser_options())
+        .map(into_static)
+        .collect();
+    let mut events = Lookaheadable::new(events.into_iter());
+    while let Some(event) = events.next() {
+        render_block_from_event(event, &mut events, ctx, w)?;
+    }
+    Ok(())
+}
+
+/// Writes the blocks up to the closing fence.
+/// Returns the events of the paragraph with the closing fence that follow it.
+fn write_fenced_content<'e>(
+    fence: &str,
+    continues_paragraph: bool,
+    events: &mut impl Events<'e>,
+    ctx: &Context<'_, 'e, '_>,
+    w: &mut impl Write,
+) -> io::Result<Vec<Event<'e>>> {
+    if continues_paragraph {
+        if let Some(rest) = write_fenced_paragraph(fence, events, ctx, w)? {
+            return Ok(rest);
+        }
+    }
+    loop {
+        let next = events.lookahead().next();
+        match next {
+            Some(Event::Start(Tag::Paragraph)) => {
+                events.next();
+                if let Some(admonition) = peek_admonition(events) {
+                    render_block(admonition, events, ctx, w)?;
+                } else if let Some(rest) = write_fenced_paragraph(fence, events, ctx, w)? {
+                    return Ok(rest);
+                }
+            }
+            Some(event) if is_block_start(&event) => {
+                let event = events.next().unwrap();
+                render_block_from_event(event, events, ctx, w)?;
+            }
+            // An admonition that isn't closed ends with its container.
+            _ => return Ok(Vec::new()),
+        }
+    }
+}
+
+/// Writes the rest of a paragraph, up to a line with the closing fence.
+/// Returns the events after the fence if there's one.
+fn write_fenced_paragraph<'e>(
+    fence: &str,
+    events: &mut impl Events<'e>,
+    ctx: &Context<'_, 'e, '_>,
+    w: &mut impl Write,
+) -> io::Result<Option<Vec<Event<'e>>>> {
+    let mut paragraph: Vec<_> = terminated!(events, Event::End(TagEnd::Paragraph)).collect();
+    let is_break = |event: Option<&Event<'_>>| {
+        matches!(event, None | Some(Event::SoftBreak | Event::HardBreak))
+    };
+    let closing = (0..paragraph.len()).find(|&index| {
+        matches!(&paragraph[index], Event::Text(text) if text.trim() == fence)
+            && is_break(index.checked_sub(1).map(|before| &paragraph[before]))
+            && is_break(paragraph.get(index + 1))
+    });
+
+    let rest = closing.map(|index| {
+        let rest = paragraph.split_off((index + 2).min(paragraph.len()));
+        // Removes the fence along with the line break before it.
+        paragraph.truncate(index.saturating_sub(1));
+        rest
+    });
+    if !paragraph.is_empty() {
+        render_block(Paragraph, &mut self::paragraph(paragraph), ctx, w)?;
+    }
+    Ok(rest)
+}
+
+/// The events of a paragraph without its start tag.
+fn paragraph(mut events: Vec<Event<'_>>) -> Lookaheadable<Event<'_>, vec::IntoIter<Event<'_>>> {
+    events.push(Event::End(TagEnd::Paragraph));
+    Lookaheadable::new(events.into_iter())
+}
+
+fn into_static(event: Event<'_>) -> Event<'static> {
+    fn owned(s: CowStr<'_>) -> CowStr<'static> {
+        s.into_string().into()
+    }
+    match event {
+        Event::Start(tag) => Event::Start(match tag {
+            Tag::Heading {
+                level,
+                id,
+                classes,
+                attrs,
+            } => Tag::Heading {
+                level,
+                id: id.map(owned),
+                classes: classes.into_iter().map(owned).collect(),
+                attrs: attrs
+                    .into_iter()
+                    .map(|(key, value)| (owned(key), value.map(owned)))
+                    .collect(),
+            },
+            Tag::CodeBlock(CodeBlockKind::Fenced(info)) => {
+                Tag::CodeBlock(CodeBlockKind::Fenced(owned(info)))
+            }
+            Tag::CodeBlock(CodeBlockKind::Indented) => Tag::CodeBlock(CodeBlockKind::Indented),
+            Tag::FootnoteDefinition(label) => Tag::FootnoteDefinition(owned(label)),
+            Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            } => Tag::Link {
+                link_type,
+                dest_url: owned(dest_url),
+                title: owned(title),
+                id: owned(id),
+            },
+            Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            } => Tag::Image {
+                link_type,
+                dest_url: owned(dest_url),
+                title: owned(title),
+                id: owned(id),
+            },
+            Tag::Paragraph => Tag::Paragraph,
+            Tag::BlockQuote(kind) => Tag::BlockQuote(kind),
+            Tag::HtmlBlock => Tag::HtmlBlock,
+            Tag::List(first_item_number) => Tag::List(first_item_number),
+            Tag::Item => Tag::Item,
+            Tag::Table(alignments) => Tag::Table(alignments),
+            Tag::TableHead => Tag::TableHead,
+            Tag::TableRow => Tag::TableRow,
+            Tag::TableCell => Tag::TableCell,
+            Tag::Emphasis => Tag::Emphasis,
+            Tag::Strong => Tag::Strong,
+            Tag::Strikethrough => Tag::Strikethrough,
+            Tag::MetadataBlock(kind) => Tag::MetadataBlock(kind),
+        }),
+        Event::End(tag) => Event::End(tag),
+        Event::Text(text) => Event::Text(owned(text)),
+        Event::Code(code) => Event::Code(owned(code)),
+        Event::InlineMath(math) => Event::InlineMath(owned(math)),
+        Event::DisplayMath(math) => Event::DisplayMath(owned(math)),
+        Event::Html(html) => Event::Html(owned(html)),
+        Event::InlineHtml(html) => Event::InlineHtml(owned(html)),
+        Event::FootnoteReference(label) => Event::FootnoteReference(owned(label)),
+        Event::SoftBreak => Event::SoftBreak,
+        Event::HardBreak => Event::HardBreak,
+        Event::Rule => Event::Rule,
+        Event::TaskListMarker(checked) => Event::TaskListMarker(checked),
+    }
+}
@@ -1,17 +1,38 @@
+use crate::lookahead::Lookahead as _;
 use crate::options::SymbolRepertoire;
 use crate::render::Events;
-use anstyle::{AnsiColor, Style};
-use pulldown_cmark::{BlockQuoteKind, Event, Tag};
-use AnsiColor::*;
+use pulldown_cmark::{BlockQuoteKind, Event, Tag, TagEnd};
 use BlockQuoteKind::*;
 use Kind::*;
 
+/// A block quote's kind along with a custom title, e.g. from `> [!WARNING] Mind the gap`.
+#[derive(Debug)]
+pub(super) struct Classification {
+    pub(super) kind: Kind,
+    pub(super) title: Option<String>,
+    /// Whether the first paragraph continues after the line that declares the kind.
+    pub(super) continues_paragraph: bool,
+}
+
+impl From<Kind> for Classification {
+    fn from(kind: Kind) -> Self {
+        Classification {
+            kind,
+            title: None,
+            continues_paragraph: false,
+        }
+    }
+}
+
+/// Classifies a block quote, consuming the declaration of an alert
+/// that pulldown-cmark doesn't recognize itself, e.g. one with a custom title.
 pub(super) fn classify<'e>(
     events: &mut impl Events<'e>,
     kind: Option<BlockQuoteKind>,
-) -> Option<Kind> {
-    kind.map(Kind::Markup)
-        .or_else(|| classify_from_text(events).map(Kind::Text))
+) -> Option<Classification> {
+    kind.map(|kind| Markup(kind).into())
+        .or_else(|| classify_alert(events))
+        .or_else(|| classify_from_text(events).map(|kind| Text(kind).into()))
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -21,33 +42,32 @@ pub(crate) enum Kind {
 }
 
 impl Kind {
-    pub(crate) fn style(self) -> Style {
-        match BlockQuoteKind::from(self) {
-            Note => Blue.on_default(),
-            Tip => Green.on_default(),
-            Important => Magenta.on_default(),
-            Warning => Yellow.on_default(),
-            Caution => Red.on_default(),
-        }
-    }
-
-    pub(super) fn title(self, symbols: SymbolRepertoire) -> Option<&'static str> {
-        // `U+FE0F` is used to request an emoji presentation for an emoji character.
-        // has little effect in my tests, hopefully the situation will improve over time.
+    /// The title shown above the block quote, unless it has a custom one.
+    pub(super) fn name(self) -> Option<&'static str> {
         match self {
-            Markup(Note) if symbols.has_emoji() => Some("ℹ️\u{FE0F} Note"),
             Markup(Note) => Some("Note"),
-            Markup(Tip) if symbols.has_emoji() => Some("💡 Tip"),
             Markup(Tip) => Some("Tip"),
-            Markup(Important) if symbols.has_emoji() => Some("💬 Important"),
             Markup(Important) => Some("Important"),
-            Markup(Warning) if symbols.has_emoji() => Some("⚠️\u{FE0F} Warning"),
             Markup(Warning) => Some("Warning"),
-            Markup(Caution) if symbols.has_emoji() => Some("🛑 Caution"),
             Markup(Caution) => Some("Caution"),
             Text(_) => None,
         }
     }
+
+    /// The icon in front of the title.
+    pub(super) fn icon(self, symbols: SymbolRepertoire) -> Option<&'static str> {
+        // `U+FE0F` is used to request an emoji presentation for an emoji character.
+        // has little effect in my tests, hopefully the situation will improve over time.
+        match self {
+            _ if !symbols.has_emoji() => None,
+            Markup(Note) => Some("ℹ️\u{FE0F}"),
+            Markup(Tip) => Some("💡"),
+            Markup(Important) => Some("💬"),
+            Markup(Warning) => Some("⚠️\u{FE0F}"),
+            Markup(Caution) => Some("🛑"),
+            Text(_) => None,
+        }
+    }
 }
 
 impl From<Kind> for BlockQuoteKind {
@@ -59,6 +79,129 @@ impl From<Kind> for BlockQuoteKind {
     }
 }
 
+/// The kind and title of an alert or admonition, e.g. from `!!! tip "Title"`.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Header {
+    pub(super) kind: BlockQuoteKind,
+    /// Empty titles hide the title line.
+    pub(super) title: String,
+}
+
+/// Reads the first line of a paragraph, i.e. the text after its start tag up to a line break.
+/// Returns the line and whether the paragraph continues after it,
+/// or `None` if the line contains anything but text.
+pub(super) fn first_line<'e>(events: impl Iterator<Item = Event<'e>>) -> Option<(String, bool)> {
+    let mut line = String::new();
+    for event in events {
+        match event {
+            Event::Text(text) => line.push_str(&text),
+            Event::SoftBreak | Event::HardBreak => return Some((line, true)),
+            Event::End(TagEnd::Paragraph) => return Some((line, false)),
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn classify_alert<'e>(events: &mut impl Events<'e>) -> Option<Classification> {
+    let mut events = events.lookahead();
+    if !matches!(events.next(), Some(Event::Start(Tag::Paragraph))) {
+        return None;
+    }
+    let (line, continues_paragraph) = first_line(&mut events)?;
+    let header = parse_alert(&line)?;
+    _ = events.commit();
+    Some(Classification {
+        kind: Markup(header.kind),
+        title: Some(header.title),
+        continues_paragraph,
+    })
+}
+
+/// Parses alerts that pulldown-cmark doesn't recognize:
+/// Those with a custom title (`[!NOTE] Title`), foldable Obsidian callouts (`[!tip]-`)
+/// and kinds other than the five from GitHub (`[!danger]`).
+fn parse_alert(line: &str) -> Option<Header> {
+    let (name, rest) = line.trim().strip_prefix("[!")?.split_once(']')?;
+    let rest = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    header(name, Some(rest.trim()).filter(|title| !title.is_empty()))
+}
+
+/// Parses the first line of a MkDocs admonition, e.g. `!!! warning "Title"`,
+/// or of a foldable one (`??? note` or `???+ note`).
+pub(super) fn parse_mkdocs_admonition(line: &str) -> Option<Header> {
+    let rest = ["!!!", "???+", "???"]
+        .iter()
+        .find_map(|marker| line.strip_prefix(marker))?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let (classes, title) = match rest.split_once('"') {
+        Some((classes, title)) => (classes, Some(title.trim_end().strip_suffix('"')?)),
+        None => (rest, None),
+    };
+    header(classes.split_whitespace().next()?, title)
+}
+
+/// Parses the opening fence of a Docusaurus or VitePress admonition,
+/// e.g. `:::tip Title` or `:::tip[Title]`, returning the fence that closes it.
+pub(super) fn parse_fenced_admonition(line: &str) -> Option<(&str, Header)> {
+    let line = line.trim_end();
+    let rest = line.trim_start_matches(':');
+    let fence = &line[..line.len() - rest.len()];
+    if fence.len() < 3 {
+        return None;
+    }
+    let rest = rest.trim_start();
+    let end = rest
+        .find(|c: char| c.is_whitespace() || c == '[')
+        .unwrap_or(rest.len());
+    let (name, title) = rest.split_at(end);
+    let title = match title.strip_prefix('[') {
+        Some(title) => Some(title.strip_suffix(']')?),
+        None => Some(title.trim()).filter(|title| !title.is_empty()),
+    };
+    Some((fence, header(name, title)?))
+}
+
+/// Defaults the title to the capitalized name, e.g. `Danger` for `!!! danger`.
+fn header(name: &str, title: Option<&str>) -> Option<Header> {
+    let kind = admonition_kind(name)?;
+    let title = title.map_or_else(
+        || {
+            let mut chars = name.chars();
+            chars
+                .next()
+                .map(|first| {
+                    first
+                        .to_uppercase()
+                        .chain(chars.flat_map(char::to_lowercase))
+                })
+                .into_iter()
+                .flatten()
+                .collect()
+        },
+        str::to_owned,
+    );
+    Some(Header { kind, title })
+}
+
+/// Maps the admonition types of GitHub, Obsidian, MkDocs and Docusaurus to alert kinds.
+fn admonition_kind(name: &str) -> Option<BlockQuoteKind> {
+    match name.to_ascii_lowercase().as_str() {
+        "note" | "info" | "abstract" | "summary" | "tldr" | "todo" | "seealso" | "example"
+        | "quote" | "cite" | "details" => Some(Note),
+        "tip" | "hint" | "success" | "check" | "done" => Some(Tip),
+        "important" | "question" | "help" | "faq" => Some(Important),
+        "warning" | "attention" => Some(Warning),
+        "caution" | "danger" | "error" | "failure" | "fail" | "missing" | "bug" => Some(Caution),
+        _ => None,
+    }
+}
+
 fn classify_from_text<'e>(events: &mut impl Events<'e>) -> Option<BlockQuoteKind> {
     macro_rules! starts_with {
         ($text:ident, $symbol:literal) => {
@@ -152,6 +295,88 @@ mod tests {
         }
     }
 
+    #[test]
+    fn alerts_with_titles() {
+        let alert = |kind, title: &str| {
+            Some(Header {
+                kind,
+                title: title.to_owned(),
+            })
+        };
+        assert_eq!(
+            alert(Note, "Custom title"),
+            parse_alert("[!NOTE] Custom title")
+        );
+        assert_eq!(alert(Tip, "Tip"), parse_alert("[!tip]-"));
+        assert_eq!(
+            alert(Caution, "Here be dragons"),
+            parse_alert("[!danger]+ Here be dragons")
+        );
+        assert_eq!(None, parse_alert("[!NOTE]: not an alert"));
+        assert_eq!(None, parse_alert("[!UNKNOWN]"));
+    }
+
+    #[test]
+    fn mkdocs_admonitions() {
+        let header = |kind, title: &str| {
+            Some(Header {
+                kind,
+                title: title.to_owned(),
+            })
+        };
+        assert_eq!(
+            header(Warning, "Warning"),
+            parse_mkdocs_admonition("!!! warning")
+        );
+        assert_eq!(
+            header(Caution, "Be careful"),
+            parse_mkdocs_admonition("!!! danger inline \"Be careful\"")
+        );
+        assert_eq!(header(Note, ""), parse_mkdocs_admonition("??? note \"\""));
+        assert_eq!(
+            header(Tip, "Success"),
+            parse_mkdocs_admonition("???+ success")
+        );
+        assert_eq!(None, parse_mkdocs_admonition("!!!warning"));
+        assert_eq!(None, parse_mkdocs_admonition("!!! note \"Unterminated"));
+    }
+
+    #[test]
+    fn fenced_admonitions() {
+        let fenced = |fence, kind, title: &str| {
+            Some((
+                fence,
+                Header {
+                    kind,
+                    title: title.to_owned(),
+                },
+            ))
+        };
+        assert_eq!(fenced(":::", Tip, "Tip"), parse_fenced_admonition(":::tip"));
+        assert_eq!(
+            fenced("::::", Important, "Pro tip"),
+            parse_fenced_admonition(":::: important Pro tip")
+        );
+        assert_eq!(
+            fenced(":::", Caution, "Careful"),
+            parse_fenced_admonition(":::danger[Careful]")
+        );
+        assert_eq!(None, parse_fenced_admonition(":::"));
+        assert_eq!(None, parse_fenced_admonition("::note"));
+    }
+
+    #[test]
+    fn classifies_alerts_with_titles() {
+        let mut parser =
+            Parser::new_ext("[!WARNING] Mind the gap\nBody", supported_parser_options());
+        let mut events = Lookaheadable::new(&mut parser);
+        let classification = super::classify(&mut events, None).unwrap();
+        assert!(matches!(classification.kind, Markup(Warning)));
+        assert_eq!(Some("Mind the gap"), classification.title.as_deref());
+        assert!(classification.continues_paragraph);
+        assert_eq!(Some(Event::Text("Body".into())), events.next());
+    }
+
     fn classify(markdown: &str) -> Option<BlockQuoteKind> {
         let mut parser = Parser::new_ext(markdown, supported_parser_options());
         let mut events = Lookaheadable::new(&mut parser);
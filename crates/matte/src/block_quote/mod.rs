@@ -1,15 +1,18 @@
 use crate::block::prelude::*;
-use crate::block::render_block_from_event;
+use crate::block::{render_block, render_block_from_event};
 use crate::inline::Inline;
 use crate::prefix::Prefix;
-use crate::themes::ThemeProvider as _;
+use crate::themes::{alert_style, ThemeProvider as _};
+use crate::Paragraph;
 use anstyle::{Reset, Style};
 use author::peek_quote_author;
 use classification::classify;
 use pulldown_cmark::BlockQuoteKind;
 
+mod admonition;
 mod author;
 mod classification;
+pub(crate) use admonition::peek_admonition;
 pub(crate) use classification::Kind;
 
 pub(crate) struct BlockQuote {
@@ -21,6 +24,10 @@ impl Block for BlockQuote {
         BlockKind::BlockQuote
     }
 
+    fn has_nested_blocks(&self) -> bool {
+        true
+    }
+
     fn render<'e>(
         self,
         events: &mut impl Events<'e>,
@@ -43,12 +50,19 @@ fn write_block_quote<'e>(
     ctx: &Context<'_, 'e, '_>,
     w: &mut impl Write,
 ) -> io::Result<()> {
-    let kind = classify(events, kind);
-    let prefix = ctx.theme().block_quote_prefix(kind, ctx);
-    let style = ctx.theme().block_quote_style(kind, ctx);
-    let ctx = ctx.block(prefix, style);
+    let classification = classify(events, kind);
+    let kind = classification.as_ref().map(|c| c.kind);
+    let theme_ctx = ctx.theme_context();
+    let alert = kind.map(BlockQuoteKind::from);
+    let prefix = ctx.theme().block_quote_prefix(alert, &theme_ctx);
+    let style = ctx.theme().block_quote_style(alert, &theme_ctx);
+    let ctx = ctx.block(Prefix::from(prefix), style);
 
-    write_title(kind, &ctx, w)?;
+    let title = classification.as_ref().and_then(|c| c.title.as_deref());
+    write_title(kind, title, &ctx, w)?;
+    if classification.is_some_and(|c| c.continues_paragraph) {
+        render_block(Paragraph, events, &ctx, w)?;
+    }
 
     terminated_for! {
         for event in terminated!(events, Event::End(TagEnd::BlockQuote)) {
@@ -59,14 +73,25 @@ fn write_block_quote<'e>(
     Ok(())
 }
 
-fn write_title(kind: Option<Kind>, ctx: &Context<'_, '_, '_>, w: &mut impl Write) -> io::Result<()> {
-    if let Some(kind) = kind {
-        if let Some(title) = kind.title(ctx.options().symbol_repertoire) {
-            w.write_prefix(ctx)?;
-            writeln!(w, "{}{title}{Reset}", kind.style().bold())?;
-        }
+/// Writes the title of an alert, a custom title replaces the name of its kind.
+fn write_title(
+    kind: Option<Kind>,
+    title: Option<&str>,
+    ctx: &Context<'_, '_, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let Some(kind) = kind else {
+        return Ok(());
+    };
+    let Some(title) = title.or(kind.name()).filter(|title| !title.is_empty()) else {
+        return Ok(());
+    };
+    w.write_prefix(ctx)?;
+    let style = alert_style(kind.into()).bold();
+    match kind.icon(ctx.options().symbol_repertoire) {
+        Some(icon) => writeln!(w, "{style}{icon} {title}{Reset}"),
+        None => writeln!(w, "{style}{title}{Reset}"),
     }
-    Ok(())
 }
 
 fn write_author<'a>(
@@ -0,0 +1,23 @@
+//! Running code blocks and showing their output, see [`CodeRunner`].
+
+use std::fmt;
+
+/// Runs the code of code blocks that have the `run` modifier, e.g. ```` ```sh,run ````.
+/// The output is shown beneath the code block.
+///
+/// Running code from a document is only safe if the document is trusted,
+/// so matte doesn't come with a runner.
+pub trait CodeRunner: fmt::Debug {
+    /// Runs `code` written in `language`.
+    /// Returns `None` if code in that language can't be run.
+    fn run(&self, language: &str, code: &str) -> Option<CodeOutput>;
+}
+
+/// What a code block printed when it was run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeOutput {
+    pub stdout: String,
+    pub stderr: String,
+    /// Why the code didn't run successfully, e.g. `exit status: 1`.
+    pub failure: Option<String>,
+}
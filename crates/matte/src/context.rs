@@ -1,14 +1,16 @@
 use crate::block::BlockKind;
 use crate::counting::Counters;
 use crate::footnotes::Footnotes;
-use crate::list::Bullets;
+use crate::html::OpenElements;
 use crate::prefix::{Prefix, PrefixChain};
 use crate::style::StyleExt;
-use crate::themes::Theme;
+use crate::syntax_highlighting::Highlighter;
+use crate::themes::{Theme, ThemeContext, ThemeProvider as _};
 use crate::Options;
 use anstyle::Style;
 use std::cell::Cell;
 use std::cmp::min;
+use std::slice;
 use unicode_width::UnicodeWidthStr as _;
 
 #[derive(Debug)]
@@ -16,8 +18,15 @@ pub(crate) struct Context<'a, 'e, 's> {
     prefix: PrefixChain<'a>,
     style: Style,
     previous_block: Cell<Option<BlockKind>>,
+    /// The last block inside of the previous block, e.g. the last paragraph of a block quote.
+    previous_nested_block: Cell<Option<BlockKind>>,
+    /// Where contexts of nested blocks report the last block that they rendered.
+    nested_block: Cell<Option<BlockKind>>,
+    parent_nested_block: Option<&'a Cell<Option<BlockKind>>>,
     current_block: Cell<Option<BlockKind>>,
     list_depth: usize,
+    /// The numbers of the ordered list items that this context is nested in.
+    list_item_numbers: Vec<usize>,
     state: &'s State<'e>,
 }
 
@@ -27,8 +36,12 @@ impl<'a, 'e, 's> Context<'a, 'e, 's> {
             prefix: Default::default(),
             style: Default::default(),
             previous_block: Default::default(),
+            previous_nested_block: Default::default(),
+            nested_block: Default::default(),
+            parent_nested_block: None,
             current_block: Default::default(),
             list_depth: Default::default(),
+            list_item_numbers: Vec::new(),
             state,
         }
     }
@@ -39,16 +52,21 @@ pub(crate) struct State<'e> {
     options: Options,
     counters: Counters,
     footnotes: Footnotes<'e>,
-    bullets: Bullets,
+    open_elements: OpenElements,
+    highlighter: Highlighter,
 }
 
 impl State<'_> {
     pub(crate) fn new(options: Options) -> Self {
         Self {
-            bullets: Bullets::default_for(options.symbol_repertoire),
+            highlighter: Highlighter::new(
+                options.syntax_theme.clone(),
+                options.diagnostics.clone(),
+            ),
             options,
             counters: Counters::default(),
             footnotes: Footnotes::default(),
+            open_elements: OpenElements::default(),
         }
     }
 }
@@ -68,8 +86,12 @@ impl<'a, 'e, 's> Context<'a, 'e, 's> {
             prefix,
             style,
             previous_block: Cell::default(),
+            previous_nested_block: Cell::default(),
+            nested_block: Cell::default(),
+            parent_nested_block: Some(&self.nested_block),
             current_block: Cell::default(),
             list_depth: self.list_depth,
+            list_item_numbers: self.list_item_numbers.clone(),
             state: self.state,
         }
     }
@@ -78,6 +100,11 @@ impl<'a, 'e, 's> Context<'a, 'e, 's> {
         self.list_depth += 1;
         self
     }
+
+    pub(crate) fn with_list_item_number(mut self, number: usize) -> Self {
+        self.list_item_numbers.push(number);
+        self
+    }
 }
 
 impl<'a, 'e> Context<'a, 'e, '_> {
@@ -94,7 +121,34 @@ impl<'a, 'e> Context<'a, 'e, '_> {
     }
 
     pub(crate) fn set_previous_block(&self, b: BlockKind) {
+        let nested = self.nested_block.take();
         self.previous_block.set(Some(b));
+        self.previous_nested_block.set(nested);
+        if let Some(parent) = self.parent_nested_block {
+            parent.set(nested.or(Some(b)));
+        }
+    }
+
+    /// The number of blank lines between the previous block and the next one,
+    /// `leading` are the first blocks nested inside of the next one.
+    /// The margins of a nested block collapse with the margins of the blocks around it:
+    /// The first block in e.g. a block quote has no margin of its own, so the margin before
+    /// the block quote is at least the one before that block, and
+    /// the margin after a block quote is at least the one after its last block.
+    pub(crate) fn margin_before(&self, next: BlockKind, leading: &[BlockKind]) -> usize {
+        let Some(previous) = self.previous_block() else {
+            return 0;
+        };
+        let theme = self.theme();
+        let theme_ctx = &self.theme_context();
+        let after = [previous]
+            .into_iter()
+            .chain(self.previous_nested_block.get());
+        let before = || slice::from_ref(&next).iter().chain(leading);
+        after
+            .flat_map(|a| before().map(move |b| theme.margin_size(&a, b, theme_ctx)))
+            .max()
+            .unwrap_or_default()
     }
 
     pub(crate) fn current_block(&self) -> Option<BlockKind> {
@@ -125,11 +179,31 @@ impl<'a, 'e> Context<'a, 'e, '_> {
         &self.state.counters
     }
 
+    pub(crate) fn list_depth(&self) -> usize {
+        self.list_depth
+    }
+
+    pub(crate) fn list_item_numbers(&self) -> &[usize] {
+        &self.list_item_numbers
+    }
+
     pub(crate) fn bullet(&self) -> &str {
-        self.state.bullets.nth(self.list_depth)
+        self.theme().bullet(&self.theme_context())
+    }
+
+    pub(crate) fn open_elements(&self) -> &OpenElements {
+        &self.state.open_elements
+    }
+
+    pub(crate) fn highlighter(&self) -> &Highlighter {
+        &self.state.highlighter
     }
 
     pub(crate) fn theme(&self) -> &Theme {
         &self.options().theme
     }
+
+    pub(crate) fn theme_context(&self) -> ThemeContext<'_> {
+        ThemeContext::new(self)
+    }
 }
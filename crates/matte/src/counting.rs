@@ -16,18 +16,21 @@ impl Counters {
     }
 }
 
+/// The numbers of the sections that the current heading is nested in.
 #[derive(Debug, Default, Clone)]
-pub(crate) struct SectionCounter {
+pub struct SectionCounter {
     counters: [usize; 6],
     end: usize,
 }
 
 impl SectionCounter {
-    pub(crate) fn as_slice(&self) -> &[usize] {
+    /// One number per level up to the current heading's,
+    /// e.g. `[1, 0, 2]` for the second H3 in the first H1 that has no H2.
+    pub fn as_slice(&self) -> &[usize] {
         &self.counters[0..=self.end]
     }
 
-    fn update(&mut self, level: HeadingLevel) {
+    pub(crate) fn update(&mut self, level: HeadingLevel) {
         let index = to_index(level);
         self.counters[index] += 1;
         self.end = index;
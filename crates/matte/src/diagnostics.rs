@@ -0,0 +1,46 @@
+//! Problems with a document that don't prevent it from being rendered.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Collects [`Diagnostic`]s reported while rendering.
+///
+/// This is a shared handle: Keep a clone around,
+/// pass the other one to [`crate::render`] via [`crate::Options`]
+/// and [take](`Diagnostics::take`) the diagnostics after rendering.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics(Arc<Mutex<Vec<Diagnostic>>>);
+
+impl Diagnostics {
+    /// Removes and returns all diagnostics reported so far.
+    pub fn take(&self) -> Vec<Diagnostic> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    pub(crate) fn report(&self, diagnostic: Diagnostic) {
+        self.lock().push(diagnostic);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Diagnostic>> {
+        // A panic while holding the lock can't leave the list in an inconsistent state.
+        self.0.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Diagnostic {
+    /// A code block's language is not known, so it's shown without highlighting.
+    UnknownLanguage { language: String },
+    /// The syntax theme is not bundled with bat, so code is highlighted with the `ansi` theme.
+    UnknownSyntaxTheme { name: String },
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Diagnostic::UnknownLanguage { language } => write!(f, "unknown language: {language}"),
+            Diagnostic::UnknownSyntaxTheme { name } => write!(f, "unknown syntax theme: {name}"),
+        }
+    }
+}
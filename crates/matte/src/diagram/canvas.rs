@@ -0,0 +1,235 @@
+use unicode_width::UnicodeWidthChar as _;
+
+pub(super) const UP: u8 = 1;
+pub(super) const DOWN: u8 = 2;
+pub(super) const LEFT: u8 = 4;
+pub(super) const RIGHT: u8 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    /// Lines leaving the cell's center in the given directions,
+    /// so that crossing lines can be drawn as junctions.
+    Line {
+        directions: u8,
+        rounded: bool,
+    },
+    Char(char),
+    /// The second column of a wide character.
+    Continuation,
+}
+
+/// A grid of characters that lines, boxes and text are drawn onto.
+/// It grows as needed when drawing.
+#[derive(Debug)]
+pub(super) struct Canvas {
+    rows: Vec<Vec<Cell>>,
+    unicode: bool,
+}
+
+impl Canvas {
+    pub(super) fn new(unicode: bool) -> Self {
+        Self {
+            rows: Vec::new(),
+            unicode,
+        }
+    }
+
+    pub(super) fn unicode(&self) -> bool {
+        self.unicode
+    }
+
+    /// The width of the widest row.
+    pub(super) fn width(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .rposition(|cell| *cell != Cell::Empty)
+                    .map_or(0, |last| last + 1)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn cell_mut(&mut self, x: usize, y: usize) -> &mut Cell {
+        if self.rows.len() <= y {
+            self.rows.resize_with(y + 1, Vec::new);
+        }
+        let row = &mut self.rows[y];
+        if row.len() <= x {
+            row.resize(x + 1, Cell::Empty);
+        }
+        &mut row[x]
+    }
+
+    /// Adds line segments to a cell, text is never overwritten by lines.
+    pub(super) fn line(&mut self, x: usize, y: usize, new: u8) {
+        let cell = self.cell_mut(x, y);
+        match cell {
+            Cell::Empty => {
+                *cell = Cell::Line {
+                    directions: new,
+                    rounded: false,
+                }
+            }
+            Cell::Line { directions, .. } => *directions |= new,
+            Cell::Char(_) | Cell::Continuation => {}
+        }
+    }
+
+    /// Draws a horizontal line from `x1` to `x2`, both inclusive.
+    pub(super) fn horizontal(&mut self, y: usize, x1: usize, x2: usize) {
+        let (start, end) = (x1.min(x2), x1.max(x2));
+        for x in start..=end {
+            let mut directions = 0;
+            if x > start {
+                directions |= LEFT;
+            }
+            if x < end {
+                directions |= RIGHT;
+            }
+            self.line(x, y, directions);
+        }
+    }
+
+    /// Draws a vertical line from `y1` to `y2`, both inclusive.
+    pub(super) fn vertical(&mut self, x: usize, y1: usize, y2: usize) {
+        let (start, end) = (y1.min(y2), y1.max(y2));
+        for y in start..=end {
+            let mut directions = 0;
+            if y > start {
+                directions |= UP;
+            }
+            if y < end {
+                directions |= DOWN;
+            }
+            self.line(x, y, directions);
+        }
+    }
+
+    /// Draws a box with the given outer size.
+    pub(super) fn rect(&mut self, x: usize, y: usize, width: usize, height: usize, rounded: bool) {
+        let (right, bottom) = (x + width - 1, y + height - 1);
+        self.horizontal(y, x, right);
+        self.horizontal(bottom, x, right);
+        self.vertical(x, y, bottom);
+        self.vertical(right, y, bottom);
+        if rounded {
+            for (x, y) in [(x, y), (right, y), (x, bottom), (right, bottom)] {
+                if let Cell::Line { rounded, .. } = self.cell_mut(x, y) {
+                    *rounded = true;
+                }
+            }
+        }
+    }
+
+    /// Writes text, replacing whatever was drawn there before.
+    pub(super) fn text(&mut self, x: usize, y: usize, text: &str) {
+        let mut x = x;
+        for c in text.chars() {
+            let width = c.width().unwrap_or(0);
+            if width == 0 {
+                continue;
+            }
+            *self.cell_mut(x, y) = Cell::Char(c);
+            for column in 1..width {
+                *self.cell_mut(x + column, y) = Cell::Continuation;
+            }
+            x += width;
+        }
+    }
+
+    pub(super) fn into_lines(self) -> Vec<String> {
+        let unicode = self.unicode;
+        self.rows
+            .into_iter()
+            .map(|row| {
+                let line: String = row
+                    .into_iter()
+                    .filter_map(|cell| match cell {
+                        Cell::Empty => Some(' '),
+                        Cell::Line {
+                            directions,
+                            rounded,
+                        } => Some(line_char(directions, rounded, unicode)),
+                        Cell::Char(c) => Some(c),
+                        Cell::Continuation => None,
+                    })
+                    .collect();
+                line.trim_end().to_owned()
+            })
+            .collect()
+    }
+}
+
+fn line_char(directions: u8, rounded: bool, unicode: bool) -> char {
+    const VERTICAL: u8 = UP | DOWN;
+    const HORIZONTAL: u8 = LEFT | RIGHT;
+    const TOP_LEFT: u8 = DOWN | RIGHT;
+    const TOP_RIGHT: u8 = DOWN | LEFT;
+    const BOTTOM_LEFT: u8 = UP | RIGHT;
+    const BOTTOM_RIGHT: u8 = UP | LEFT;
+    const TEE_RIGHT: u8 = VERTICAL | RIGHT;
+    const TEE_LEFT: u8 = VERTICAL | LEFT;
+    const TEE_DOWN: u8 = HORIZONTAL | DOWN;
+    const TEE_UP: u8 = HORIZONTAL | UP;
+
+    if !unicode {
+        return match (directions & VERTICAL != 0, directions & HORIZONTAL != 0) {
+            (true, false) => '|',
+            (false, true) => '-',
+            _ => '+',
+        };
+    }
+    match (directions, rounded) {
+        (UP | DOWN | VERTICAL, _) => '│',
+        (LEFT | RIGHT | HORIZONTAL, _) => '─',
+        (TOP_LEFT, false) => '┌',
+        (TOP_LEFT, true) => '╭',
+        (TOP_RIGHT, false) => '┐',
+        (TOP_RIGHT, true) => '╮',
+        (BOTTOM_LEFT, false) => '└',
+        (BOTTOM_LEFT, true) => '╰',
+        (BOTTOM_RIGHT, false) => '┘',
+        (BOTTOM_RIGHT, true) => '╯',
+        (TEE_RIGHT, _) => '├',
+        (TEE_LEFT, _) => '┤',
+        (TEE_DOWN, _) => '┬',
+        (TEE_UP, _) => '┴',
+        _ => '┼',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_join() {
+        let mut canvas = Canvas::new(true);
+        canvas.rect(0, 0, 6, 3, false);
+        canvas.vertical(2, 2, 4);
+        canvas.horizontal(4, 2, 6);
+        canvas.text(1, 1, "日本");
+        assert_eq!(
+            vec!["┌────┐", "│日本│", "└─┬──┘", "  │", "  └────"],
+            canvas.into_lines()
+        );
+    }
+
+    #[test]
+    fn ascii_lines() {
+        let mut canvas = Canvas::new(false);
+        canvas.rect(0, 0, 4, 3, true);
+        canvas.text(1, 1, "ab");
+        assert_eq!(vec!["+--+", "|ab|", "+--+"], canvas.into_lines());
+    }
+
+    #[test]
+    fn rounded_corners() {
+        let mut canvas = Canvas::new(true);
+        canvas.rect(0, 0, 3, 2, true);
+        assert_eq!(vec!["╭─╮", "╰─╯"], canvas.into_lines());
+    }
+}
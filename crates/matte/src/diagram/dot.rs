@@ -0,0 +1,329 @@
+use super::{Direction, Edge, Graph, Shape};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// An identifier, a number or a quoted string.
+    Id(String),
+    /// `->` or `--`.
+    EdgeOp,
+    Punct(char),
+}
+
+/// Parses a DOT graph without subgraphs.
+pub(super) fn parse(source: &str) -> Option<Graph> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        graph: Graph::default(),
+        node_shape: Shape::Rounded,
+    };
+    parser.graph()?;
+    Some(parser.graph)
+}
+
+fn tokenize(source: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    let mut at_line_start = true;
+    while let Some((index, c)) = chars.next() {
+        let line_start = at_line_start;
+        at_line_start = c == '\n' || (line_start && c.is_whitespace());
+        match c {
+            c if c.is_whitespace() => {}
+            // Preprocessor output lines.
+            '#' if line_start => at_line_start = skip_line(&mut chars),
+            '/' if source[index..].starts_with("//") => at_line_start = skip_line(&mut chars),
+            '/' if source[index..].starts_with("/*") => {
+                let end = source[index + 2..].find("*/")?;
+                while chars.next_if(|(i, _)| *i < index + 2 + end + 2).is_some() {}
+            }
+            '-' if matches!(chars.peek(), Some((_, '>' | '-'))) => {
+                chars.next();
+                tokens.push(Token::EdgeOp);
+            }
+            '"' => {
+                let mut id = String::new();
+                loop {
+                    match chars.next()?.1 {
+                        '"' => break,
+                        '\\' => match chars.next()?.1 {
+                            '"' => id.push('"'),
+                            '\n' => {}
+                            c => {
+                                id.push('\\');
+                                id.push(c);
+                            }
+                        },
+                        c => id.push(c),
+                    }
+                }
+                tokens.push(Token::Id(id));
+            }
+            '{' | '}' | '[' | ']' | ';' | ',' | '=' | ':' => tokens.push(Token::Punct(c)),
+            c if c.is_alphanumeric() || matches!(c, '_' | '.' | '-') => {
+                let mut id = c.to_string();
+                while let Some((_, c)) =
+                    chars.next_if(|(_, c)| c.is_alphanumeric() || matches!(c, '_' | '.'))
+                {
+                    id.push(c);
+                }
+                tokens.push(Token::Id(id));
+            }
+            // HTML labels and anything else we don't understand.
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+/// Skips the rest of the line, returning whether a line follows.
+fn skip_line(chars: &mut impl Iterator<Item = (usize, char)>) -> bool {
+    chars.any(|(_, c)| c == '\n')
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    graph: Graph,
+    /// The shape of new nodes, graphviz draws ellipses by default.
+    node_shape: Shape,
+}
+
+impl Parser<'_> {
+    fn graph(&mut self) -> Option<()> {
+        self.keyword("strict");
+        let directed = if self.keyword("digraph") {
+            true
+        } else if self.keyword("graph") {
+            false
+        } else {
+            return None;
+        };
+        if matches!(self.tokens.first(), Some(Token::Id(_))) {
+            self.id()?;
+        }
+        self.punct('{').then_some(())?;
+        while !self.punct('}') {
+            self.statement(directed)?;
+            self.punct(';');
+        }
+        self.tokens.is_empty().then_some(())
+    }
+
+    fn statement(&mut self, directed: bool) -> Option<()> {
+        let id = self.id()?;
+        if id.eq_ignore_ascii_case("subgraph") {
+            return None;
+        }
+        if id.eq_ignore_ascii_case("graph") && self.tokens.first() == Some(&Token::Punct('[')) {
+            for (key, value) in self.attributes()? {
+                self.graph_attribute(&key, &value)?;
+            }
+            return Some(());
+        }
+        if id.eq_ignore_ascii_case("node") && self.tokens.first() == Some(&Token::Punct('[')) {
+            for (key, value) in self.attributes()? {
+                if key == "shape" {
+                    self.node_shape = shape(&value);
+                }
+            }
+            return Some(());
+        }
+        if id.eq_ignore_ascii_case("edge") && self.tokens.first() == Some(&Token::Punct('[')) {
+            // Default edge attributes are about styling.
+            self.attributes()?;
+            return Some(());
+        }
+        if self.punct('=') {
+            let value = self.id()?;
+            return self.graph_attribute(&id, &value);
+        }
+
+        let mut nodes = vec![self.node(&id)?];
+        while self.tokens.first() == Some(&Token::EdgeOp) {
+            self.tokens = &self.tokens[1..];
+            let id = self.id()?;
+            nodes.push(self.node(&id)?);
+        }
+        let attributes = self.attributes()?;
+        if nodes.len() == 1 {
+            let node = &mut self.graph.nodes[nodes[0]];
+            for (key, value) in attributes {
+                match key.as_str() {
+                    "label" => node.label = label_lines(&value, &node.id),
+                    "shape" => node.shape = shape(&value),
+                    _ => {}
+                }
+            }
+        } else {
+            let label = attributes
+                .into_iter()
+                .find(|(key, _)| key == "label")
+                .map(|(_, value)| label_lines(&value, "").join(" "));
+            for pair in nodes.windows(2) {
+                self.graph.edges.push(Edge {
+                    from: pair[0],
+                    to: pair[1],
+                    label: label.clone(),
+                    arrow: directed,
+                });
+            }
+        }
+        Some(())
+    }
+
+    fn graph_attribute(&mut self, key: &str, value: &str) -> Option<()> {
+        if key == "rankdir" {
+            self.graph.direction = match value {
+                "TB" => Direction::TopDown,
+                "LR" => Direction::LeftRight,
+                _ => return None,
+            };
+        }
+        Some(())
+    }
+
+    /// Adds a node by its id, ignoring ports such as `a:n`.
+    fn node(&mut self, id: &str) -> Option<usize> {
+        if id.eq_ignore_ascii_case("subgraph") {
+            return None;
+        }
+        while self.punct(':') {
+            self.id()?;
+        }
+        let new = !self.graph.nodes.iter().any(|node| node.id == id);
+        let index = self.graph.node(id);
+        if new {
+            self.graph.nodes[index].shape = self.node_shape;
+        }
+        Some(index)
+    }
+
+    /// Parses optional attribute lists, e.g. `[label="a", shape=box]`.
+    fn attributes(&mut self) -> Option<Vec<(String, String)>> {
+        let mut attributes = Vec::new();
+        while self.punct('[') {
+            while !self.punct(']') {
+                let key = self.id()?;
+                self.punct('=').then_some(())?;
+                attributes.push((key, self.id()?));
+                if !self.punct(',') {
+                    self.punct(';');
+                }
+            }
+        }
+        Some(attributes)
+    }
+
+    fn id(&mut self) -> Option<String> {
+        let (Token::Id(id), rest) = self.tokens.split_first()? else {
+            return None;
+        };
+        self.tokens = rest;
+        Some(id.clone())
+    }
+
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.tokens.first() {
+            Some(Token::Id(id)) if id.eq_ignore_ascii_case(keyword) => {
+                self.tokens = &self.tokens[1..];
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn punct(&mut self, punct: char) -> bool {
+        if self.tokens.first() == Some(&Token::Punct(punct)) {
+            self.tokens = &self.tokens[1..];
+            true
+        } else {
+            false
+        }
+    }
+}
+
+fn shape(shape: &str) -> Shape {
+    match shape {
+        "box" | "rect" | "rectangle" | "square" | "record" => Shape::Rectangle,
+        _ => Shape::Rounded,
+    }
+}
+
+/// Splits a label at its escaped line breaks, replacing `\N` with the node's id.
+fn label_lines(label: &str, id: &str) -> Vec<String> {
+    let label = label
+        .replace("\\N", id)
+        .replace("\\l", "\n")
+        .replace("\\r", "\n")
+        .replace("\\n", "\n");
+    let lines: Vec<_> = label.lines().map(|line| line.trim().to_owned()).collect();
+    if lines.is_empty() {
+        vec![String::new()]
+    } else {
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>, bool)> {
+        graph
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    graph.nodes[edge.from].id.as_str(),
+                    graph.nodes[edge.to].id.as_str(),
+                    edge.label.as_deref(),
+                    edge.arrow,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_digraphs() {
+        let graph = parse(
+            r#"// Build pipeline
+            strict digraph build {
+                rankdir=LR;
+                node [shape=box];
+                src [label="Sources\nin src/"];
+                src -> build -> "test suite" [label=runs];
+                /* ports are ignored */
+                build:s -> done
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(Direction::LeftRight, graph.direction);
+        assert_eq!(vec!["Sources", "in src/"], graph.nodes[0].label);
+        assert_eq!(Shape::Rectangle, graph.nodes[0].shape);
+        assert_eq!(
+            vec![
+                ("src", "build", Some("runs"), true),
+                ("build", "test suite", Some("runs"), true),
+                ("build", "done", None, true),
+            ],
+            edges(&graph)
+        );
+    }
+
+    #[test]
+    fn parses_node_shapes() {
+        let graph = parse("graph { a [shape=box]; b [shape=circle]; a -- b }").unwrap();
+        assert_eq!(Shape::Rectangle, graph.nodes[0].shape);
+        assert_eq!(Shape::Rounded, graph.nodes[1].shape);
+        assert_eq!(vec![("a", "b", None, false)], edges(&graph));
+    }
+
+    #[test]
+    fn unsupported_graphs() {
+        assert_eq!(None, parse("digraph { subgraph cluster { a } }"));
+        assert_eq!(None, parse("digraph { { a b } -> c }"));
+        assert_eq!(None, parse("digraph { a [label=<<b>a</b>>] }"));
+        assert_eq!(None, parse("digraph { rankdir=BT; a -> b }"));
+        assert_eq!(None, parse("digraph { a -> b"));
+    }
+}
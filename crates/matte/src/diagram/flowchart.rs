@@ -0,0 +1,625 @@
+//! Lays out graphs in layers, loosely following Sugiyama et al.:
+//! Nodes are assigned to layers so that all edges point in the same direction,
+//! edges that span multiple layers get invisible dummy nodes in the layers in between,
+//! and the nodes of each layer are ordered to reduce crossings.
+
+use super::canvas::Canvas;
+use super::{Direction, Graph, Shape};
+use std::collections::BTreeMap;
+use unicode_width::UnicodeWidthStr as _;
+
+/// Graphs with more nodes than this are unreadable as text anyway.
+const MAX_NODES: usize = 100;
+
+/// Limits the number of edges and of the nodes and dummy nodes in all layers,
+/// as ordering and positioning them takes quadratic time.
+const MAX_EDGES: usize = 200;
+const MAX_ITEMS: usize = 1000;
+
+/// How often the layers are reordered to reduce crossings.
+const ORDERING_PASSES: usize = 4;
+
+/// Draws the graph, returns `None` if it has cycles or too many nodes or edges.
+pub(super) fn draw(graph: &Graph, canvas: &mut Canvas) -> Option<()> {
+    if graph.nodes.len() > MAX_NODES || graph.edges.len() > MAX_EDGES {
+        return None;
+    }
+    let ranks = ranks(graph)?;
+    // Each edge has a dummy node in every layer between its ends.
+    let dummies: usize = graph
+        .edges
+        .iter()
+        .map(|edge| ranks[edge.to].abs_diff(ranks[edge.from]).saturating_sub(1))
+        .sum();
+    if graph.nodes.len() + dummies > MAX_ITEMS {
+        return None;
+    }
+    let mut layout = Layout::new(graph, &ranks);
+    layout.order();
+    layout.position();
+    layout.draw(graph, canvas);
+    Some(())
+}
+
+/// Assigns each node to a layer such that all edges point to a later layer.
+/// Returns `None` if the graph has cycles.
+fn ranks(graph: &Graph) -> Option<Vec<usize>> {
+    let mut incoming = vec![0; graph.nodes.len()];
+    for edge in &graph.edges {
+        incoming[edge.to] += 1;
+    }
+    let mut ready: Vec<_> = (0..graph.nodes.len())
+        .filter(|&node| incoming[node] == 0)
+        .collect();
+    let mut ranks = vec![0; graph.nodes.len()];
+    let mut sorted = Vec::with_capacity(graph.nodes.len());
+    while let Some(node) = ready.pop() {
+        sorted.push(node);
+        for edge in graph.edges.iter().filter(|edge| edge.from == node) {
+            ranks[edge.to] = ranks[edge.to].max(ranks[node] + 1);
+            incoming[edge.to] -= 1;
+            if incoming[edge.to] == 0 {
+                ready.push(edge.to);
+            }
+        }
+    }
+    if sorted.len() < graph.nodes.len() {
+        return None;
+    }
+
+    // Sources are moved down to just above their first successor
+    // so that they aren't connected by needlessly long edges.
+    for &node in sorted.iter().rev() {
+        if graph.edges.iter().any(|edge| edge.to == node) {
+            continue;
+        }
+        if let Some(first_successor) = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == node)
+            .map(|edge| ranks[edge.to])
+            .min()
+        {
+            ranks[node] = first_successor - 1;
+        }
+    }
+    Some(ranks)
+}
+
+#[derive(Debug)]
+enum ItemKind<'a> {
+    Node(usize),
+    /// Where an edge passes through a layer, optionally with the edge's label.
+    Dummy(Option<&'a str>),
+}
+
+/// A node or dummy node with its size and position.
+/// `main` is along the direction of the edges, `cross` is across it.
+#[derive(Debug)]
+struct Item<'a> {
+    kind: ItemKind<'a>,
+    layer: usize,
+    main_size: usize,
+    cross_size: usize,
+    main: usize,
+    cross: i64,
+}
+
+impl Item<'_> {
+    fn center(&self) -> i64 {
+        self.cross + self.cross_size as i64 / 2
+    }
+}
+
+/// The part of an edge between two adjacent layers.
+#[derive(Debug)]
+struct Segment {
+    from: usize,
+    to: usize,
+    arrow: bool,
+}
+
+#[derive(Debug)]
+struct Layout<'a> {
+    direction: Direction,
+    items: Vec<Item<'a>>,
+    segments: Vec<Segment>,
+    /// The items of each layer, in order.
+    layers: Vec<Vec<usize>>,
+}
+
+impl<'a> Layout<'a> {
+    fn new(graph: &'a Graph, ranks: &[usize]) -> Self {
+        let direction = graph.direction;
+        // Labels go into a layer of their own between the nodes.
+        let spacing = if graph.edges.iter().any(|edge| edge.label.is_some()) {
+            2
+        } else {
+            1
+        };
+
+        let mut items: Vec<_> = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                let width = node
+                    .label
+                    .iter()
+                    .map(|line| line.width())
+                    .max()
+                    .unwrap_or(0)
+                    + 4;
+                let height = node.label.len() + 2;
+                let (main_size, cross_size) = match direction {
+                    Direction::TopDown => (height, width),
+                    Direction::LeftRight => (width, height),
+                };
+                Item {
+                    kind: ItemKind::Node(index),
+                    layer: ranks[index] * spacing,
+                    main_size,
+                    cross_size,
+                    main: 0,
+                    cross: 0,
+                }
+            })
+            .collect();
+
+        let mut segments = Vec::new();
+        for edge in &graph.edges {
+            let mut label = edge.label.as_deref();
+            let mut previous = edge.from;
+            for layer in items[edge.from].layer + 1..items[edge.to].layer {
+                let width = label.map_or(1, |label| label.width());
+                let (main_size, cross_size) = match (direction, label) {
+                    (Direction::TopDown, _) => (1, width),
+                    (Direction::LeftRight, Some(_)) => (width + 2, 1),
+                    (Direction::LeftRight, None) => (1, 1),
+                };
+                items.push(Item {
+                    kind: ItemKind::Dummy(label.take()),
+                    layer,
+                    main_size,
+                    cross_size,
+                    main: 0,
+                    cross: 0,
+                });
+                segments.push(Segment {
+                    from: previous,
+                    to: items.len() - 1,
+                    arrow: false,
+                });
+                previous = items.len() - 1;
+            }
+            segments.push(Segment {
+                from: previous,
+                to: edge.to,
+                arrow: edge.arrow,
+            });
+        }
+
+        let layer_count = items.iter().map(|item| item.layer + 1).max().unwrap_or(0);
+        let mut layers = vec![Vec::new(); layer_count];
+        for (index, item) in items.iter().enumerate() {
+            layers[item.layer].push(index);
+        }
+        Self {
+            direction,
+            items,
+            segments,
+            layers,
+        }
+    }
+
+    fn predecessors(&self, item: usize) -> Vec<usize> {
+        self.segments
+            .iter()
+            .filter(|segment| segment.to == item)
+            .map(|segment| segment.from)
+            .collect()
+    }
+
+    fn successors(&self, item: usize) -> Vec<usize> {
+        self.segments
+            .iter()
+            .filter(|segment| segment.from == item)
+            .map(|segment| segment.to)
+            .collect()
+    }
+
+    /// Orders the items of each layer by the average position of their neighbors
+    /// in the previous (or next) layer, which tends to reduce crossings.
+    fn order(&mut self) {
+        let mut positions = vec![0.0; self.items.len()];
+        for layer in &self.layers {
+            for (position, &item) in layer.iter().enumerate() {
+                positions[item] = position as f64;
+            }
+        }
+
+        for _ in 0..ORDERING_PASSES {
+            let down = (1..self.layers.len()).map(|layer| (layer, true));
+            let up = (0..self.layers.len().saturating_sub(1))
+                .rev()
+                .map(|layer| (layer, false));
+            for (layer, down) in down.chain(up).collect::<Vec<_>>() {
+                let mut keys: Vec<_> = self.layers[layer]
+                    .iter()
+                    .map(|&item| {
+                        let neighbors = if down {
+                            self.predecessors(item)
+                        } else {
+                            self.successors(item)
+                        };
+                        let key = if neighbors.is_empty() {
+                            positions[item]
+                        } else {
+                            neighbors.iter().map(|&n| positions[n]).sum::<f64>()
+                                / neighbors.len() as f64
+                        };
+                        (key, item)
+                    })
+                    .collect();
+                keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+                self.layers[layer] = keys.into_iter().map(|(_, item)| item).collect();
+                for (position, &item) in self.layers[layer].iter().enumerate() {
+                    positions[item] = position as f64;
+                }
+            }
+        }
+    }
+
+    fn cross_gap(&self) -> i64 {
+        match self.direction {
+            Direction::TopDown => 2,
+            Direction::LeftRight => 1,
+        }
+    }
+
+    /// How long the lines leaving a node are before they turn.
+    fn stub_length(&self) -> usize {
+        match self.direction {
+            Direction::TopDown => 1,
+            Direction::LeftRight => 2,
+        }
+    }
+
+    /// Centers the items below (and then above) their neighbors.
+    fn position(&mut self) {
+        for layer in 0..self.layers.len() {
+            self.place(layer, &vec![Vec::new(); self.layers[layer].len()]);
+        }
+        for layer in 1..self.layers.len() {
+            let neighbors: Vec<_> = self.layers[layer]
+                .iter()
+                .map(|&item| self.predecessors(item))
+                .collect();
+            self.place(layer, &neighbors);
+        }
+        for layer in (0..self.layers.len().saturating_sub(1)).rev() {
+            let neighbors: Vec<_> = self.layers[layer]
+                .iter()
+                .map(|&item| self.successors(item))
+                .collect();
+            self.place(layer, &neighbors);
+        }
+        let min = self.items.iter().map(|item| item.cross).min().unwrap_or(0);
+        for item in &mut self.items {
+            item.cross -= min;
+        }
+
+        let mut start = 0;
+        for layer in 0..self.layers.len() {
+            let thickness = self.layers[layer]
+                .iter()
+                .map(|&item| self.items[item].main_size)
+                .max()
+                .unwrap_or(0);
+            for &item in &self.layers[layer] {
+                let item = &mut self.items[item];
+                item.main = start + (thickness - item.main_size) / 2;
+            }
+            let lanes = self.lanes(layer).len();
+            let passing_through = self.layers[layer]
+                .iter()
+                .all(|&item| matches!(self.items[item].kind, ItemKind::Dummy(None)));
+            // Edges that just pass through a layer don't need room to leave it.
+            let stub = if passing_through && lanes == 0 {
+                0
+            } else {
+                self.stub_length()
+            };
+            start += thickness + stub + lanes + 1;
+        }
+    }
+
+    /// Moves the items of a layer as close to the center of their neighbors as possible,
+    /// given the neighbors of each item in the layer's order.
+    fn place(&mut self, layer: usize, neighbors: &[Vec<usize>]) {
+        let mut free = i64::MIN;
+        for (&item, neighbors) in self.layers[layer].iter().zip(neighbors) {
+            let desired = if neighbors.is_empty() {
+                self.items[item].cross.max(0)
+            } else {
+                let centers: i64 = neighbors.iter().map(|&n| self.items[n].center()).sum();
+                centers / neighbors.len() as i64 - self.items[item].cross_size as i64 / 2
+            };
+            let item = &mut self.items[item];
+            item.cross = desired.max(free);
+            free = item.cross + item.cross_size as i64 + self.cross_gap();
+        }
+    }
+
+    /// Assigns the segments that leave a layer to lanes, i.e. rows (or columns)
+    /// where they run across. Segments that leave the same item share a lane,
+    /// segments that would otherwise overlap get separate lanes.
+    /// Returns the lanes by item that the segments leave.
+    fn lanes(&self, layer: usize) -> BTreeMap<usize, usize> {
+        let mut spans = BTreeMap::<usize, (i64, i64)>::new();
+        for segment in &self.segments {
+            let (from, to) = (&self.items[segment.from], &self.items[segment.to]);
+            if from.layer != layer || from.center() == to.center() {
+                continue;
+            }
+            let span = spans
+                .entry(segment.from)
+                .or_insert((from.center(), from.center()));
+            span.0 = span.0.min(to.center());
+            span.1 = span.1.max(to.center());
+        }
+
+        let mut spans: Vec<_> = spans.into_iter().collect();
+        spans.sort_by_key(|(_, (start, _))| *start);
+        let mut lane_ends: Vec<i64> = Vec::new();
+        let mut lanes = BTreeMap::new();
+        for (item, (start, end)) in spans {
+            let lane = match lane_ends.iter().position(|&lane_end| lane_end < start) {
+                Some(lane) => lane,
+                None => {
+                    lane_ends.push(i64::MIN);
+                    lane_ends.len() - 1
+                }
+            };
+            lane_ends[lane] = end;
+            lanes.insert(item, lane);
+        }
+        lanes
+    }
+
+    fn draw(&self, graph: &Graph, canvas: &mut Canvas) {
+        let mut plot = Plot {
+            canvas,
+            direction: self.direction,
+        };
+
+        for item in &self.items {
+            let ItemKind::Node(node) = item.kind else {
+                continue;
+            };
+            let node = &graph.nodes[node];
+            let (x, y) = plot.point(item.main, item.cross as usize);
+            let (width, height) = plot.size(item.main_size, item.cross_size);
+            plot.canvas
+                .rect(x, y, width, height, node.shape == Shape::Rounded);
+            for (index, line) in node.label.iter().enumerate() {
+                let indent = (width - 2 - line.width()) / 2;
+                plot.canvas.text(x + 1 + indent, y + 1 + index, line);
+            }
+        }
+
+        let mut arrows = Vec::new();
+        for layer in 0..self.layers.len() {
+            let lanes = self.lanes(layer);
+            let thickness = self.layers[layer]
+                .iter()
+                .map(|&item| self.items[item].main + self.items[item].main_size)
+                .max()
+                .unwrap_or(0);
+            let lanes_start = thickness + self.stub_length();
+            for segment in &self.segments {
+                let (from, to) = (&self.items[segment.from], &self.items[segment.to]);
+                if from.layer != layer {
+                    continue;
+                }
+                let (from_center, to_center) = (from.center() as usize, to.center() as usize);
+                let arrow = segment.arrow && matches!(to.kind, ItemKind::Node(_));
+                let start = self.out_port(from);
+                let end = self.in_port(to, arrow);
+                match lanes.get(&segment.from) {
+                    Some(lane) => {
+                        let lane = lanes_start + lane;
+                        plot.main_line(from_center, start, lane);
+                        plot.cross_line(lane, from_center, to_center);
+                        plot.main_line(to_center, lane, end);
+                    }
+                    None => plot.main_line(from_center, start, end),
+                }
+                if arrow {
+                    arrows.push(plot.point(end, to_center));
+                }
+            }
+        }
+
+        let arrow = match (self.direction, plot.canvas.unicode()) {
+            (Direction::TopDown, true) => "▼",
+            (Direction::TopDown, false) => "v",
+            (Direction::LeftRight, true) => "▶",
+            (Direction::LeftRight, false) => ">",
+        };
+        for (x, y) in arrows {
+            plot.canvas.text(x, y, arrow);
+        }
+
+        for item in &self.items {
+            if let ItemKind::Dummy(Some(label)) = item.kind {
+                let (x, y) = plot.point(item.main, item.cross as usize);
+                match self.direction {
+                    Direction::TopDown => plot.canvas.text(x, y, label),
+                    Direction::LeftRight => plot.canvas.text(x + 1, y, label),
+                }
+            }
+        }
+    }
+
+    /// Where lines leaving the item start.
+    fn out_port(&self, item: &Item<'_>) -> usize {
+        match (&item.kind, self.direction) {
+            (ItemKind::Dummy(Some(_)), Direction::TopDown) => item.main + item.main_size,
+            _ => item.main + item.main_size - 1,
+        }
+    }
+
+    /// Where lines entering the item end.
+    fn in_port(&self, item: &Item<'_>, arrow: bool) -> usize {
+        match (&item.kind, self.direction) {
+            (ItemKind::Node(_), _) if arrow => item.main - 1,
+            (ItemKind::Dummy(Some(_)), Direction::TopDown) => item.main - 1,
+            _ => item.main,
+        }
+    }
+}
+
+/// Maps the main and cross axes of the layout to the canvas' x and y axes.
+struct Plot<'c> {
+    canvas: &'c mut Canvas,
+    direction: Direction,
+}
+
+impl Plot<'_> {
+    fn point(&self, main: usize, cross: usize) -> (usize, usize) {
+        match self.direction {
+            Direction::TopDown => (cross, main),
+            Direction::LeftRight => (main, cross),
+        }
+    }
+
+    fn size(&self, main_size: usize, cross_size: usize) -> (usize, usize) {
+        self.point(main_size, cross_size)
+    }
+
+    fn main_line(&mut self, cross: usize, start: usize, end: usize) {
+        match self.direction {
+            Direction::TopDown => self.canvas.vertical(cross, start, end),
+            Direction::LeftRight => self.canvas.horizontal(cross, start, end),
+        }
+    }
+
+    fn cross_line(&mut self, main: usize, start: usize, end: usize) {
+        match self.direction {
+            Direction::TopDown => self.canvas.horizontal(main, start, end),
+            Direction::LeftRight => self.canvas.vertical(main, start, end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Edge;
+    use super::*;
+
+    fn graph(direction: Direction, edges: &[(&str, &str, Option<&str>)]) -> Graph {
+        let mut graph = Graph {
+            direction,
+            ..Graph::default()
+        };
+        for (from, to, label) in edges {
+            let (from, to) = (graph.node(from), graph.node(to));
+            graph.edges.push(Edge {
+                from,
+                to,
+                label: label.map(str::to_owned),
+                arrow: true,
+            });
+        }
+        graph
+    }
+
+    fn lines(graph: &Graph) -> Vec<String> {
+        let mut canvas = Canvas::new(true);
+        draw(graph, &mut canvas).unwrap();
+        canvas.into_lines()
+    }
+
+    #[test]
+    fn rejects_graphs_with_too_many_dummy_nodes() {
+        let names: Vec<_> = (0..100).map(|n| format!("n{n}")).collect();
+        let mut edges: Vec<_> = names
+            .windows(2)
+            .map(|pair| (pair[0].as_str(), pair[1].as_str(), None))
+            .collect();
+        // Each of these passes through 98 layers.
+        edges.extend((0..20).map(|_| ("n0", "n99", None)));
+        let graph = graph(Direction::TopDown, &edges);
+        assert!(draw(&graph, &mut Canvas::new(true)).is_none());
+    }
+
+    #[test]
+    fn fan_out_shares_a_lane() {
+        let graph = graph(
+            Direction::TopDown,
+            &[("root", "a", None), ("root", "b", None)],
+        );
+        assert_eq!(
+            vec![
+                " ┌──────┐",
+                " │ root │",
+                " └───┬──┘",
+                "     │",
+                "  ┌──┴───┐",
+                "  ▼      ▼",
+                "┌───┐  ┌───┐",
+                "│ a │  │ b │",
+                "└───┘  └───┘",
+            ],
+            lines(&graph)
+        );
+    }
+
+    #[test]
+    fn left_to_right_with_label() {
+        let graph = graph(Direction::LeftRight, &[("a", "b", Some("yes"))]);
+        assert_eq!(
+            vec![
+                "┌───┐           ┌───┐",
+                "│ a ├────yes───▶│ b │",
+                "└───┘           └───┘",
+            ],
+            lines(&graph)
+        );
+    }
+
+    #[test]
+    fn long_edges_pass_through_layers() {
+        let graph = graph(
+            Direction::TopDown,
+            &[("a", "b", None), ("b", "c", None), ("a", "c", None)],
+        );
+        assert_eq!(
+            vec![
+                "  ┌───┐",
+                "  │ a │",
+                "  └─┬─┘",
+                "    │",
+                "  ┌─┴──┐",
+                "  ▼    │",
+                "┌───┐  │",
+                "│ b │  │",
+                "└─┬─┘  │",
+                "  │    │",
+                "  ├────┘",
+                "  ▼",
+                "┌───┐",
+                "│ c │",
+                "└───┘",
+            ],
+            lines(&graph)
+        );
+    }
+
+    #[test]
+    fn cycles_are_not_supported() {
+        let graph = graph(Direction::TopDown, &[("a", "b", None), ("b", "a", None)]);
+        assert_eq!(None, draw(&graph, &mut Canvas::new(true)));
+    }
+}
@@ -0,0 +1,458 @@
+use super::sequence::{Head, Message, Sequence};
+use super::{Direction, Edge, Graph, Shape};
+use std::iter;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Diagram {
+    Flowchart(Graph),
+    Sequence(Sequence),
+}
+
+/// Parses a mermaid flowchart or sequence diagram.
+pub(super) fn parse(source: &str) -> Option<Diagram> {
+    let mut lines = source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("%%"));
+    let first = lines.next()?;
+    let (header, rest) = first.split_once(';').unwrap_or((first, ""));
+    match header.split_whitespace().collect::<Vec<_>>()[..] {
+        ["graph" | "flowchart"] => {
+            flowchart(Direction::TopDown, iter::once(rest).chain(lines)).map(Diagram::Flowchart)
+        }
+        ["graph" | "flowchart", direction] => {
+            let direction = match direction {
+                "TD" | "TB" => Direction::TopDown,
+                "LR" => Direction::LeftRight,
+                _ => return None,
+            };
+            flowchart(direction, iter::once(rest).chain(lines)).map(Diagram::Flowchart)
+        }
+        ["sequenceDiagram"] => sequence(lines).map(Diagram::Sequence),
+        _ => None,
+    }
+}
+
+fn flowchart<'a>(direction: Direction, lines: impl Iterator<Item = &'a str>) -> Option<Graph> {
+    let mut graph = Graph {
+        direction,
+        ..Graph::default()
+    };
+    for statement in lines.flat_map(statements) {
+        let keyword = statement.split_whitespace().next().unwrap_or_default();
+        match keyword {
+            "" => {}
+            // Styling doesn't translate to text.
+            "classDef" | "class" | "style" | "linkStyle" | "click" => {}
+            "subgraph" | "end" | "direction" => return None,
+            _ => Scanner { rest: statement }.statement(&mut graph)?,
+        }
+    }
+    Some(graph)
+}
+
+/// Splits a line at semicolons that are not part of a label.
+fn statements(line: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut depth = 0_usize;
+    let mut quoted = false;
+    let mut start = 0;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '[' | '(' | '{' if !quoted => depth += 1,
+            ']' | ')' | '}' if !quoted => depth = depth.saturating_sub(1),
+            ';' if !quoted && depth == 0 => {
+                statements.push(line[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(line[start..].trim());
+    statements
+}
+
+/// Node shapes by their opening and closing delimiters, longest first.
+const SHAPES: &[(&str, &[&str], Shape)] = &[
+    ("(((", &[")))"], Shape::Rounded),
+    ("([", &["])"], Shape::Rounded),
+    ("((", &["))"], Shape::Rounded),
+    ("[(", &[")]"], Shape::Rounded),
+    ("[[", &["]]"], Shape::Rectangle),
+    ("[/", &["/]", "\\]"], Shape::Rectangle),
+    ("[\\", &["\\]", "/]"], Shape::Rectangle),
+    ("{{", &["}}"], Shape::Rectangle),
+    ("[", &["]"], Shape::Rectangle),
+    ("(", &[")"], Shape::Rounded),
+    ("{", &["}"], Shape::Rectangle),
+    (">", &["]"], Shape::Rectangle),
+];
+
+struct Link {
+    label: Option<String>,
+    arrow: bool,
+}
+
+struct Scanner<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Scanner<'a> {
+    /// A chain of nodes connected by links, e.g. `A --> B & C -- text --- D`.
+    fn statement(&mut self, graph: &mut Graph) -> Option<()> {
+        let mut previous = self.nodes(graph)?;
+        loop {
+            self.skip_whitespace();
+            if self.rest.is_empty() {
+                return Some(());
+            }
+            let link = self.link()?;
+            self.skip_whitespace();
+            let next = self.nodes(graph)?;
+            for &from in &previous {
+                for &to in &next {
+                    graph.edges.push(Edge {
+                        from,
+                        to,
+                        label: link.label.clone(),
+                        arrow: link.arrow,
+                    });
+                }
+            }
+            previous = next;
+        }
+    }
+
+    fn nodes(&mut self, graph: &mut Graph) -> Option<Vec<usize>> {
+        let mut nodes = vec![self.node(graph)?];
+        loop {
+            self.skip_whitespace();
+            if !self.eat("&") {
+                return Some(nodes);
+            }
+            self.skip_whitespace();
+            nodes.push(self.node(graph)?);
+        }
+    }
+
+    fn node(&mut self, graph: &mut Graph) -> Option<usize> {
+        let id = self.take_while(|c| c.is_alphanumeric() || c == '_');
+        if id.is_empty() {
+            return None;
+        }
+        let shape = self.shape()?;
+        if self.eat(":::") {
+            self.take_while(|c| c.is_alphanumeric() || c == '_' || c == '-');
+        }
+
+        let node = graph.node(id);
+        if let Some((label, shape)) = shape {
+            graph.nodes[node].label = label_lines(label);
+            graph.nodes[node].shape = shape;
+        }
+        Some(node)
+    }
+
+    /// The shape and label of a node, if any. Returns `None` if the shape is not closed.
+    fn shape(&mut self) -> Option<Option<(&'a str, Shape)>> {
+        let Some((open, closes, shape)) = SHAPES
+            .iter()
+            .find(|(open, _, _)| self.rest.starts_with(open))
+        else {
+            return Some(None);
+        };
+        self.rest = &self.rest[open.len()..];
+        let (end, close) = closes
+            .iter()
+            .filter_map(|close| Some((self.rest.find(close)?, close)))
+            .min()?;
+        let label = &self.rest[..end];
+        self.rest = &self.rest[end + close.len()..];
+        Some(Some((label, *shape)))
+    }
+
+    /// A link such as `-->`, `---`, `-.->` or `==>`, with an optional label
+    /// either in the middle (`-- text -->`) or after it (`-->|text|`).
+    fn link(&mut self) -> Option<Link> {
+        let mut label = None;
+        for (open, closes) in [
+            ("--", ["-->", "---"]),
+            ("==", ["==>", "==="]),
+            ("-.", [".->", ".-"]),
+        ] {
+            let Some(rest) = self.rest.strip_prefix(open) else {
+                continue;
+            };
+            if !rest.starts_with(char::is_whitespace) {
+                continue;
+            }
+            let end = closes.iter().filter_map(|close| rest.find(close)).min()?;
+            label = Some(unquote(rest[..end].trim()).to_owned());
+            self.rest = &rest[end..];
+            break;
+        }
+
+        let line = self.take_while(|c| matches!(c, '<' | '-' | '=' | '.' | '>'));
+        // Circle and cross ends, e.g. `--o` and `--x`.
+        let head = self.rest.starts_with(['o', 'x'])
+            && self.rest[1..]
+                .chars()
+                .next()
+                .is_none_or(char::is_whitespace);
+        if head {
+            self.rest = &self.rest[1..];
+        }
+        if line.len() + usize::from(head) < 3 || !line.contains(['-', '=']) {
+            return None;
+        }
+
+        self.skip_whitespace();
+        if self.eat("|") {
+            let end = self.rest.find('|')?;
+            label = Some(unquote(self.rest[..end].trim()).to_owned());
+            self.rest = &self.rest[end + 1..];
+        }
+        Some(Link {
+            label: label.filter(|label| !label.is_empty()),
+            arrow: line.ends_with('>') || head,
+        })
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        match self.rest.strip_prefix(prefix) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let end = self.rest.find(|c| !predicate(c)).unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        taken
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+}
+
+/// Labels can be quoted and may contain markdown in backticks.
+fn unquote(label: &str) -> &str {
+    let label = label.trim();
+    let label = label
+        .strip_prefix('"')
+        .and_then(|label| label.strip_suffix('"'))
+        .unwrap_or(label);
+    label
+        .strip_prefix('`')
+        .and_then(|label| label.strip_suffix('`'))
+        .unwrap_or(label)
+}
+
+fn label_lines(label: &str) -> Vec<String> {
+    let mut label = unquote(label).to_owned();
+    for line_break in ["<br />", "<br/>", "<br>", "\\n"] {
+        label = label.replace(line_break, "\n");
+    }
+    let lines: Vec<_> = label.lines().map(|line| line.trim().to_owned()).collect();
+    if lines.is_empty() {
+        vec![String::new()]
+    } else {
+        lines
+    }
+}
+
+/// Arrows of sequence diagrams, longest first.
+const ARROWS: &[(&str, bool, Head)] = &[
+    ("-->>", true, Head::Arrow),
+    ("->>", false, Head::Arrow),
+    ("--x", true, Head::Cross),
+    ("-x", false, Head::Cross),
+    ("--)", true, Head::Open),
+    ("-)", false, Head::Open),
+    ("-->", true, Head::None),
+    ("->", false, Head::None),
+];
+
+fn sequence<'a>(lines: impl Iterator<Item = &'a str>) -> Option<Sequence> {
+    let mut sequence = Sequence::default();
+    let mut autonumber = false;
+    for line in lines {
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match keyword {
+            "participant" | "actor" => {
+                let (id, label) = rest.split_once(" as ").unwrap_or((rest, rest));
+                let participant = sequence.participant(id.trim());
+                sequence.participants[participant].label = label.trim().to_owned();
+            }
+            "autonumber" => autonumber = true,
+            // Activations are not shown.
+            "activate" | "deactivate" => {}
+            _ => {
+                let (statement, text) = line.split_once(':').unwrap_or((line, ""));
+                let (start, (pattern, dashed, head)) = ARROWS
+                    .iter()
+                    .filter_map(|arrow| Some((statement.find(arrow.0)?, arrow)))
+                    .min_by_key(|(start, arrow)| (*start, usize::MAX - arrow.0.len()))?;
+                let from = statement[..start].trim();
+                let to = statement[start + pattern.len()..]
+                    .trim()
+                    .trim_start_matches(['+', '-']);
+                if [from, to]
+                    .iter()
+                    .any(|id| id.is_empty() || id.contains(char::is_whitespace))
+                {
+                    return None;
+                }
+                let (from, to) = (sequence.participant(from), sequence.participant(to));
+                let mut text = text.trim().to_owned();
+                if autonumber {
+                    text = format!("{}. {text}", sequence.messages.len() + 1);
+                }
+                sequence.messages.push(Message {
+                    from,
+                    to,
+                    text,
+                    dashed: *dashed,
+                    head: *head,
+                });
+            }
+        }
+    }
+    Some(sequence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flowchart(source: &str) -> Graph {
+        match parse(source) {
+            Some(Diagram::Flowchart(graph)) => graph,
+            diagram => panic!("not a flowchart: {diagram:?}"),
+        }
+    }
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, Option<&str>, bool)> {
+        graph
+            .edges
+            .iter()
+            .map(|edge| {
+                (
+                    graph.nodes[edge.from].id.as_str(),
+                    graph.nodes[edge.to].id.as_str(),
+                    edge.label.as_deref(),
+                    edge.arrow,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_links() {
+        let graph = flowchart(
+            "graph LR\n  A -->|yes| B\n  B -- no --- C\n  C -.-> D\n  D ==> E & F\n  E --o F",
+        );
+        assert_eq!(Direction::LeftRight, graph.direction);
+        assert_eq!(
+            vec![
+                ("A", "B", Some("yes"), true),
+                ("B", "C", Some("no"), false),
+                ("C", "D", None, true),
+                ("D", "E", None, true),
+                ("D", "F", None, true),
+                ("E", "F", None, true),
+            ],
+            edges(&graph)
+        );
+    }
+
+    #[test]
+    fn parses_node_shapes_and_labels() {
+        let graph = flowchart(
+            "flowchart TD; start([Start]) --> check{Is it?}; check --> done[\"Done<br>for now\"]:::ok",
+        );
+        assert_eq!(vec!["Start"], graph.nodes[0].label);
+        assert_eq!(Shape::Rounded, graph.nodes[0].shape);
+        assert_eq!(vec!["Is it?"], graph.nodes[1].label);
+        assert_eq!(vec!["Done", "for now"], graph.nodes[2].label);
+        assert_eq!(Shape::Rectangle, graph.nodes[2].shape);
+    }
+
+    #[test]
+    fn later_definitions_keep_labels() {
+        let graph =
+            flowchart("graph TD\n  A[Alpha] --> B\n  A --> C\n  %% comment\n  style A fill:#f9f");
+        assert_eq!(3, graph.nodes.len());
+        assert_eq!(vec!["Alpha"], graph.nodes[0].label);
+    }
+
+    #[test]
+    fn unsupported_flowcharts() {
+        assert_eq!(None, parse("graph BT\n  A --> B"));
+        assert_eq!(None, parse("graph TD\n  subgraph one\n  A --> B\n  end"));
+        assert_eq!(None, parse("graph TD\n  A[unclosed --> B"));
+        assert_eq!(None, parse("graph TD\n  A -> B"));
+        assert_eq!(None, parse("pie\n  \"a\": 1"));
+    }
+
+    #[test]
+    fn parses_sequence_diagrams() {
+        let Some(Diagram::Sequence(sequence)) = parse(
+            "sequenceDiagram\n  participant A as Alice\n  autonumber\n  A->>+Bob: Hello\n  Bob-->>-A: Hi!\n  Bob-xBob: Hmm",
+        ) else {
+            panic!("not a sequence diagram");
+        };
+        assert_eq!(
+            vec!["Alice", "Bob"],
+            sequence
+                .participants
+                .iter()
+                .map(|participant| participant.label.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                Message {
+                    from: 0,
+                    to: 1,
+                    text: "1. Hello".to_owned(),
+                    dashed: false,
+                    head: Head::Arrow
+                },
+                Message {
+                    from: 1,
+                    to: 0,
+                    text: "2. Hi!".to_owned(),
+                    dashed: true,
+                    head: Head::Arrow
+                },
+                Message {
+                    from: 1,
+                    to: 1,
+                    text: "3. Hmm".to_owned(),
+                    dashed: false,
+                    head: Head::Cross
+                },
+            ],
+            sequence.messages
+        );
+    }
+
+    #[test]
+    fn unsupported_sequence_diagrams() {
+        assert_eq!(
+            None,
+            parse("sequenceDiagram\n  A->>B: Hi\n  Note right of B: Hmm")
+        );
+        assert_eq!(
+            None,
+            parse("sequenceDiagram\n  loop Every minute\n  A->>B: Hi\n  end")
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Draws diagrams from code blocks as box-drawing text art,
+//! e.g. a mermaid flowchart such as `graph TD; A --> B` to boxes connected by lines.
+//!
+//! This covers mermaid flowcharts (`graph` and `flowchart`), mermaid sequence diagrams
+//! and DOT graphs, but only the parts of them that can be drawn reasonably with text:
+//! Nodes and edges with labels, but no subgraphs, styling or cycles.
+//! Everything else makes the conversion fail so that the caller
+//! can fall back to showing the diagram's source.
+
+use self::canvas::Canvas;
+
+mod canvas;
+mod dot;
+mod flowchart;
+mod mermaid;
+mod sequence;
+
+/// The code block languages that [`render`] supports.
+pub(crate) fn is_diagram_language(language: &str) -> bool {
+    ["mermaid", "dot", "graphviz"]
+        .iter()
+        .any(|l| l.eq_ignore_ascii_case(language))
+}
+
+/// Draws the diagram in `source` as lines of text that are at most `width` columns wide.
+/// Returns `None` if the diagram uses unsupported features or is too wide.
+pub(crate) fn render(
+    language: &str,
+    source: &str,
+    width: usize,
+    unicode: bool,
+) -> Option<Vec<String>> {
+    let mut canvas = Canvas::new(unicode);
+    if language.eq_ignore_ascii_case("mermaid") {
+        match mermaid::parse(source)? {
+            mermaid::Diagram::Flowchart(graph) => flowchart::draw(&graph, &mut canvas)?,
+            mermaid::Diagram::Sequence(sequence) => sequence::draw(&sequence, &mut canvas)?,
+        }
+    } else if language.eq_ignore_ascii_case("dot") || language.eq_ignore_ascii_case("graphviz") {
+        flowchart::draw(&dot::parse(source)?, &mut canvas)?;
+    } else {
+        return None;
+    }
+
+    if canvas.width() > width {
+        return None;
+    }
+    Some(canvas.into_lines())
+}
+
+/// A graph of nodes connected by edges, as described by a flowchart or a DOT graph.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Graph {
+    direction: Direction,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+impl Graph {
+    /// Looks up a node by its id, adding it if it doesn't exist yet.
+    fn node(&mut self, id: &str) -> usize {
+        match self.nodes.iter().position(|node| node.id == id) {
+            Some(index) => index,
+            None => {
+                self.nodes.push(Node {
+                    id: id.to_owned(),
+                    label: vec![id.to_owned()],
+                    shape: Shape::default(),
+                });
+                self.nodes.len() - 1
+            }
+        }
+    }
+}
+
+/// The direction in which edges point.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    #[default]
+    TopDown,
+    LeftRight,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Node {
+    id: String,
+    /// The lines of the node's label.
+    label: Vec<String>,
+    shape: Shape,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    #[default]
+    Rectangle,
+    Rounded,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Edge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+    /// Whether the edge ends in an arrowhead.
+    arrow: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_mermaid_and_dot() {
+        assert_eq!(
+            vec![
+                "┌───┐",
+                "│ a │",
+                "└─┬─┘",
+                "  │",
+                "  ▼",
+                "┌───┐",
+                "│ b │",
+                "└───┘"
+            ],
+            render("mermaid", "graph TD\n  a --> b\n", 80, true).unwrap()
+        );
+        assert_eq!(
+            vec![
+                "╭───╮",
+                "│ a │",
+                "╰─┬─╯",
+                "  │",
+                "  ▼",
+                "╭───╮",
+                "│ b │",
+                "╰───╯"
+            ],
+            render("dot", "digraph { a -> b }", 80, true).unwrap()
+        );
+    }
+
+    #[test]
+    fn too_wide_diagrams_are_not_rendered() {
+        assert_eq!(None, render("mermaid", "graph LR\n  a --> b\n", 10, true));
+        assert!(render("mermaid", "graph LR\n  a --> b\n", 20, true).is_some());
+    }
+
+    #[test]
+    fn unsupported_languages() {
+        assert_eq!(None, render("rust", "graph TD\n  a --> b\n", 80, true));
+        assert!(is_diagram_language("Mermaid"));
+        assert!(!is_diagram_language("rust"));
+    }
+}
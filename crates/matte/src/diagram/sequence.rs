@@ -0,0 +1,230 @@
+use super::canvas::Canvas;
+use unicode_width::UnicodeWidthStr as _;
+
+/// The minimum space between the boxes of two participants.
+const GAP: usize = 3;
+
+/// Participants that exchange messages, drawn as boxes at the top and bottom
+/// connected by lifelines, with messages as arrows between the lifelines.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(super) struct Sequence {
+    pub(super) participants: Vec<Participant>,
+    pub(super) messages: Vec<Message>,
+}
+
+impl Sequence {
+    /// Looks up a participant by its id, adding it if it doesn't exist yet.
+    pub(super) fn participant(&mut self, id: &str) -> usize {
+        match self.participants.iter().position(|p| p.id == id) {
+            Some(index) => index,
+            None => {
+                self.participants.push(Participant {
+                    id: id.to_owned(),
+                    label: id.to_owned(),
+                });
+                self.participants.len() - 1
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Participant {
+    id: String,
+    pub(super) label: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Message {
+    pub(super) from: usize,
+    pub(super) to: usize,
+    pub(super) text: String,
+    /// Dashed lines are usually used for replies.
+    pub(super) dashed: bool,
+    pub(super) head: Head,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Head {
+    None,
+    Arrow,
+    /// An asynchronous message.
+    Open,
+    Cross,
+}
+
+pub(super) fn draw(sequence: &Sequence, canvas: &mut Canvas) -> Option<()> {
+    let lifelines = lifelines(sequence)?;
+    let bottom = 3
+        + sequence
+            .messages
+            .iter()
+            .map(|message| if message.from == message.to { 3 } else { 2 })
+            .sum::<usize>()
+        + 1;
+
+    for (participant, &x) in sequence.participants.iter().zip(&lifelines) {
+        let width = participant.label.width() + 4;
+        for y in [0, bottom] {
+            canvas.rect(x - width / 2, y, width, 3, false);
+            canvas.text(x - width / 2 + 2, y + 1, &participant.label);
+        }
+        canvas.vertical(x, 2, bottom);
+    }
+
+    let unicode = canvas.unicode();
+    let mut y = 3;
+    for message in &sequence.messages {
+        let from = lifelines[message.from];
+        let to = lifelines[message.to];
+        let text_width = message.text.width();
+        if from == to {
+            canvas.text(from + 2, y, &message.text);
+            canvas.horizontal(y + 1, from, from + 3);
+            canvas.vertical(from + 3, y + 1, y + 2);
+            canvas.horizontal(y + 2, from + 1, from + 3);
+            if message.dashed {
+                dash(canvas, y + 1, from + 1, from + 2);
+                dash(canvas, y + 2, from + 2, from + 2);
+            }
+            if let Some(head) = head(message.head, false, unicode) {
+                canvas.text(from + 1, y + 2, head);
+            }
+            y += 3;
+        } else {
+            let (left, right) = (from.min(to), from.max(to));
+            canvas.text(
+                left + 1 + (right - left - 1 - text_width) / 2,
+                y,
+                &message.text,
+            );
+            let head_x = if to > from { to - 1 } else { to + 1 };
+            match head(message.head, to > from, unicode) {
+                Some(head) => {
+                    canvas.horizontal(y + 1, from, head_x);
+                    canvas.text(head_x, y + 1, head);
+                }
+                None => canvas.horizontal(y + 1, from, to),
+            }
+            if message.dashed {
+                let (start, end) = (from.min(head_x), from.max(head_x));
+                dash(canvas, y + 1, start + 1, end - 1);
+            }
+            y += 2;
+        }
+    }
+    Some(())
+}
+
+/// The x coordinates of the participants' lifelines, spaced so that the messages fit.
+/// Returns `None` if there are no participants.
+fn lifelines(sequence: &Sequence) -> Option<Vec<usize>> {
+    let widths: Vec<_> = sequence
+        .participants
+        .iter()
+        .map(|participant| participant.label.width() + 4)
+        .collect();
+    let first = widths.first()?;
+    let mut gaps: Vec<_> = widths
+        .windows(2)
+        .map(|pair| (pair[0] - 1 - pair[0] / 2) + GAP + pair[1] / 2 + 1)
+        .collect();
+
+    let mut messages: Vec<_> = sequence.messages.iter().collect();
+    messages.sort_by_key(|message| message.from.abs_diff(message.to));
+    for message in messages {
+        let (left, right) = (message.from.min(message.to), message.from.max(message.to));
+        let text_width = message.text.width();
+        if left == right {
+            // Self messages loop to the right of the lifeline.
+            if let Some(gap) = gaps.get_mut(left) {
+                *gap = (*gap).max(text_width + 4);
+            }
+            continue;
+        }
+        let needed = text_width + 4;
+        let available: usize = gaps[left..right].iter().sum();
+        if available < needed {
+            gaps[right - 1] += needed - available;
+        }
+    }
+
+    let mut lifelines = vec![first / 2];
+    for gap in gaps {
+        lifelines.push(lifelines.last().unwrap() + gap);
+    }
+    Some(lifelines)
+}
+
+fn dash(canvas: &mut Canvas, y: usize, start: usize, end: usize) {
+    let dash = if canvas.unicode() { "╌" } else { "." };
+    for x in start..=end {
+        canvas.text(x, y, dash);
+    }
+}
+
+fn head(head: Head, right: bool, unicode: bool) -> Option<&'static str> {
+    match (head, right, unicode) {
+        (Head::None, _, _) => None,
+        (Head::Arrow, true, true) => Some("▶"),
+        (Head::Arrow, false, true) => Some("◀"),
+        (Head::Open, true, true) => Some("▷"),
+        (Head::Open, false, true) => Some("◁"),
+        (Head::Cross, _, true) => Some("×"),
+        (Head::Arrow | Head::Open, true, false) => Some(">"),
+        (Head::Arrow | Head::Open, false, false) => Some("<"),
+        (Head::Cross, _, false) => Some("x"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(from: usize, to: usize, text: &str, dashed: bool) -> Message {
+        Message {
+            from,
+            to,
+            text: text.to_owned(),
+            dashed,
+            head: Head::Arrow,
+        }
+    }
+
+    #[test]
+    fn draws_messages_between_lifelines() {
+        let mut sequence = Sequence::default();
+        let (alice, bob) = (sequence.participant("Alice"), sequence.participant("Bob"));
+        sequence.messages = vec![
+            message(alice, bob, "Hello Bob", false),
+            message(bob, alice, "Hi", true),
+            message(bob, bob, "Think", false),
+        ];
+        let mut canvas = Canvas::new(true);
+        draw(&sequence, &mut canvas).unwrap();
+        assert_eq!(
+            vec![
+                "┌───────┐     ┌─────┐",
+                "│ Alice │     │ Bob │",
+                "└───┬───┘     └──┬──┘",
+                "    │ Hello Bob  │",
+                "    ├───────────▶│",
+                "    │     Hi     │",
+                "    │◀╌╌╌╌╌╌╌╌╌╌╌┤",
+                "    │            │ Think",
+                "    │            ├──┐",
+                "    │            │◀─┘",
+                "    │            │",
+                "┌───┴───┐     ┌──┴──┐",
+                "│ Alice │     │ Bob │",
+                "└───────┘     └─────┘",
+            ],
+            canvas.into_lines()
+        );
+    }
+
+    #[test]
+    fn no_participants() {
+        assert_eq!(None, draw(&Sequence::default(), &mut Canvas::new(true)));
+    }
+}
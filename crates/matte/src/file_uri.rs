@@ -1,5 +1,5 @@
 use std::ffi::OsString;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::{env, error, fmt, io};
 use url::Url;
 
@@ -24,6 +24,25 @@ pub fn file_in_current_dir(file_path: impl AsRef<Path>) -> Result<Url, Error> {
     Ok(url)
 }
 
+/// Converts a file URI to a path on this machine.
+/// Unlike [`Url::to_file_path`] this also accepts URIs that name
+/// the current host, as created by [`current_dir`] and [`file_in_current_dir`].
+pub fn local_path(url: &Url) -> Option<PathBuf> {
+    if url.scheme() != "file" {
+        return None;
+    }
+    let is_local = match url.host_str() {
+        None | Some("" | "localhost") => true,
+        Some(host) => hostname::get().is_ok_and(|h| h.to_str() == Some(host)),
+    };
+    if !is_local {
+        return None;
+    }
+    let mut without_host = Url::parse("file:///").expect("valid URL");
+    without_host.set_path(url.path());
+    without_host.to_file_path().ok()
+}
+
 // Explicitly setting the hostname allows
 // terminal emulators some freedome (e.g. when connected over SSH).
 // See: https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda
@@ -76,3 +95,33 @@ impl error::Error for Error {
         }
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_local_file_uris_to_paths() {
+        let url = Url::parse("file:///tmp/image.png").unwrap();
+        assert_eq!(Some(PathBuf::from("/tmp/image.png")), local_path(&url));
+        let url = Url::parse("file://localhost/tmp/image.png").unwrap();
+        assert_eq!(Some(PathBuf::from("/tmp/image.png")), local_path(&url));
+    }
+
+    #[test]
+    fn converts_uris_from_this_host_to_paths() {
+        let url = file_in_current_dir("image.png").unwrap();
+        let expected = env::current_dir().unwrap().join("image.png");
+        assert_eq!(Some(expected), local_path(&url));
+        let url = file_in_current_dir("/tmp/image.png").unwrap();
+        assert_eq!(Some(PathBuf::from("/tmp/image.png")), local_path(&url));
+    }
+
+    #[test]
+    fn rejects_other_uris() {
+        let url = Url::parse("https://example.com/image.png").unwrap();
+        assert_eq!(None, local_path(&url));
+        let url = Url::parse("file://some-other-host.invalid/image.png").unwrap();
+        assert_eq!(None, local_path(&url));
+    }
+}
@@ -0,0 +1,96 @@
+use super::{color, resize_to_width};
+use crate::context::Context;
+use crate::writer::WriteExt as _;
+use anstyle::{Reset, RgbColor};
+use image::{DynamicImage, RgbaImage};
+use std::io::{self, Write};
+
+/// Each braille pattern has two columns of four dots.
+const DOTS_PER_COLUMN: u32 = 2;
+const DOTS_PER_ROW: u32 = 4;
+
+/// The bit for each dot of a braille pattern, indexed by `[y][x]`.
+const DOT_BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+pub(super) fn write(
+    image: &DynamicImage,
+    available_width: u32,
+    ctx: &Context<'_, '_, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let image = resize_to_width(image, available_width * DOTS_PER_COLUMN, DOTS_PER_ROW);
+    let threshold = mean_luminance(&image);
+    for y in (0..image.height()).step_by(DOTS_PER_ROW as usize) {
+        w.write_prefix(ctx)?;
+        for x in (0..image.width()).step_by(DOTS_PER_COLUMN as usize) {
+            let cell = Cell::new(&image, x, y, threshold);
+            match cell.color() {
+                Some(color) => write!(w, "{}{}{Reset}", color.on_default(), cell.symbol())?,
+                None => write!(w, " ")?,
+            }
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Dots are set for pixels that are brighter than average.
+fn mean_luminance(image: &RgbaImage) -> u32 {
+    let luminances: Vec<_> = image
+        .pixels()
+        .filter_map(|p| color(*p))
+        .map(luminance)
+        .collect();
+    let count = u32::try_from(luminances.len()).unwrap_or(u32::MAX).max(1);
+    luminances.iter().sum::<u32>() / count
+}
+
+fn luminance(RgbColor(r, g, b): RgbColor) -> u32 {
+    (299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b)) / 1000
+}
+
+/// The dots of a single braille pattern.
+struct Cell {
+    bits: u32,
+    colors: Vec<RgbColor>,
+}
+
+impl Cell {
+    fn new(image: &RgbaImage, x: u32, y: u32, threshold: u32) -> Self {
+        let mut cell = Cell {
+            bits: 0,
+            colors: Vec::new(),
+        };
+        for (dy, row) in DOT_BITS.iter().enumerate() {
+            for (dx, bit) in row.iter().enumerate() {
+                let (x, y) = (x + dx as u32, y + dy as u32);
+                let Some(color) = image.get_pixel_checked(x, y).and_then(|p| color(*p)) else {
+                    continue;
+                };
+                if luminance(color) > threshold {
+                    cell.bits |= bit;
+                    cell.colors.push(color);
+                }
+            }
+        }
+        cell
+    }
+
+    fn symbol(&self) -> char {
+        char::from_u32(0x2800 + self.bits).expect("braille patterns are valid chars")
+    }
+
+    /// The average color of all dots that are set.
+    fn color(&self) -> Option<RgbColor> {
+        let count = u32::try_from(self.colors.len()).ok().filter(|c| *c > 0)?;
+        let average = |channel: fn(&RgbColor) -> u8| {
+            let sum: u32 = self.colors.iter().map(|c| u32::from(channel(c))).sum();
+            u8::try_from(sum / count).expect("average of u8 fits into u8")
+        };
+        Some(RgbColor(
+            average(|c| c.0),
+            average(|c| c.1),
+            average(|c| c.2),
+        ))
+    }
+}
@@ -0,0 +1,35 @@
+use super::{color, resize_to_width};
+use crate::context::Context;
+use crate::writer::WriteExt as _;
+use anstyle::{Reset, Style};
+use image::DynamicImage;
+use std::io::{self, Write};
+
+/// Each cell shows two pixels stacked on top of each other:
+/// The upper one as the foreground color of "▀", the lower one as the background color.
+const PIXELS_PER_ROW: u32 = 2;
+
+pub(super) fn write(
+    image: &DynamicImage,
+    available_width: u32,
+    ctx: &Context<'_, '_, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let image = resize_to_width(image, available_width, PIXELS_PER_ROW);
+    for y in (0..image.height()).step_by(PIXELS_PER_ROW as usize) {
+        w.write_prefix(ctx)?;
+        for x in 0..image.width() {
+            let upper = color(*image.get_pixel(x, y));
+            let lower = color(*image.get_pixel(x, y + 1));
+            let (symbol, style) = match (upper, lower) {
+                (None, None) => (" ", Style::new()),
+                (Some(upper), None) => ("▀", upper.on_default()),
+                (None, Some(lower)) => ("▄", lower.on_default()),
+                (Some(upper), Some(lower)) => ("▀", upper.on(lower)),
+            };
+            write!(w, "{style}{symbol}{Reset}")?;
+        }
+        writeln!(w)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use super::encode_png;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use image::DynamicImage;
+use std::io::{self, Write};
+
+/// Displays the image as PNG, scaled to `columns` × `rows` cells.
+/// See: <https://iterm2.com/documentation-images.html>
+pub(super) fn write(
+    image: &DynamicImage,
+    columns: u32,
+    rows: u32,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let png = encode_png(image)?;
+    write!(
+        w,
+        "\x1b]1337;File=inline=1;size={size};width={columns};height={rows};preserveAspectRatio=1:{data}\x07",
+        size = png.len(),
+        data = STANDARD.encode(&png),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_header_fields() {
+        let image = DynamicImage::new_rgb8(2, 2);
+        let png = encode_png(&image).unwrap();
+        let mut output = Vec::new();
+        write(&image, 3, 2, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let data = output
+            .strip_prefix(&format!(
+                "\x1b]1337;File=inline=1;size={};width=3;height=2;preserveAspectRatio=1:",
+                png.len()
+            ))
+            .and_then(|rest| rest.strip_suffix('\x07'))
+            .unwrap();
+        assert_eq!(png, STANDARD.decode(data).unwrap());
+    }
+}
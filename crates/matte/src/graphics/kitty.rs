@@ -0,0 +1,85 @@
+use super::encode_png;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use image::DynamicImage;
+use std::io::{self, Write};
+
+/// Kitty limits the size of the payload per escape sequence.
+const CHUNK_SIZE: usize = 4096;
+
+/// Transmits and displays the image as PNG, scaled to `columns` × `rows` cells.
+/// See: <https://sw.kovidgoyal.net/kitty/graphics-protocol/>
+pub(super) fn write(
+    image: &DynamicImage,
+    columns: u32,
+    rows: u32,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let data = STANDARD.encode(encode_png(image)?);
+    let chunks: Vec<_> = data.as_bytes().chunks(CHUNK_SIZE).collect();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(index + 1 < chunks.len());
+        if index == 0 {
+            // a=T: transmit and display, f=100: PNG, q=2: no responses, C=1: don't move the cursor.
+            write!(w, "\x1b_Ga=T,f=100,q=2,C=1,c={columns},r={rows},m={more};")?;
+        } else {
+            write!(w, "\x1b_Gm={more};")?;
+        }
+        w.write_all(chunk)?;
+        write!(w, "\x1b\\")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    /// Noise doesn't compress, so the payload spans several chunks.
+    fn noise(size: u32) -> DynamicImage {
+        let mut state = 1u32;
+        DynamicImage::ImageRgb8(RgbImage::from_fn(size, size, |_, _| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            image::Rgb(state.to_be_bytes()[..3].try_into().unwrap())
+        }))
+    }
+
+    fn chunks(output: &[u8]) -> Vec<&str> {
+        let output = std::str::from_utf8(output).unwrap();
+        output
+            .strip_suffix("\x1b\\")
+            .unwrap()
+            .split("\x1b\\")
+            .collect()
+    }
+
+    #[test]
+    fn splits_payload_into_chunks() {
+        let mut output = Vec::new();
+        write(&noise(64), 7, 4, &mut output).unwrap();
+        let chunks = chunks(&output);
+        assert!(chunks.len() > 2);
+
+        let (first, rest) = chunks.split_first().unwrap();
+        let (last, middle) = rest.split_last().unwrap();
+        let first = first
+            .strip_prefix("\x1b_Ga=T,f=100,q=2,C=1,c=7,r=4,m=1;")
+            .unwrap();
+        assert_eq!(CHUNK_SIZE, first.len());
+        for chunk in middle {
+            assert_eq!(CHUNK_SIZE, chunk.strip_prefix("\x1b_Gm=1;").unwrap().len());
+        }
+        let last = last.strip_prefix("\x1b_Gm=0;").unwrap();
+        assert!((1..=CHUNK_SIZE).contains(&last.len()));
+    }
+
+    #[test]
+    fn writes_small_images_in_one_chunk() {
+        let mut output = Vec::new();
+        write(&DynamicImage::new_rgb8(1, 1), 1, 1, &mut output).unwrap();
+        let chunks = chunks(&output);
+        assert_eq!(1, chunks.len());
+        assert!(chunks[0].starts_with("\x1b_Ga=T,f=100,q=2,C=1,c=1,r=1,m=0;"));
+    }
+}
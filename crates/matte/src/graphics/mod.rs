@@ -0,0 +1,160 @@
+//! Shows images using the graphics protocols of terminals such as Kitty,
+//! or as text art in terminals without support for graphics.
+
+use crate::context::Context;
+use crate::file_uri;
+use crate::inline::parse_url;
+use crate::options::ImageProtocol;
+use crate::writer::WriteExt as _;
+use anstyle::RgbColor;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use std::io::{self, Cursor, Write};
+use unicode_width::UnicodeWidthStr as _;
+
+mod braille;
+mod half_blocks;
+mod iterm2;
+mod kitty;
+mod sixel;
+
+/// The size of a terminal cell in pixels.
+/// There's no portable way to find out the actual size,
+/// so we assume a typical cell which is twice as high as it is wide.
+const CELL_WIDTH: u32 = 10;
+const CELL_HEIGHT: u32 = 20;
+
+/// A local image that is shown using a graphics protocol or as text art.
+pub(crate) struct Graphic {
+    image: DynamicImage,
+    protocol: ImageProtocol,
+}
+
+impl Graphic {
+    /// Loads the image at `dest_url` if images are enabled.
+    /// Returns `None` for images that should be represented by their alt text instead.
+    pub(crate) fn load(dest_url: &str, ctx: &Context<'_, '_, '_>) -> Option<Self> {
+        let protocol = ctx.options().image_protocol?;
+        let url = parse_url(dest_url, ctx)?;
+        let path = file_uri::local_path(&url)?;
+        let image = image::open(path).ok()?;
+        Some(Self { image, protocol })
+    }
+
+    pub(crate) fn write(&self, ctx: &Context<'_, '_, '_>, w: &mut impl Write) -> io::Result<()> {
+        let available_width = u32::try_from(ctx.available_width()).unwrap_or(u32::MAX);
+        match self.protocol {
+            ImageProtocol::Kitty => {
+                self.write_escape_sequence(kitty::write, available_width, ctx, w)
+            }
+            ImageProtocol::Iterm2 => {
+                self.write_escape_sequence(iterm2::write, available_width, ctx, w)
+            }
+            ImageProtocol::Sixel => {
+                self.write_escape_sequence(sixel::write, available_width, ctx, w)
+            }
+            ImageProtocol::HalfBlocks => half_blocks::write(&self.image, available_width, ctx, w),
+            ImageProtocol::Braille => braille::write(&self.image, available_width, ctx, w),
+        }
+    }
+
+    /// Text art does not convey what the image shows as well as
+    /// a proper image, so we show the alt text as a caption.
+    pub(crate) fn has_caption(&self) -> bool {
+        matches!(
+            self.protocol,
+            ImageProtocol::HalfBlocks | ImageProtocol::Braille
+        )
+    }
+
+    fn write_escape_sequence<W: Write>(
+        &self,
+        write_image: fn(&DynamicImage, u32, u32, &mut W) -> io::Result<()>,
+        available_width: u32,
+        ctx: &Context<'_, '_, '_>,
+        w: &mut W,
+    ) -> io::Result<()> {
+        let (columns, rows) = self.size(available_width);
+
+        // The image is drawn over empty lines that we write beforehand,
+        // so that lines covered by the image are prefixed like any other line.
+        for _ in 0..rows {
+            w.write_prefix(ctx)?;
+            writeln!(w)?;
+        }
+        write!(w, "\x1b[{rows}A")?;
+        let prefix_width = ctx.prefix_chain().width();
+        if prefix_width > 0 {
+            write!(w, "\x1b[{prefix_width}C")?;
+        }
+
+        // Terminals disagree on where to put the cursor after an image,
+        // so we save and restore it.
+        write!(w, "\x1b7")?;
+        write_image(&self.image, columns, rows, w)?;
+        write!(w, "\x1b8\x1b[{rows}B\r")
+    }
+
+    /// Size of the image in terminal cells. Images are scaled down to fit into
+    /// `available_width` columns, but never scaled up.
+    fn size(&self, available_width: u32) -> (u32, u32) {
+        let (width, height) = (self.image.width().max(1), self.image.height().max(1));
+        let columns = width.div_ceil(CELL_WIDTH).min(available_width).max(1);
+        let rows = (columns * CELL_WIDTH * height)
+            .div_ceil(width * CELL_HEIGHT)
+            .max(1);
+        (columns, rows)
+    }
+}
+
+fn encode_png(image: &DynamicImage) -> io::Result<Vec<u8>> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(io::Error::other)?;
+    Ok(png)
+}
+
+/// Scales the image down to `width` pixels, keeping its aspect ratio.
+/// `row_height` is the number of pixels per row of text, the height is rounded up to fill entire rows.
+fn resize_to_width(image: &DynamicImage, width: u32, row_height: u32) -> RgbaImage {
+    let width = width.min(image.width()).max(1);
+    let height = (width * image.height())
+        .div_ceil(image.width().max(1))
+        .max(1);
+    let height = height.div_ceil(row_height) * row_height;
+    image
+        .resize_exact(width, height, FilterType::Triangle)
+        .to_rgba8()
+}
+
+/// The color of an opaque pixel, `None` for transparent pixels.
+fn color(pixel: Rgba<u8>) -> Option<RgbColor> {
+    let [r, g, b, a] = pixel.0;
+    (a >= 128).then_some(RgbColor(r, g, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn size(width: u32, height: u32, available_width: u32) -> (u32, u32) {
+        let graphic = Graphic {
+            image: DynamicImage::new_rgb8(width, height),
+            protocol: ImageProtocol::Kitty,
+        };
+        graphic.size(available_width)
+    }
+
+    #[test]
+    fn scales_down_to_available_width() {
+        assert_eq!((40, 10), size(1000, 500, 40));
+        assert_eq!((1, 1), size(1000, 10, 0));
+    }
+
+    #[test]
+    fn does_not_scale_up() {
+        assert_eq!((10, 5), size(100, 100, 80));
+        assert_eq!((1, 1), size(1, 1, 80));
+    }
+}
@@ -0,0 +1,149 @@
+use super::{CELL_HEIGHT, CELL_WIDTH};
+use image::imageops::FilterType;
+use image::{DynamicImage, Rgba};
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+/// Each sixel encodes a column of six pixels.
+const SIXEL_HEIGHT: u32 = 6;
+
+/// Number of levels per channel of our color palette.
+/// Six levels per channel make for 216 colors, which is less than
+/// the 256 color registers that most terminals support.
+const LEVELS: u32 = 6;
+
+/// Draws the image using sixels, scaled to fit into `columns` × `rows` cells.
+/// See: <https://vt100.net/docs/vt3xx-gp/chapter14.html>
+pub(super) fn write(
+    image: &DynamicImage,
+    columns: u32,
+    rows: u32,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let image = image
+        .resize(
+            columns * CELL_WIDTH,
+            rows * CELL_HEIGHT,
+            FilterType::Triangle,
+        )
+        .to_rgba8();
+    let (width, height) = image.dimensions();
+    let pixels: Vec<Option<u32>> = image.pixels().map(palette_index).collect();
+    let pixel = |x: u32, y: u32| pixels[(y * width + x) as usize];
+
+    // P2=1: pixels that we don't draw stay transparent.
+    write!(w, "\x1bP0;1;0q\"1;1;{width};{height}")?;
+
+    let colors: BTreeSet<_> = pixels.iter().flatten().copied().collect();
+    for color in &colors {
+        let [r, g, b] = palette_color(*color);
+        write!(w, "#{color};2;{r};{g};{b}")?;
+    }
+
+    for band in (0..height).step_by(SIXEL_HEIGHT as usize) {
+        let band_height = SIXEL_HEIGHT.min(height - band);
+        let band_colors: BTreeSet<_> = (band..band + band_height)
+            .flat_map(|y| (0..width).filter_map(move |x| pixel(x, y)))
+            .collect();
+        for color in band_colors {
+            write!(w, "#{color}")?;
+            let mut run = Run::default();
+            for x in 0..width {
+                let bits = (0..band_height)
+                    .filter(|dy| pixel(x, band + dy) == Some(color))
+                    .fold(0, |bits, dy| bits | (1 << dy));
+                run.push(b'?' + bits, w)?;
+            }
+            run.flush(w)?;
+            // Return to the start of the band for the next color.
+            write!(w, "$")?;
+        }
+        write!(w, "-")?;
+    }
+
+    write!(w, "\x1b\\")
+}
+
+/// The palette entry closest to the pixel's color, `None` for transparent pixels.
+fn palette_index(pixel: &Rgba<u8>) -> Option<u32> {
+    let [r, g, b, a] = pixel.0;
+    let level = |c: u8| (u32::from(c) * (LEVELS - 1) + 127) / 255;
+    (a >= 128).then(|| (level(r) * LEVELS + level(g)) * LEVELS + level(b))
+}
+
+/// The color of a palette entry with channels in percent, as expected by sixel.
+fn palette_color(index: u32) -> [u32; 3] {
+    let percent = |level: u32| level * 100 / (LEVELS - 1);
+    [
+        percent(index / (LEVELS * LEVELS)),
+        percent(index / LEVELS % LEVELS),
+        percent(index % LEVELS),
+    ]
+}
+
+/// Run-length encoding of repeated sixels.
+#[derive(Default)]
+struct Run {
+    sixel: u8,
+    count: usize,
+}
+
+impl Run {
+    fn push(&mut self, sixel: u8, w: &mut impl Write) -> io::Result<()> {
+        if self.count > 0 && sixel != self.sixel {
+            self.flush(w)?;
+        }
+        self.sixel = sixel;
+        self.count += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self, w: &mut impl Write) -> io::Result<()> {
+        let sixel = char::from(self.sixel);
+        match self.count {
+            0 => {}
+            1..=3 => (0..self.count).try_for_each(|_| write!(w, "{sixel}"))?,
+            count => write!(w, "!{count}{sixel}")?,
+        }
+        self.count = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbaImage;
+
+    #[test]
+    fn maps_colors_to_palette() {
+        assert_eq!(Some(180), palette_index(&Rgba([255, 0, 0, 255])));
+        assert_eq!(Some(0), palette_index(&Rgba([0, 0, 0, 255])));
+        assert_eq!(None, palette_index(&Rgba([255, 0, 0, 0])));
+        assert_eq!([100, 0, 0], palette_color(180));
+        assert_eq!([0, 40, 100], palette_color(2 * LEVELS + 5));
+    }
+
+    #[test]
+    fn encodes_bands() {
+        // Scaled up to 10×10 pixels: A full band of six rows and a band of four rows.
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(1, 1, Rgba([255, 0, 0, 255])));
+        let mut output = Vec::new();
+        write(&image, 1, 1, &mut output).unwrap();
+        assert_eq!(
+            "\x1bP0;1;0q\"1;1;10;10#180;2;100;0;0#180!10~$-#180!10N$-\x1b\\",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn encodes_runs() {
+        let mut output = Vec::new();
+        let mut run = Run::default();
+        for sixel in [b'a', b'a', b'a', b'b', b'c', b'c', b'c', b'c'] {
+            run.push(sixel, &mut output).unwrap();
+        }
+        run.flush(&mut output).unwrap();
+        assert_eq!("aaab!4c", String::from_utf8(output).unwrap());
+    }
+}
@@ -1,6 +1,7 @@
 use crate::block::prelude::*;
 use crate::inline::into_inlines;
-use crate::ThemeProvider;
+use crate::prefix::Prefix;
+use crate::ThemeProvider as _;
 use pulldown_cmark::HeadingLevel;
 
 pub(crate) struct Heading {
@@ -20,9 +21,10 @@ impl Block for Heading {
     ) -> io::Result<()> {
         ctx.counters().update_section(self.level);
 
-        let style = ctx.theme().heading_style(self.level, ctx);
-        let prefix = ctx.theme().heading_prefix(self.level, ctx);
-        let ctx = ctx.block(prefix, style);
+        let theme_ctx = ctx.theme_context();
+        let style = ctx.theme().heading_style(self.level, &theme_ctx);
+        let prefix = ctx.theme().heading_prefix(self.level, &theme_ctx);
+        let ctx = ctx.block(Prefix::from(prefix), style);
 
         let writer = w.inline_writer(&ctx);
         writer.write_all(
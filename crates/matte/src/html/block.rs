@@ -0,0 +1,338 @@
+use super::inline::{scripted, token_inlines};
+use super::tokenizer::{StartTag, Token, Tokenizer};
+use crate::block::prelude::*;
+use crate::block::render_block_from_event;
+use crate::fmt_utils::Repeat;
+use crate::graphics::Graphic;
+use crate::inline::{write_lines, Inline};
+use crate::prefix::Prefix;
+use crate::Rule;
+use anstyle::Style;
+use pulldown_cmark::{Alignment, CowStr};
+use std::collections::VecDeque;
+
+/// A block of raw HTML.
+/// `<details>` can span multiple blocks: Everything up to the
+/// block that contains `</details>` is rendered as part of it.
+pub(crate) struct HtmlBlock {
+    pub(crate) html: String,
+}
+
+impl HtmlBlock {
+    pub(crate) fn collect<'e>(events: &mut impl Events<'e>) -> Self {
+        let html = terminated!(events, Event::End(TagEnd::HtmlBlock))
+            .filter_map(|event| match event {
+                Event::Html(html) | Event::Text(html) => Some(html),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .concat();
+        Self { html }
+    }
+}
+
+impl Block for HtmlBlock {
+    fn kind(&self) -> BlockKind {
+        BlockKind::Html
+    }
+
+    fn render<'e>(
+        self,
+        events: &mut impl Events<'e>,
+        ctx: &Context<'_, 'e, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        // The margin before this block is already written,
+        // so we start with a context that has no previous block.
+        let ctx = ctx.block(None, Style::new());
+        render_tokens(&mut tokenize(&self.html), None, events, &ctx, w)?;
+        Ok(())
+    }
+
+    /// Blocks that consist only of comments and tags without content
+    /// don't produce any output.
+    fn is_blank(&self, _ctx: &Context<'_, '_, '_>) -> bool {
+        Tokenizer::new(&self.html).all(|token| match token {
+            Token::Text(text) => text.trim().is_empty(),
+            Token::Start(tag) => !matches!(tag.name.as_str(), "img" | "hr" | "details"),
+            Token::End(_) | Token::Comment => true,
+        })
+    }
+}
+
+type Tokens = VecDeque<Token<'static>>;
+
+fn tokenize(html: &str) -> Tokens {
+    Tokenizer::new(html)
+        .map(|token| match token {
+            Token::Text(text) => Token::Text(text.into_owned().into()),
+            Token::Start(tag) => Token::Start(tag),
+            Token::End(name) => Token::End(name),
+            Token::Comment => Token::Comment,
+        })
+        .collect()
+}
+
+/// Renders tokens as blocks until the closing tag of the element named `closing` is found.
+/// Returns whether the closing tag was found.
+fn render_tokens<'e>(
+    tokens: &mut Tokens,
+    closing: Option<&str>,
+    events: &mut impl Events<'e>,
+    ctx: &Context<'_, 'e, '_>,
+    w: &mut impl Write,
+) -> io::Result<bool> {
+    let mut paragraph = HtmlParagraph::default();
+    let mut alignments: Vec<(String, Alignment)> = Vec::new();
+    let alignment =
+        |alignments: &[(String, Alignment)]| alignments.last().map_or(Alignment::None, |(_, a)| *a);
+
+    while let Some(token) = tokens.pop_front() {
+        match token {
+            Token::End(ref name) if Some(name.as_str()) == closing => {
+                paragraph.write(alignment(&alignments), ctx, w)?;
+                return Ok(true);
+            }
+            Token::Start(ref tag) if tag.name == "details" && !tag.self_closing => {
+                paragraph.write(alignment(&alignments), ctx, w)?;
+                details(tokens, events, ctx, w)?;
+            }
+            Token::Start(ref tag) if tag.name == "hr" => {
+                paragraph.write(alignment(&alignments), ctx, w)?;
+                w.write_block_start(ctx, BlockKind::Rule, &[])?;
+                Rule.render(events, ctx, w)?;
+                ctx.set_previous_block(BlockKind::Rule);
+            }
+            Token::Start(ref tag) if tag.name == "img" => match load_graphic(tag, ctx) {
+                Some(graphic) => {
+                    paragraph.write(alignment(&alignments), ctx, w)?;
+                    write_graphic(&graphic, tag.attribute("alt"), ctx, w)?;
+                }
+                None => paragraph.extend(token_inlines(token, ctx)),
+            },
+            Token::Start(ref tag) if is_block(&tag.name) => {
+                paragraph.write(alignment(&alignments), ctx, w)?;
+                if let Some(a) = tag_alignment(tag) {
+                    alignments.push((tag.name.clone(), a));
+                }
+                paragraph.extend(token_inlines(token, ctx));
+            }
+            Token::End(ref name) if is_block(name) => {
+                let name = name.clone();
+                paragraph.extend(token_inlines(token, ctx));
+                paragraph.write(alignment(&alignments), ctx, w)?;
+                if alignments.last().is_some_and(|(n, _)| *n == name) {
+                    alignments.pop();
+                }
+            }
+            Token::Text(text) => paragraph.push_text(&text, ctx),
+            token => paragraph.extend(token_inlines(token, ctx)),
+        }
+    }
+
+    paragraph.write(alignment(&alignments), ctx, w)?;
+    Ok(false)
+}
+
+/// Terminals can't collapse anything, so details are always shown expanded.
+fn details<'e>(
+    tokens: &mut Tokens,
+    events: &mut impl Events<'e>,
+    ctx: &Context<'_, 'e, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    w.write_block_start(ctx, BlockKind::Html, &[])?;
+    let inner = ctx.block(Prefix::continued(disclosure_marker(ctx)), Style::new());
+
+    if !render_tokens(tokens, Some("details"), events, &inner, w)? {
+        // The content continues after this HTML block, usually with markdown.
+        while let Some(event) = next_unless_end(events) {
+            if let Event::Start(Tag::HtmlBlock) = event {
+                tokens.extend(tokenize(&HtmlBlock::collect(events).html));
+                if render_tokens(tokens, Some("details"), events, &inner, w)? {
+                    break;
+                }
+            } else {
+                render_block_from_event(event, events, &inner, w)?;
+            }
+        }
+    }
+
+    ctx.set_previous_block(BlockKind::Html);
+    Ok(())
+}
+
+/// The next event, unless it ends the block that contains the details.
+fn next_unless_end<'e>(events: &mut impl Events<'e>) -> Option<Event<'e>> {
+    if matches!(events.lookahead().next(), Some(Event::End(_))) {
+        None
+    } else {
+        events.next()
+    }
+}
+
+fn disclosure_marker(ctx: &Context<'_, '_, '_>) -> &'static str {
+    if ctx.options().symbol_repertoire.is_unicode() {
+        "▾ "
+    } else {
+        "v "
+    }
+}
+
+fn is_block(name: &str) -> bool {
+    matches!(
+        name,
+        "p" | "div"
+            | "center"
+            | "section"
+            | "article"
+            | "header"
+            | "footer"
+            | "nav"
+            | "main"
+            | "aside"
+            | "figure"
+            | "figcaption"
+            | "blockquote"
+            | "summary"
+            | "h1"
+            | "h2"
+            | "h3"
+            | "h4"
+            | "h5"
+            | "h6"
+            | "ul"
+            | "ol"
+            | "li"
+            | "dl"
+            | "dt"
+            | "dd"
+            | "table"
+            | "tr"
+    )
+}
+
+fn tag_alignment(tag: &StartTag) -> Option<Alignment> {
+    if tag.name == "center" {
+        return Some(Alignment::Center);
+    }
+    match tag.attribute("align")?.to_ascii_lowercase().as_str() {
+        "left" => Some(Alignment::Left),
+        "center" => Some(Alignment::Center),
+        "right" => Some(Alignment::Right),
+        _ => None,
+    }
+}
+
+fn load_graphic(tag: &StartTag, ctx: &Context<'_, '_, '_>) -> Option<Graphic> {
+    Graphic::load(tag.attribute("src")?, ctx)
+}
+
+fn write_graphic(
+    graphic: &Graphic,
+    alt: Option<&str>,
+    ctx: &Context<'_, '_, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    w.write_block_start(ctx, BlockKind::Html, &[])?;
+    graphic.write(ctx, w)?;
+    if let Some(alt) = alt.filter(|alt| graphic.has_caption() && !alt.is_empty()) {
+        let ctx = ctx.block(None, Style::new().italic());
+        w.inline_writer(&ctx)
+            .write_all([Inline::Text(alt.to_owned().into())])?;
+    }
+    ctx.set_previous_block(BlockKind::Html);
+    Ok(())
+}
+
+/// The inline content between two block-level tags.
+#[derive(Debug, Default)]
+struct HtmlParagraph {
+    inlines: Vec<Inline<'static>>,
+}
+
+impl HtmlParagraph {
+    fn extend(&mut self, inlines: impl IntoIterator<Item = Inline<'static>>) {
+        self.inlines.extend(inlines);
+    }
+
+    /// Whitespace is collapsed like in a browser.
+    fn push_text(&mut self, text: &str, ctx: &Context<'_, '_, '_>) {
+        let mut collapsed = String::with_capacity(text.len());
+        let mut after_space = self.is_at_line_start();
+        for c in text.chars() {
+            if c.is_whitespace() && c != '\u{00A0}' {
+                if !after_space {
+                    collapsed.push(' ');
+                }
+                after_space = true;
+            } else {
+                collapsed.push(c);
+                after_space = false;
+            }
+        }
+        if !collapsed.is_empty() {
+            let text = scripted(CowStr::from(collapsed), ctx);
+            self.inlines.push(Inline::Text(text));
+        }
+    }
+
+    fn is_at_line_start(&self) -> bool {
+        self.inlines
+            .iter()
+            .rev()
+            .find_map(|inline| match inline {
+                Inline::Text(text) => Some(text.ends_with(' ')),
+                Inline::HardBreak | Inline::SoftBreak => Some(true),
+                _ => None,
+            })
+            .unwrap_or(true)
+    }
+
+    fn write(
+        &mut self,
+        alignment: Alignment,
+        ctx: &Context<'_, '_, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        let inlines = std::mem::take(&mut self.inlines);
+        // Elements can't continue in the next paragraph, so we start over.
+        ctx.open_elements().clear();
+        if !has_content(&inlines) {
+            return Ok(());
+        }
+        w.write_block_start(ctx, BlockKind::Html, &[])?;
+        write_aligned(inlines, alignment, ctx, w)?;
+        ctx.set_previous_block(BlockKind::Html);
+        Ok(())
+    }
+}
+
+fn has_content(inlines: &[Inline<'_>]) -> bool {
+    inlines
+        .iter()
+        .any(|inline| matches!(inline, Inline::Text(text) if !text.trim().is_empty()))
+}
+
+fn write_aligned(
+    inlines: Vec<Inline<'static>>,
+    alignment: Alignment,
+    ctx: &Context<'_, '_, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    if matches!(alignment, Alignment::None | Alignment::Left) {
+        return w.inline_writer(ctx).write_all(inlines);
+    }
+
+    let width = ctx.text_width();
+    for line in write_lines(ctx.style(), width, None, inlines) {
+        let fill = width.saturating_sub(line.width);
+        let padding = match alignment {
+            Alignment::Center => fill / 2,
+            _ => fill,
+        };
+        w.write_prefix(ctx)?;
+        writeln!(w, "{}{}", Repeat(padding, " "), line.text)?;
+    }
+    Ok(())
+}
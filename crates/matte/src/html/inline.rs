@@ -0,0 +1,195 @@
+use super::tokenizer::{StartTag, Token, Tokenizer};
+use crate::chars::NO_BREAK_SPACE;
+use crate::context::Context;
+use crate::inline::{image_end, image_start, parse_url, Inline, Inlines};
+use crate::math;
+use crate::themes::ThemeProvider as _;
+use anstyle::Style;
+use pulldown_cmark::CowStr;
+use smallvec::smallvec;
+use std::cell::RefCell;
+
+/// Inline elements that are currently open.
+/// This way closing tags only undo what their opening tags did
+/// and stray closing tags are ignored.
+#[derive(Debug, Default)]
+pub(crate) struct OpenElements(RefCell<Vec<OpenElement>>);
+
+impl OpenElements {
+    pub(crate) fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+
+    fn push(&self, name: &str, kind: ElementKind) {
+        self.0.borrow_mut().push(OpenElement {
+            name: name.to_owned(),
+            kind,
+        });
+    }
+
+    /// Closes the innermost element named `name` and all elements nested inside of it.
+    fn close(&self, name: &str) -> Vec<ElementKind> {
+        let mut elements = self.0.borrow_mut();
+        match elements.iter().rposition(|e| e.name == name) {
+            Some(index) => elements.drain(index..).rev().map(|e| e.kind).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn script(&self) -> Option<ElementKind> {
+        self.0
+            .borrow()
+            .iter()
+            .rev()
+            .map(|e| e.kind)
+            .find(|k| matches!(k, ElementKind::Superscript | ElementKind::Subscript))
+    }
+}
+
+#[derive(Debug)]
+struct OpenElement {
+    name: String,
+    kind: ElementKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ElementKind {
+    Style,
+    KeyCap,
+    Link,
+    Superscript,
+    Subscript,
+}
+
+/// Converts inline HTML, such as `<kbd>` or `<sup>`, to inlines.
+/// Unknown tags are dropped, their content is kept as-is.
+pub(crate) fn inline_html<'a>(html: &str, ctx: &Context<'_, '_, '_>) -> Inlines<'a> {
+    Tokenizer::new(html)
+        .flat_map(|token| token_inlines(token, ctx))
+        .collect()
+}
+
+pub(super) fn token_inlines<'a>(token: Token<'_>, ctx: &Context<'_, '_, '_>) -> Inlines<'a> {
+    match token {
+        Token::Start(tag) => start_tag(&tag, ctx),
+        Token::End(name) => end_tag(&name, ctx),
+        Token::Text(text) => {
+            smallvec![Inline::Text(scripted(CowStr::from(text.into_owned()), ctx))]
+        }
+        Token::Comment => Inlines::default(),
+    }
+}
+
+/// Converts text inside of `<sup>` and `<sub>` to superscript or subscript characters.
+/// Text that can't be converted completely is left as-is.
+pub(crate) fn scripted<'a>(text: CowStr<'a>, ctx: &Context<'_, '_, '_>) -> CowStr<'a> {
+    let converted = match ctx.open_elements().script() {
+        Some(ElementKind::Superscript) => math::to_superscript(&text),
+        Some(ElementKind::Subscript) => math::to_subscript(&text),
+        _ => None,
+    };
+    converted.map(CowStr::from).unwrap_or(text)
+}
+
+fn start_tag<'a>(tag: &StartTag, ctx: &Context<'_, '_, '_>) -> Inlines<'a> {
+    let open_elements = ctx.open_elements();
+    match tag.name.as_str() {
+        "br" => smallvec![Inline::HardBreak],
+        "img" => image(tag),
+        _ if tag.self_closing => Inlines::default(),
+        "a" => {
+            open_elements.push(&tag.name, ElementKind::Link);
+            link(tag, ctx)
+        }
+        "kbd" => {
+            open_elements.push(&tag.name, ElementKind::KeyCap);
+            smallvec![key_cap_style().into(), NO_BREAK_SPACE.into()]
+        }
+        "sup" => {
+            open_elements.push(&tag.name, ElementKind::Superscript);
+            Inlines::default()
+        }
+        "sub" => {
+            open_elements.push(&tag.name, ElementKind::Subscript);
+            Inlines::default()
+        }
+        "code" | "tt" | "samp" => {
+            open_elements.push(&tag.name, ElementKind::Style);
+            smallvec![ctx.theme().inline_code_style(&ctx.theme_context()).into()]
+        }
+        name => match style(name) {
+            Some(style) => {
+                open_elements.push(name, ElementKind::Style);
+                smallvec![style.into()]
+            }
+            None => Inlines::default(),
+        },
+    }
+}
+
+fn end_tag<'a>(name: &str, ctx: &Context<'_, '_, '_>) -> Inlines<'a> {
+    ctx.open_elements()
+        .close(name)
+        .into_iter()
+        .flat_map(|kind| -> Inlines<'a> {
+            match kind {
+                ElementKind::Style => smallvec![Inline::PopStyle],
+                ElementKind::KeyCap => smallvec![NO_BREAK_SPACE.into(), Inline::PopStyle],
+                ElementKind::Link => smallvec![Inline::UnsetLink, Inline::PopStyle],
+                ElementKind::Superscript | ElementKind::Subscript => Inlines::default(),
+            }
+        })
+        .collect()
+}
+
+fn style(name: &str) -> Option<Style> {
+    match name {
+        "b" | "strong" | "summary" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            Some(Style::new().bold())
+        }
+        "i" | "em" | "cite" | "var" | "dfn" => Some(Style::new().italic()),
+        "s" | "del" | "strike" => Some(Style::new().strikethrough()),
+        "u" | "ins" => Some(Style::new().underline()),
+        "mark" => Some(Style::new().invert()),
+        _ => None,
+    }
+}
+
+fn key_cap_style() -> Style {
+    Style::new().bold().invert()
+}
+
+fn link<'a>(tag: &StartTag, ctx: &Context<'_, '_, '_>) -> Inlines<'a> {
+    let url = tag
+        .attribute("href")
+        .filter(|_| ctx.options().hyperlinks)
+        .and_then(|href| parse_url(href, ctx));
+    let style = ctx.theme().link_style(&ctx.theme_context());
+    match url {
+        Some(url) => smallvec![style.into(), Inline::SetLink(url)],
+        None => smallvec![style.into()],
+    }
+}
+
+fn image<'a>(tag: &StartTag) -> Inlines<'a> {
+    let alt = tag.attribute("alt").unwrap_or_default().to_owned();
+    image_start()
+        .into_iter()
+        .chain([Inline::Text(alt.into())])
+        .chain(image_end())
+        .collect()
+}
+
+/// The source and alt text of inline HTML that consists of a single `<img>` tag.
+pub(crate) fn inline_image(html: &str) -> Option<(String, String)> {
+    let mut tokens = Tokenizer::new(html.trim());
+    let (Some(Token::Start(tag)), None) = (tokens.next(), tokens.next()) else {
+        return None;
+    };
+    if tag.name != "img" {
+        return None;
+    }
+    let src = tag.attribute("src")?.to_owned();
+    let alt = tag.attribute("alt").unwrap_or_default().to_owned();
+    Some((src, alt))
+}
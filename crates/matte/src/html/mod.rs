@@ -0,0 +1,10 @@
+//! Interprets the subset of HTML that is commonly found in markdown documents,
+//! e.g. `<details>`, `<kbd>` or `<p align="center">` in READMEs.
+//! Unknown tags are dropped, but their text content is kept.
+
+mod block;
+mod inline;
+mod tokenizer;
+
+pub(crate) use block::*;
+pub(crate) use inline::{inline_html, inline_image, scripted, OpenElements};
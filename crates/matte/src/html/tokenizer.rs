@@ -0,0 +1,298 @@
+use std::borrow::Cow;
+
+/// A token of an HTML fragment.
+/// This is far from a spec compliant tokenizer, but it handles
+/// the kind of HTML that people put into their markdown documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Token<'a> {
+    Start(StartTag),
+    End(String),
+    Text(Cow<'a, str>),
+    Comment,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct StartTag {
+    /// The lowercased name of the tag.
+    pub(super) name: String,
+    pub(super) attributes: Vec<(String, String)>,
+    pub(super) self_closing: bool,
+}
+
+impl StartTag {
+    pub(super) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+pub(super) struct Tokenizer<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub(super) fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn comment(&mut self) -> Token<'a> {
+        let rest = &self.rest()["<!--".len()..];
+        let length = rest.find("-->").map_or(rest.len(), |end| end + "-->".len());
+        self.position += "<!--".len() + length;
+        Token::Comment
+    }
+
+    fn tag(&mut self) -> Option<Token<'a>> {
+        let rest = self.rest();
+        let end = tag_end(rest)?;
+        let tag = &rest[1..end];
+        self.position += end + 1;
+        if let Some(name) = tag.strip_prefix('/') {
+            return Some(Token::End(name.trim().to_ascii_lowercase()));
+        }
+        // Doctypes, processing instructions and CDATA sections
+        // don't have any content that we'd want to show.
+        if tag.starts_with(['!', '?']) {
+            return Some(Token::Comment);
+        }
+        Some(Token::Start(start_tag(tag)))
+    }
+
+    fn text(&mut self) -> Token<'a> {
+        let rest = self.rest();
+        // A `<` that does not start a tag is part of the text.
+        let length = rest
+            .match_indices('<')
+            .map(|(index, _)| index)
+            .find(|index| *index > 0 && starts_tag(&rest[*index..]))
+            .unwrap_or(rest.len());
+        self.position += length;
+        Token::Text(decode_entities(&rest[..length]))
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest();
+        if rest.is_empty() {
+            None
+        } else if rest.starts_with("<!--") {
+            Some(self.comment())
+        } else if starts_tag(rest) {
+            Some(self.tag().unwrap_or_else(|| self.text()))
+        } else {
+            Some(self.text())
+        }
+    }
+}
+
+fn starts_tag(input: &str) -> bool {
+    let mut chars = input.chars();
+    chars.next() == Some('<')
+        && chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'))
+}
+
+/// Finds the closing `>` of a tag, skipping over quoted attribute values.
+fn tag_end(input: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in input.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn start_tag(tag: &str) -> StartTag {
+    let (tag, self_closing) = match tag.trim_end().strip_suffix('/') {
+        Some(tag) => (tag, true),
+        None => (tag, false),
+    };
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    StartTag {
+        name: tag[..name_end].to_ascii_lowercase(),
+        attributes: attributes(&tag[name_end..]),
+        self_closing,
+    }
+}
+
+fn attributes(mut input: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    loop {
+        input = input.trim_start();
+        if input.is_empty() {
+            break;
+        }
+        let name_end = input
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(input.len());
+        let name = input[..name_end].to_ascii_lowercase();
+        input = input[name_end..].trim_start();
+        let value = match input.strip_prefix('=') {
+            Some(rest) => {
+                let (value, rest) = attribute_value(rest.trim_start());
+                input = rest;
+                decode_entities(value).into_owned()
+            }
+            None => String::new(),
+        };
+        attributes.push((name, value));
+    }
+    attributes
+}
+
+fn attribute_value(input: &str) -> (&str, &str) {
+    match input.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            let value = &input[1..];
+            match value.find(quote) {
+                Some(end) => (&value[..end], &value[end + 1..]),
+                None => (value, ""),
+            }
+        }
+        _ => {
+            let end = input.find(char::is_whitespace).unwrap_or(input.len());
+            input.split_at(end)
+        }
+    }
+}
+
+/// Decodes character references. Only the most common named references are supported.
+pub(super) fn decode_entities(input: &str) -> Cow<'_, str> {
+    if !input.contains('&') {
+        return Cow::Borrowed(input);
+    }
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find('&') {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match rest
+            .find(';')
+            .and_then(|end| Some((entity(&rest[1..end])?, end)))
+        {
+            Some((c, end)) => {
+                output.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    Cow::Owned(output)
+}
+
+fn entity(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let code_point = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code_point);
+    }
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{00A0}',
+        "ensp" => '\u{2002}',
+        "emsp" => '\u{2003}',
+        "thinsp" => '\u{2009}',
+        "shy" => '\u{00AD}',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "middot" => '·',
+        "bull" => '•',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "deg" => '°',
+        "times" => '×',
+        "larr" => '←',
+        "rarr" => '→',
+        "uarr" => '↑',
+        "darr" => '↓',
+        _ => return None,
+    };
+    Some(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokenize(input: &str) -> Vec<Token<'_>> {
+        Tokenizer::new(input).collect()
+    }
+
+    #[test]
+    fn tokenizes_tags_and_text() {
+        assert_eq!(
+            vec![
+                Token::Start(StartTag {
+                    name: "kbd".to_owned(),
+                    attributes: vec![],
+                    self_closing: false
+                }),
+                Token::Text("Ctrl".into()),
+                Token::End("kbd".to_owned()),
+            ],
+            tokenize("<KBD>Ctrl</kbd >")
+        );
+    }
+
+    #[test]
+    fn parses_attributes() {
+        let tokens = tokenize(r#"<img src="a > b.png" alt='Logo' width=200 hidden/>"#);
+        let [Token::Start(tag)] = tokens.as_slice() else {
+            panic!("expected a single start tag, got {tokens:?}");
+        };
+        assert_eq!("img", tag.name);
+        assert!(tag.self_closing);
+        assert_eq!(Some("a > b.png"), tag.attribute("src"));
+        assert_eq!(Some("Logo"), tag.attribute("alt"));
+        assert_eq!(Some("200"), tag.attribute("width"));
+        assert_eq!(Some(""), tag.attribute("hidden"));
+    }
+
+    #[test]
+    fn skips_comments() {
+        assert_eq!(
+            vec![Token::Comment, Token::Text("text".into()), Token::Comment],
+            tokenize("<!-- a <b>comment</b> -->text<!DOCTYPE html>")
+        );
+    }
+
+    #[test]
+    fn keeps_stray_angle_brackets_as_text() {
+        assert_eq!(vec![Token::Text("1 < 2<3".into())], tokenize("1 < 2<3"));
+    }
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(
+            "a & b < c © d — e & f;",
+            decode_entities("a &amp; b &lt; c &#169; d &#x2014; e & f;")
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use crate::chars::NO_BREAK_SPACE;
 use crate::context::Context;
+use crate::html;
 use crate::inline::Inline;
-use anstyle::{AnsiColor, Style};
+use crate::math;
+use crate::themes::ThemeProvider as _;
+use anstyle::Style;
 use fmtastic::Superscript;
 use pulldown_cmark::{CowStr, Event, LinkType, Tag, TagEnd};
 use smallvec::{smallvec, SmallVec};
@@ -25,10 +28,9 @@ pub(crate) fn try_into_inlines<'a>(
     ctx: &Context<'_, '_, '_>,
 ) -> Result<Inlines<'a>, Event<'a>> {
     match event {
-        Event::Text(text) => Ok(inlines![text]),
-        Event::Code(c) => Ok(code(c, AnsiColor::Yellow)),
-        Event::InlineMath(math) => Ok(code(math, AnsiColor::Cyan)),
-        Event::DisplayMath(_) => Ok(display_math()),
+        Event::Text(text) => Ok(inlines![html::scripted(text, ctx)]),
+        Event::Code(c) => Ok(code(c, ctx)),
+        Event::InlineMath(tex) | Event::DisplayMath(tex) => Ok(math(tex)),
         Event::Start(Tag::Strong) => Ok(inlines![Style::new().bold()]),
         Event::End(TagEnd::Strong) => Ok(inlines![Inline::PopStyle]),
         Event::Start(Tag::Emphasis) => Ok(inlines![Style::new().italic()]),
@@ -43,11 +45,10 @@ pub(crate) fn try_into_inlines<'a>(
             title,
             id,
         }) => Ok(link(link_type, &dest_url, &title, &id, ctx)),
-        Event::End(TagEnd::Link) => Ok(inlines![Inline::UnsetLink]),
+        Event::End(TagEnd::Link) => Ok(inlines![Inline::UnsetLink, Inline::PopStyle]),
         Event::SoftBreak => Ok(inlines![Inline::SoftBreak]),
         Event::HardBreak => Ok(inlines![Inline::HardBreak]),
-        Event::InlineHtml(html) if is_br_tag(&html) => Ok(inlines![Inline::HardBreak]),
-        Event::InlineHtml(_html) => Ok(Inlines::default()),
+        Event::InlineHtml(html) => Ok(html::inline_html(&html, ctx)),
         Event::FootnoteReference(reference) => Ok(footnote_reference(&reference, ctx)),
         Event::TaskListMarker(_) => {
             unreachable!("TaskListMarker is handled by list rendering")
@@ -56,28 +57,24 @@ pub(crate) fn try_into_inlines<'a>(
     }
 }
 
-fn is_br_tag(html: &str) -> bool {
-    let html = html.replace(char::is_whitespace, "");
-    html == "<br>" || html == "<br/>"
+fn code<'a>(code: CowStr<'a>, ctx: &Context<'_, '_, '_>) -> Inlines<'a> {
+    let style = ctx.theme().inline_code_style(&ctx.theme_context());
+    inlines![style, code, Inline::PopStyle]
 }
 
-fn code(code: CowStr<'_>, color: AnsiColor) -> Inlines<'_> {
-    inlines![color.on_default().italic(), code, Inline::PopStyle]
+fn math(tex: CowStr<'_>) -> Inlines<'_> {
+    let text = match math::to_unicode(&tex) {
+        Some(unicode) => CowStr::from(unicode.replace('\n', " ")),
+        None => tex,
+    };
+    inlines![math::style(), text, Inline::PopStyle]
 }
 
-fn display_math<'a>() -> Inlines<'a> {
-    inlines![
-        AnsiColor::Red.on_default().invert(),
-        "[TODO: display math]",
-        Inline::PopStyle,
-    ]
-}
-
-fn image_start<'a>() -> Inlines<'a> {
+pub(crate) fn image_start<'a>() -> Inlines<'a> {
     inlines![Style::new().invert(), "🖼", NO_BREAK_SPACE]
 }
 
-fn image_end<'a>() -> Inlines<'a> {
+pub(crate) fn image_end<'a>() -> Inlines<'a> {
     inlines![Inline::PopStyle]
 }
 
@@ -88,16 +85,17 @@ fn link<'a>(
     _id: &str,
     ctx: &Context<'_, '_, '_>,
 ) -> Inlines<'a> {
+    let style = ctx.theme().link_style(&ctx.theme_context());
     if ctx.options().hyperlinks {
         if let Some(url) = parse_url(dest_url, ctx) {
-            return inlines![Inline::SetLink(url)];
+            return inlines![style, Inline::SetLink(url)];
         }
     }
 
-    Inlines::default()
+    inlines![style]
 }
 
-fn parse_url(url: &str, ctx: &Context<'_, '_, '_>) -> Option<Url> {
+pub(crate) fn parse_url(url: &str, ctx: &Context<'_, '_, '_>) -> Option<Url> {
     Url::parse(url).ok().or_else(|| {
         ctx.options()
             .base_url
@@ -108,9 +106,6 @@ fn parse_url(url: &str, ctx: &Context<'_, '_, '_>) -> Option<Url> {
 
 fn footnote_reference<'a>(reference: &str, ctx: &Context<'_, '_, '_>) -> Inlines<'a> {
     let text = format!("{}", Superscript(ctx.footnotes().get_number(reference)));
-    inlines![
-        AnsiColor::Green.on_default(),
-        CowStr::from(text),
-        Inline::PopStyle
-    ]
+    let style = ctx.theme().footnote_reference_style(&ctx.theme_context());
+    inlines![style, CowStr::from(text), Inline::PopStyle]
 }
@@ -8,7 +8,7 @@ mod hyperlink;
 mod writer;
 pub(crate) use writer::*;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) enum Inline<'a> {
     Text(CowStr<'a>),
     SoftBreak,
@@ -1,10 +1,13 @@
 use super::hyperlink::{CloseHyperlink, Hyperlink};
 use super::Inline;
 use crate::style::{StyleExt as _, StyleStack};
-use crate::textwrap::{Chunk, ChunkLayouter, RawChunk};
+use crate::textwrap::{clip, Chunk, ChunkLayouter, DisplayWidth, RawChunk};
 use anstyle::{Reset, Style};
+use pulldown_cmark::CowStr;
+use std::convert::Infallible;
 use std::io;
 use trait_set::trait_set;
+use unicode_width::UnicodeWidthStr as _;
 use url::Url;
 
 /// Writes an [`Inline`] and tracks the last used style.
@@ -40,13 +43,19 @@ where
     ) -> Self {
         Self {
             chunk_layouter: ChunkLayouter::new(max_width),
-            state: WriterState {
-                style_stack: StyleStack::new(default_style),
-                link: None,
-                link_id: 0,
-                writer,
-                write_prefix,
-            },
+            state: WriterState::new(default_style, writer, write_prefix),
+        }
+    }
+}
+
+impl<'w, F> WriterState<'w, F> {
+    fn new(default_style: Style, writer: &'w mut dyn io::Write, write_prefix: F) -> Self {
+        Self {
+            style_stack: StyleStack::new(default_style),
+            link: None,
+            link_id: 0,
+            writer,
+            write_prefix,
         }
     }
 }
@@ -82,6 +91,136 @@ where
     }
 }
 
+/// A line of written inlines, including escape sequences for styles and links.
+#[derive(Debug)]
+pub(crate) struct WrittenLine {
+    pub(crate) text: String,
+    /// The width of the text without trailing whitespace,
+    /// measured before the escape sequences were added.
+    pub(crate) width: usize,
+}
+
+/// Wraps `inlines` into lines that are at most `max_width` wide and writes each line on its own.
+/// Words that are too long to fit on a line are cut off and marked with `ellipsis`, if given.
+pub(crate) fn write_lines<'a>(
+    default_style: Style,
+    max_width: usize,
+    ellipsis: Option<&str>,
+    inlines: impl IntoIterator<Item = Inline<'a>>,
+) -> Vec<WrittenLine> {
+    let mut lines = layout_lines(max_width, inlines);
+    let widths: Vec<_> = lines
+        .iter_mut()
+        .map(|line| {
+            trim_end(line);
+            match ellipsis {
+                Some(ellipsis) => truncate(line, max_width, ellipsis),
+                None => line_width(line),
+            }
+        })
+        .collect();
+
+    let mut buffer = Vec::new();
+    let mut state = WriterState::new(default_style, &mut buffer, |_: &mut dyn io::Write| Ok(()));
+    lines
+        .into_iter()
+        .flatten()
+        .try_for_each(|chunk| write_chunk(chunk, &mut state))
+        .expect("writing to a Vec never fails");
+    String::from_utf8(buffer)
+        .expect("inline writer writes valid UTF-8")
+        .lines()
+        .zip(widths)
+        .map(|(text, width)| WrittenLine {
+            text: text.to_owned(),
+            width,
+        })
+        .collect()
+}
+
+type LaidOutLine = Vec<Chunk<'static, PassthroughInline>>;
+
+/// The chunks of each line, every line but the last one ends with [`Chunk::LineEnd`].
+fn layout_lines<'a>(
+    max_width: usize,
+    inlines: impl IntoIterator<Item = Inline<'a>>,
+) -> Vec<LaidOutLine> {
+    let mut lines = vec![LaidOutLine::new()];
+    let mut push = |chunk: Chunk<'_, PassthroughInline>| {
+        let line_end = matches!(chunk, Chunk::LineEnd);
+        lines
+            .last_mut()
+            .expect("there is always a line")
+            .push(chunk.into_static());
+        if line_end {
+            lines.push(LaidOutLine::new());
+        }
+        Ok::<_, Infallible>(())
+    };
+    let mut chunk_layouter = ChunkLayouter::new(max_width);
+    for inline in inlines {
+        let Ok(()) = chunk_layouter.chunk(RawChunk::from(inline), &mut push);
+    }
+    let Ok(()) = chunk_layouter.end(&mut push);
+    if lines.last().is_some_and(Vec::is_empty) {
+        lines.pop();
+    }
+    lines
+}
+
+fn trim_end(line: &mut LaidOutLine) {
+    for chunk in line.iter_mut().rev() {
+        if let Chunk::Text(text) = chunk {
+            let trimmed = text.trim_end();
+            if trimmed.len() < text.len() {
+                *text = DisplayWidth::from(CowStr::from(trimmed.to_owned()));
+            }
+            if !text.is_empty() {
+                return;
+            }
+        }
+    }
+}
+
+fn line_width(line: &LaidOutLine) -> usize {
+    line.iter()
+        .map(|chunk| match chunk {
+            Chunk::Text(text) => text.width(),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Cuts off the text of `line` at `max_width` columns and returns the new width.
+/// Styles and links after the cut are retained so that they are properly closed.
+fn truncate(line: &mut LaidOutLine, max_width: usize, ellipsis: &str) -> usize {
+    let width = line_width(line);
+    if width <= max_width {
+        return width;
+    }
+
+    // An ellipsis that is wider than `max_width` would still overflow it.
+    let ellipsis = clip(ellipsis, max_width);
+    let budget = max_width - ellipsis.width();
+    let mut used_width = 0;
+    let mut truncated = false;
+    line.retain_mut(|chunk| match chunk {
+        Chunk::Text(_) if truncated => false,
+        Chunk::Text(text) if used_width + text.width() <= budget => {
+            used_width += text.width();
+            true
+        }
+        Chunk::Text(text) => {
+            let text_before_cut = clip(text, budget - used_width);
+            *text = DisplayWidth::from(CowStr::from(format!("{text_before_cut}{ellipsis}")));
+            truncated = true;
+            true
+        }
+        _ => true,
+    });
+    line_width(line)
+}
+
 fn write_chunk<F>(
     chunk: Chunk<'_, PassthroughInline>,
     ctx: &mut WriterState<'_, F>,
@@ -133,7 +272,12 @@ fn passthrough_inline<F>(
             write!(w, "{}", stack.head())
         }
         PopStyle => {
+            let popped = stack.head();
             stack.pop();
+            // Styles that didn't change anything, such as the default link style, leave no trace.
+            if popped == stack.head() {
+                return Ok(());
+            }
             write!(w, "{Reset}{}", stack.head())
         }
         SetLink(url) => {
@@ -179,3 +323,52 @@ impl<'a> From<Inline<'a>> for RawChunk<'a, PassthroughInline> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link() -> Url {
+        Url::parse("https://example.com").unwrap()
+    }
+
+    #[test]
+    fn measures_lines_without_trailing_whitespace() {
+        let inlines = [
+            Inline::from("foo "),
+            Inline::from("bar  "),
+            Inline::HardBreak,
+        ];
+        let lines = write_lines(Style::new(), 5, None, inlines);
+        let lines: Vec<_> = lines.iter().map(|l| (l.text.as_str(), l.width)).collect();
+        assert_eq!(vec![("foo", 3), ("bar", 3)], lines);
+    }
+
+    #[test]
+    fn truncates_lines_and_closes_styles_and_links() {
+        let inlines = [
+            Inline::PushStyle(Style::new().bold()),
+            Inline::SetLink(link()),
+            Inline::from("bold_link"),
+            Inline::UnsetLink,
+            Inline::PopStyle,
+        ];
+        let lines = write_lines(Style::new(), 3, Some("…"), inlines);
+        assert_eq!(1, lines.len());
+        assert_eq!(3, lines[0].width);
+        assert_eq!(
+            format!(
+                "{bold}{}bo…{CloseHyperlink}{Reset}",
+                Hyperlink::new(&link(), 1),
+                bold = Style::new().bold()
+            ),
+            lines[0].text
+        );
+    }
+
+    #[test]
+    fn clips_ellipsis_to_max_width() {
+        let lines = write_lines(Style::new(), 2, Some("..."), [Inline::from("abcdef")]);
+        assert_eq!(("..", 2), (lines[0].text.as_str(), lines[0].width));
+    }
+}
@@ -8,17 +8,26 @@ pub use url;
 #[macro_use]
 mod macros;
 
+mod ansi;
 mod block;
 mod block_quote;
 mod chars;
+mod code_runner;
 mod context;
 mod counting;
+mod diagnostics;
+mod diagram;
 mod fmt_utils;
 mod footnotes;
+mod graphics;
 mod heading;
+mod html;
 mod inline;
 mod list;
 mod lookahead;
+mod math;
+mod metadata;
+mod numbering;
 mod options;
 mod prefix;
 mod render;
@@ -28,6 +37,12 @@ mod textwrap;
 mod themes;
 mod writer;
 
+pub use block::BlockKind;
+pub use code_runner::*;
+pub use counting::SectionCounter;
+pub use diagnostics::*;
+pub use metadata::*;
+pub use numbering::{HeadingNumbering, InvalidNumberingPattern, ListNumbering, NumberingPattern};
 pub use options::*;
 pub use render::*;
 pub mod file_uri;
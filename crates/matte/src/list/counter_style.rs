@@ -2,33 +2,69 @@ use crate::context::Context;
 use crate::prefix::Prefix;
 use crate::style::StyledStr;
 use anstyle::Style;
+use unicode_width::UnicodeWidthStr as _;
 use CounterStyle::*;
 
 #[derive(Debug)]
 pub(super) enum CounterStyle<'a> {
-    Numbered(u64),
+    /// Markers are right-aligned to the `width` of the widest marker in the list.
+    Numbered {
+        number: u64,
+        width: usize,
+    },
     Bulleted(&'a str),
 }
 
 impl<'a> CounterStyle<'a> {
-    pub(super) fn from_context(number: Option<u64>, ctx: &'a Context<'_, '_, '_>) -> Self {
-        match number {
-            Some(n) => Numbered(n),
+    pub(super) fn from_context(
+        first_item_number: Option<u64>,
+        item_count: u64,
+        ctx: &'a Context<'_, '_, '_>,
+    ) -> Self {
+        match first_item_number {
+            Some(first) => Numbered {
+                number: first,
+                width: (first..first.saturating_add(item_count))
+                    .map(|n| marker(n, ctx).width())
+                    .max()
+                    .unwrap_or_default(),
+            },
             None => Bulleted(ctx.bullet()),
         }
     }
 
     pub(super) fn next(&mut self) {
-        if let Numbered(n) = self {
-            *n += 1;
+        if let Numbered { number, .. } = self {
+            *number += 1;
         }
     }
 
-    pub(super) fn to_prefix(&self) -> Prefix {
+    pub(super) fn number(&self) -> Option<usize> {
+        match self {
+            Numbered { number, .. } => Some(to_usize(*number)),
+            Bulleted(_) => None,
+        }
+    }
+
+    pub(super) fn to_prefix(&self, ctx: &Context<'_, '_, '_>) -> Prefix {
         let value = match self {
-            Numbered(n) => format!("{n}. "),
+            Numbered { number, width } => {
+                let marker = marker(*number, ctx);
+                let padding = " ".repeat(width.saturating_sub(marker.width()));
+                format!("{padding}{marker} ")
+            }
             Bulleted(bullet) => format!("{bullet} "),
         };
         Prefix::continued(StyledStr::new(value, Style::new().bold()))
     }
 }
+
+fn marker(number: u64, ctx: &Context<'_, '_, '_>) -> String {
+    let mut numbers = ctx.list_item_numbers().to_vec();
+    numbers.push(to_usize(number));
+    ctx.options().list_numbering.format(&numbers)
+}
+
+fn to_usize(number: u64) -> usize {
+    usize::try_from(number).unwrap_or(usize::MAX)
+}
@@ -1,9 +1,9 @@
 use super::counter_style::CounterStyle;
 use super::task_list::TaskListMarker;
 use crate::block::prelude::*;
-use crate::block::{render_block_from_event, try_render_block_from_event};
-use crate::inline::{into_inlines, try_into_inlines};
+use crate::block::{is_block_start, render_block_from_event, try_render_block_from_event};
 use crate::prefix::Prefix;
+use crate::render::write_inlines;
 use anstyle::Style;
 
 pub(super) fn render_item<'e>(
@@ -12,10 +12,13 @@ pub(super) fn render_item<'e>(
     ctx: &Context<'_, 'e, '_>,
     w: &mut impl Write,
 ) -> io::Result<()> {
-    let ctx = ctx
-        .block(item_prefix(counter, events), Style::default())
+    let mut item_ctx = ctx
+        .block(item_prefix(counter, events, ctx), Style::default())
         .list_depth_incremented();
-    render_item_contents(events, &ctx, w)
+    if let Some(number) = counter.number() {
+        item_ctx = item_ctx.with_list_item_number(number);
+    }
+    render_item_contents(events, &item_ctx, w)
 }
 
 enum ListItemState<'e> {
@@ -40,10 +43,14 @@ fn render_item_contents<'e>(
     Ok(())
 }
 
-fn item_prefix<'e>(list_type: &CounterStyle<'_>, events: &mut impl Events<'e>) -> Prefix {
+fn item_prefix<'e>(
+    list_type: &CounterStyle<'_>,
+    events: &mut impl Events<'e>,
+    ctx: &Context<'_, 'e, '_>,
+) -> Prefix {
     match TaskListMarker::try_consume(events) {
         Some(marker) => marker.to_prefix(),
-        None => list_type.to_prefix(),
+        None => list_type.to_prefix(ctx),
     }
 }
 
@@ -53,26 +60,21 @@ fn list_item_inlines<'e>(
     ctx: &Context<'_, 'e, '_>,
     w: &mut impl Write,
 ) -> io::Result<ListItemState<'e>> {
-    let mut writer = w.inline_writer(ctx);
-
-    if let Some(event) = first_event {
-        writer.write_iter(into_inlines(event, ctx))?;
-    }
+    let mut inlines: Vec<_> = first_event.into_iter().collect();
+    let mut next_state = ListItemState::Complete;
 
     terminated_for! {
         for event in terminated!(events, Event::End(TagEnd::Item)) {
-            match try_into_inlines(event, ctx) {
-                Ok(inlines) => writer.write_iter(inlines)?,
-                Err(rejected_event) => {
-                    writer.end()?;
-                    return Ok(ListItemState::Blocks(rejected_event));
-                }
+            if is_block_start(&event) {
+                next_state = ListItemState::Blocks(event);
+                break;
             }
+            inlines.push(event);
         }
     }
 
-    writer.end()?;
-    Ok(ListItemState::Complete)
+    write_inlines(inlines, ctx, w)?;
+    Ok(next_state)
 }
 
 fn list_item_blocks<'e>(
@@ -1,8 +1,6 @@
 use crate::block::prelude::*;
 use counter_style::CounterStyle;
 
-mod bullets;
-pub(crate) use bullets::*;
 use item::render_item;
 mod counter_style;
 mod item;
@@ -17,13 +15,21 @@ impl Block for List {
         BlockKind::List
     }
 
+    fn has_nested_blocks(&self) -> bool {
+        true
+    }
+
     fn render<'e>(
         self,
         events: &mut impl Events<'e>,
         ctx: &Context<'_, 'e, '_>,
         w: &mut impl Write,
     ) -> io::Result<()> {
-        let mut counter = CounterStyle::from_context(self.first_item_number, ctx);
+        let item_count = match self.first_item_number {
+            Some(_) => count_items(events),
+            None => 0,
+        };
+        let mut counter = CounterStyle::from_context(self.first_item_number, item_count, ctx);
 
         terminated_for! {
             for event in terminated!(events, Event::End(TagEnd::List(..))) {
@@ -39,3 +45,19 @@ impl Block for List {
         Ok(())
     }
 }
+
+/// The number of items in the list, without the items of nested lists.
+fn count_items<'e>(events: &mut impl Events<'e>) -> u64 {
+    let mut depth = 0;
+    let mut count = 0;
+    for event in events.lookahead() {
+        match event {
+            Event::Start(Tag::List(_)) => depth += 1,
+            Event::End(TagEnd::List(_)) if depth == 0 => break,
+            Event::End(TagEnd::List(_)) => depth -= 1,
+            Event::Start(Tag::Item) if depth == 0 => count += 1,
+            _ => {}
+        }
+    }
+    count
+}
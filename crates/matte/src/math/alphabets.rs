@@ -0,0 +1,88 @@
+/// The math alphabets selected by commands such as `\mathbb`.
+#[derive(Debug, Copy, Clone)]
+pub(super) enum Alphabet {
+    Bold,
+    Italic,
+    Script,
+    Fraktur,
+    DoubleStruck,
+    SansSerif,
+    Monospace,
+}
+
+impl Alphabet {
+    pub(super) fn from_command(command: &str) -> Option<Self> {
+        Some(match command {
+            "mathbf" | "boldsymbol" => Alphabet::Bold,
+            "mathit" => Alphabet::Italic,
+            "mathcal" | "mathscr" => Alphabet::Script,
+            "mathfrak" => Alphabet::Fraktur,
+            "mathbb" => Alphabet::DoubleStruck,
+            "mathsf" => Alphabet::SansSerif,
+            "mathtt" => Alphabet::Monospace,
+            _ => return None,
+        })
+    }
+
+    /// Converts `c` to this alphabet. Characters without a counterpart are kept as they are.
+    pub(super) fn apply(self, c: char) -> char {
+        self.exception(c).or_else(|| self.offset(c)).unwrap_or(c)
+    }
+
+    fn offset(self, c: char) -> Option<char> {
+        let (upper, lower, digits) = self.starts();
+        let start = match c {
+            'A'..='Z' => upper + (c as u32 - 'A' as u32),
+            'a'..='z' => lower + (c as u32 - 'a' as u32),
+            '0'..='9' => digits? + (c as u32 - '0' as u32),
+            _ => return None,
+        };
+        char::from_u32(start)
+    }
+
+    /// Start of the capital letters, small letters and digits
+    /// in the Mathematical Alphanumeric Symbols block.
+    fn starts(self) -> (u32, u32, Option<u32>) {
+        match self {
+            Alphabet::Bold => (0x1D400, 0x1D41A, Some(0x1D7CE)),
+            Alphabet::Italic => (0x1D434, 0x1D44E, None),
+            Alphabet::Script => (0x1D49C, 0x1D4B6, None),
+            Alphabet::Fraktur => (0x1D504, 0x1D51E, None),
+            Alphabet::DoubleStruck => (0x1D538, 0x1D552, Some(0x1D7D8)),
+            Alphabet::SansSerif => (0x1D5A0, 0x1D5BA, Some(0x1D7E2)),
+            Alphabet::Monospace => (0x1D670, 0x1D68A, Some(0x1D7F6)),
+        }
+    }
+
+    /// Letters that were already part of the Letterlike Symbols block
+    /// and are left out of the Mathematical Alphanumeric Symbols block.
+    fn exception(self, c: char) -> Option<char> {
+        Some(match (self, c) {
+            (Alphabet::Italic, 'h') => 'ℎ',
+            (Alphabet::Script, 'B') => 'ℬ',
+            (Alphabet::Script, 'E') => 'ℰ',
+            (Alphabet::Script, 'F') => 'ℱ',
+            (Alphabet::Script, 'H') => 'ℋ',
+            (Alphabet::Script, 'I') => 'ℐ',
+            (Alphabet::Script, 'L') => 'ℒ',
+            (Alphabet::Script, 'M') => 'ℳ',
+            (Alphabet::Script, 'R') => 'ℛ',
+            (Alphabet::Script, 'e') => 'ℯ',
+            (Alphabet::Script, 'g') => 'ℊ',
+            (Alphabet::Script, 'o') => 'ℴ',
+            (Alphabet::Fraktur, 'C') => 'ℭ',
+            (Alphabet::Fraktur, 'H') => 'ℌ',
+            (Alphabet::Fraktur, 'I') => 'ℑ',
+            (Alphabet::Fraktur, 'R') => 'ℜ',
+            (Alphabet::Fraktur, 'Z') => 'ℨ',
+            (Alphabet::DoubleStruck, 'C') => 'ℂ',
+            (Alphabet::DoubleStruck, 'H') => 'ℍ',
+            (Alphabet::DoubleStruck, 'N') => 'ℕ',
+            (Alphabet::DoubleStruck, 'P') => 'ℙ',
+            (Alphabet::DoubleStruck, 'Q') => 'ℚ',
+            (Alphabet::DoubleStruck, 'R') => 'ℝ',
+            (Alphabet::DoubleStruck, 'Z') => 'ℤ',
+            _ => return None,
+        })
+    }
+}
@@ -0,0 +1,445 @@
+//! Converts TeX math to Unicode text, e.g. `\alpha^2 \le \sqrt{x}` to `α² ≤ √x`.
+//!
+//! This only covers the subset of TeX that has a reasonable representation
+//! in plain text. Everything else makes the conversion fail
+//! so that the caller can fall back to showing the TeX source.
+
+use self::alphabets::Alphabet;
+use anstyle::{AnsiColor, Style};
+
+mod alphabets;
+mod symbols;
+
+pub(crate) fn style() -> Style {
+    AnsiColor::Cyan.on_default().italic()
+}
+
+/// Converts `tex` to Unicode, returns `None` if `tex` uses unsupported features.
+/// Line breaks (`\\`) are retained as newlines.
+pub(crate) fn to_unicode(tex: &str) -> Option<String> {
+    let mut parser = Parser { rest: tex };
+    let output = parser.expression()?;
+    if !parser.rest.is_empty() {
+        // Unbalanced closing brace
+        return None;
+    }
+    let lines: Vec<_> = output.lines().map(collapse_spaces).collect();
+    Some(lines.join("\n").trim_matches('\n').to_owned())
+}
+
+/// Trims the line and collapses runs of spaces which
+/// are easily introduced by `\text{ ... }` and the like.
+fn collapse_spaces(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    for c in line.trim_matches(' ').chars() {
+        if !(c == ' ' && output.ends_with(' ')) {
+            output.push(c);
+        }
+    }
+    output
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Token<'a> {
+    Command(&'a str),
+    Open,
+    Close,
+    Superscript,
+    Subscript,
+    Space,
+    Char(char),
+}
+
+#[derive(Clone, Copy)]
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    /// Converts tokens up to the end of input or an unmatched closing brace.
+    fn expression(&mut self) -> Option<String> {
+        let mut output = String::new();
+        while let Some(token) = self.peek() {
+            if token == Token::Close {
+                break;
+            }
+            self.next();
+            self.atom(token, &mut output)?;
+        }
+        Some(output)
+    }
+
+    fn atom(&mut self, token: Token<'a>, output: &mut String) -> Option<()> {
+        match token {
+            Token::Space => {
+                if !output.is_empty() && !output.ends_with([' ', '\n']) {
+                    output.push(' ');
+                }
+            }
+            // Alignment points are meaningless without a grid.
+            Token::Char('&') => {}
+            Token::Char(c) => output.push(char(c)),
+            Token::Open => output.push_str(&self.group()?),
+            Token::Superscript => {
+                let script = self.argument()?;
+                trim_trailing_spaces(output);
+                output.push_str(&script_with(&script, symbols::superscript)?);
+            }
+            Token::Subscript => {
+                let script = self.argument()?;
+                trim_trailing_spaces(output);
+                output.push_str(&script_with(&script, symbols::subscript)?);
+            }
+            Token::Command(command) => output.push_str(&self.command(command)?),
+            Token::Close => return None,
+        }
+        Some(())
+    }
+
+    fn command(&mut self, command: &'a str) -> Option<String> {
+        if let Some(symbol) = symbols::symbol(command) {
+            return Some(symbol.to_owned());
+        }
+        if let Some(space) = symbols::space(command) {
+            return Some(space.to_owned());
+        }
+        if symbols::is_function(command) {
+            return Some(self.operator_name(command.to_owned()));
+        }
+        if symbols::is_ignored(command) {
+            return Some(String::new());
+        }
+        if let Some(alphabet) = Alphabet::from_command(command) {
+            return Some(
+                self.argument()?
+                    .chars()
+                    .map(|c| alphabet.apply(c))
+                    .collect(),
+            );
+        }
+        if let Some(accent) = symbols::accent(command) {
+            return accented(&self.argument()?, accent, command == "overline");
+        }
+
+        match command {
+            "\\" | "newline" => Some("\n".to_owned()),
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.argument()?;
+                let denominator = self.argument()?;
+                Some(fraction(&numerator, &denominator))
+            }
+            "sqrt" => {
+                let index = self.optional_argument()?;
+                let radicand = self.argument()?;
+                root(index.as_deref(), &radicand)
+            }
+            "text" | "textrm" | "textit" | "textbf" | "mbox" => self.text_argument(),
+            "mathrm" => self.argument(),
+            "operatorname" => {
+                let name = self.argument()?;
+                Some(self.operator_name(name))
+            }
+            "left" | "right" => {
+                // `\left.` is an invisible delimiter.
+                if self.peek() == Some(Token::Char('.')) {
+                    self.next();
+                }
+                Some(String::new())
+            }
+            "begin" | "end" => {
+                symbols::is_aligning_environment(&self.text_argument()?).then(String::new)
+            }
+            _ => None,
+        }
+    }
+
+    /// Operator names are followed by a thin space in TeX, e.g. `\cos\theta` is `cos θ`.
+    fn operator_name(&self, mut name: String) -> String {
+        let starts_identifier = match self.peek() {
+            Some(Token::Char(c)) => c.is_alphabetic(),
+            Some(Token::Command(command)) => symbols::is_greek(command),
+            _ => false,
+        };
+        if starts_identifier {
+            name.push(' ');
+        }
+        name
+    }
+
+    /// The argument of a command or script, either a group or a single token.
+    fn argument(&mut self) -> Option<String> {
+        self.skip_spaces();
+        match self.next()? {
+            Token::Open => self.group(),
+            Token::Char(c) => Some(char(c).to_string()),
+            Token::Command(command) => self.command(command),
+            _ => None,
+        }
+    }
+
+    /// An optional argument in square brackets, e.g. the `3` in `\sqrt[3]{x}`.
+    fn optional_argument(&mut self) -> Option<Option<String>> {
+        self.skip_spaces();
+        let Some(rest) = self.rest.strip_prefix('[') else {
+            return Some(None);
+        };
+        let end = rest.find(']')?;
+        self.rest = &rest[end + 1..];
+        to_unicode(&rest[..end]).map(Some)
+    }
+
+    /// A group whose contents are taken verbatim.
+    fn text_argument(&mut self) -> Option<String> {
+        self.skip_spaces();
+        let rest = self.rest.strip_prefix('{')?;
+        let mut depth = 0;
+        for (index, c) in rest.char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    self.rest = &rest[index + 1..];
+                    return Some(rest[..index].to_owned());
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// The rest of a group after its opening brace.
+    fn group(&mut self) -> Option<String> {
+        let output = self.expression()?;
+        (self.next()? == Token::Close).then_some(output)
+    }
+
+    fn skip_spaces(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        let mut parser = *self;
+        parser.next()
+    }
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        let c = self.rest.chars().next()?;
+        let after = &self.rest[c.len_utf8()..];
+
+        if c.is_whitespace() {
+            self.rest = after.trim_start();
+            return Some(Token::Space);
+        }
+
+        self.rest = after;
+        let token = match c {
+            '{' => Token::Open,
+            '}' => Token::Close,
+            '^' => Token::Superscript,
+            '_' => Token::Subscript,
+            '\\' => self.command_name(),
+            c => Token::Char(c),
+        };
+        Some(token)
+    }
+
+    fn command_name(&mut self) -> Token<'a> {
+        let len = self
+            .rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(self.rest.len());
+        if len > 0 {
+            let (name, rest) = self.rest.split_at(len);
+            self.rest = rest;
+            Token::Command(name)
+        } else {
+            // Control symbols such as `\,` or `\{` consist of a single character.
+            let len = self.rest.chars().next().map_or(0, char::len_utf8);
+            let (name, rest) = self.rest.split_at(len);
+            self.rest = rest;
+            Token::Command(name)
+        }
+    }
+}
+
+/// Maps characters that TeX typesets differently in math mode.
+fn char(c: char) -> char {
+    match c {
+        '-' => '−',
+        '\'' => '′',
+        '~' => ' ',
+        c => c,
+    }
+}
+
+fn trim_trailing_spaces(output: &mut String) {
+    output.truncate(output.trim_end_matches(' ').len());
+}
+
+/// Converts `text` to superscript characters, e.g. for HTML's `<sup>`.
+/// Returns `None` if a character has no superscript form.
+pub(crate) fn to_superscript(text: &str) -> Option<String> {
+    text.chars()
+        .map(keep_whitespace(symbols::superscript))
+        .collect()
+}
+
+/// Converts `text` to subscript characters, e.g. for HTML's `<sub>`.
+/// Returns `None` if a character has no subscript form.
+pub(crate) fn to_subscript(text: &str) -> Option<String> {
+    text.chars()
+        .map(keep_whitespace(symbols::subscript))
+        .collect()
+}
+
+fn keep_whitespace(map: fn(char) -> Option<String>) -> impl Fn(char) -> Option<String> {
+    move |c| {
+        if c.is_whitespace() {
+            Some(c.to_string())
+        } else {
+            map(c)
+        }
+    }
+}
+
+fn script_with(script: &str, map: fn(char) -> Option<String>) -> Option<String> {
+    script.chars().filter(|c| *c != ' ').map(map).collect()
+}
+
+fn fraction(numerator: &str, denominator: &str) -> String {
+    if let Some(fraction) = symbols::vulgar_fraction(numerator, denominator) {
+        return fraction.to_owned();
+    }
+
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if is_number(numerator) && is_number(denominator) {
+        if let (Some(numerator), Some(denominator)) = (
+            script_with(numerator, symbols::superscript),
+            script_with(denominator, symbols::subscript),
+        ) {
+            return format!("{numerator}⁄{denominator}");
+        }
+    }
+
+    format!(
+        "{}/{}",
+        parenthesized(numerator),
+        parenthesized(denominator)
+    )
+}
+
+fn root(index: Option<&str>, radicand: &str) -> Option<String> {
+    let radical = match index {
+        None | Some("2") => "√".to_owned(),
+        Some("3") => "∛".to_owned(),
+        Some("4") => "∜".to_owned(),
+        Some(index) => format!("{}√", script_with(index, symbols::superscript)?),
+    };
+    Some(format!("{radical}{}", parenthesized(radicand)))
+}
+
+/// Wraps `s` in parentheses unless it is a single symbol, number or word.
+fn parenthesized(s: &str) -> String {
+    if s.chars().count() <= 1 || s.chars().all(char::is_alphanumeric) {
+        s.to_owned()
+    } else {
+        format!("({s})")
+    }
+}
+
+fn accented(s: &str, accent: char, every_char: bool) -> Option<String> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(format!("{c}{accent}")),
+        (Some(_), Some(_)) if every_char => Some(s.chars().flat_map(|c| [c, accent]).collect()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn greek_letters_and_operators() {
+        assert_eq!(
+            Some("α ⋅ β ≤ Γ → ∞"),
+            to_unicode(r"\alpha \cdot \beta \le \Gamma \to \infty").as_deref()
+        );
+    }
+
+    #[test]
+    fn space_after_operator_names() {
+        assert_eq!(
+            Some("cos θ + sin x"),
+            to_unicode(r"\cos\theta + \sin x").as_deref()
+        );
+        assert_eq!(Some("sin θ"), to_unicode(r"\sin \theta").as_deref());
+        assert_eq!(Some("log(x)"), to_unicode(r"\log(x)").as_deref());
+        assert_eq!(Some("sgn x"), to_unicode(r"\operatorname{sgn}x").as_deref());
+    }
+
+    #[test]
+    fn scripts() {
+        assert_eq!(Some("x²ⁿ⁺¹"), to_unicode("x^{2n+1}").as_deref());
+        assert_eq!(Some("∑ᵢ₌₁ⁿ aᵢ"), to_unicode(r"\sum_{i=1}^n a_i").as_deref());
+        assert_eq!(Some("f′(x)"), to_unicode("f'(x)").as_deref());
+        assert_eq!(Some("90°"), to_unicode(r"90^\circ").as_deref());
+    }
+
+    #[test]
+    fn fractions() {
+        assert_eq!(Some("½"), to_unicode(r"\frac12").as_deref());
+        assert_eq!(Some("¹⁷⁄₃₂"), to_unicode(r"\frac{17}{32}").as_deref());
+        assert_eq!(
+            Some("(a + b)/2c"),
+            to_unicode(r"\frac{a + b}{2c}").as_deref()
+        );
+    }
+
+    #[test]
+    fn roots() {
+        assert_eq!(Some("√(x² + 1)"), to_unicode(r"\sqrt{x^2 + 1}").as_deref());
+        assert_eq!(Some("∛x"), to_unicode(r"\sqrt[3]{x}").as_deref());
+        assert_eq!(Some("⁵√2"), to_unicode(r"\sqrt[5]2").as_deref());
+    }
+
+    #[test]
+    fn alphabets() {
+        assert_eq!(
+            Some("x ∈ ℝ, 𝒜 ⊆ ℕ, 𝐯"),
+            to_unicode(r"x \in \mathbb{R}, \mathcal A \subseteq \mathbb N, \mathbf{v}").as_deref()
+        );
+    }
+
+    #[test]
+    fn text_and_functions() {
+        assert_eq!(
+            Some("sin x if x > 0"),
+            to_unicode(r"\sin x \text{ if } x > 0").as_deref()
+        );
+    }
+
+    #[test]
+    fn accents() {
+        assert_eq!(Some("x\u{0302}"), to_unicode(r"\hat{x}").as_deref());
+        assert_eq!(None, to_unicode(r"\hat{xy}"));
+    }
+
+    #[test]
+    fn aligned_lines() {
+        assert_eq!(
+            Some("a = b\nc = d"),
+            to_unicode(r"\begin{aligned} a &= b \\ c &= d \end{aligned}").as_deref()
+        );
+    }
+
+    #[test]
+    fn unsupported_input() {
+        assert_eq!(None, to_unicode(r"\unknowncommand"));
+        assert_eq!(None, to_unicode(r"e^{\pi}"));
+        assert_eq!(None, to_unicode(r"\frac{a}"));
+        assert_eq!(None, to_unicode(r"a}"));
+        assert_eq!(None, to_unicode(r"\begin{pmatrix} a \end{pmatrix}"));
+    }
+}
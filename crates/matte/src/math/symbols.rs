@@ -0,0 +1,442 @@
+use fmtastic::{Subscript, Superscript};
+
+/// The symbol for a command that stands on its own, e.g. `\alpha` or `\to`.
+pub(super) fn symbol(command: &str) -> Option<&'static str> {
+    greek(command)
+        .or_else(|| operator(command))
+        .or_else(|| relation(command))
+        .or_else(|| arrow(command))
+        .or_else(|| delimiter(command))
+        .or_else(|| miscellaneous(command))
+}
+
+pub(super) fn is_greek(command: &str) -> bool {
+    greek(command).is_some()
+}
+
+fn greek(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "omicron" => "ο",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        _ => return None,
+    })
+}
+
+fn operator(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "cdot" => "⋅",
+        "times" => "×",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "ast" => "∗",
+        "star" => "⋆",
+        "circ" => "∘",
+        "bullet" => "∙",
+        "cap" => "∩",
+        "cup" => "∪",
+        "setminus" => "∖",
+        "wedge" | "land" => "∧",
+        "vee" | "lor" => "∨",
+        "neg" | "lnot" => "¬",
+        "oplus" => "⊕",
+        "ominus" => "⊖",
+        "otimes" => "⊗",
+        "odot" => "⊙",
+        "sum" => "∑",
+        "prod" => "∏",
+        "coprod" => "∐",
+        "int" => "∫",
+        "iint" => "∬",
+        "iiint" => "∭",
+        "oint" => "∮",
+        "bigcup" => "⋃",
+        "bigcap" => "⋂",
+        "bigoplus" => "⨁",
+        "bigotimes" => "⨂",
+        _ => return None,
+    })
+}
+
+fn relation(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "le" | "leq" => "≤",
+        "ge" | "geq" => "≥",
+        "ne" | "neq" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "cong" => "≅",
+        "propto" => "∝",
+        "ll" => "≪",
+        "gg" => "≫",
+        "prec" => "≺",
+        "succ" => "≻",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "mid" => "∣",
+        "parallel" => "∥",
+        "perp" => "⊥",
+        "models" => "⊨",
+        "vdash" => "⊢",
+        "coloneqq" => "≔",
+        _ => return None,
+    })
+}
+
+fn arrow(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "to" | "rightarrow" => "→",
+        "gets" | "leftarrow" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" => "⇔",
+        "iff" | "Longleftrightarrow" => "⟺",
+        "implies" | "Longrightarrow" => "⟹",
+        "impliedby" | "Longleftarrow" => "⟸",
+        "longrightarrow" => "⟶",
+        "longleftarrow" => "⟵",
+        "mapsto" => "↦",
+        "longmapsto" => "⟼",
+        "hookrightarrow" => "↪",
+        "hookleftarrow" => "↩",
+        "uparrow" => "↑",
+        "downarrow" => "↓",
+        "updownarrow" => "↕",
+        "Uparrow" => "⇑",
+        "Downarrow" => "⇓",
+        "nearrow" => "↗",
+        "searrow" => "↘",
+        "rightleftharpoons" => "⇌",
+        _ => return None,
+    })
+}
+
+fn delimiter(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "{" | "lbrace" => "{",
+        "}" | "rbrace" => "}",
+        "|" | "Vert" => "‖",
+        "vert" => "|",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        _ => return None,
+    })
+}
+
+fn miscellaneous(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "forall" => "∀",
+        "exists" => "∃",
+        "nexists" => "∄",
+        "emptyset" | "varnothing" => "∅",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "prime" => "′",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        "aleph" => "ℵ",
+        "Re" => "ℜ",
+        "Im" => "ℑ",
+        "wp" => "℘",
+        "angle" => "∠",
+        "degree" => "°",
+        "top" => "⊤",
+        "bot" => "⊥",
+        "therefore" => "∴",
+        "because" => "∵",
+        "checkmark" => "✓",
+        "square" => "□",
+        "triangle" => "△",
+        "%" => "%",
+        "$" => "$",
+        "#" => "#",
+        "&" => "&",
+        "_" => "_",
+        _ => return None,
+    })
+}
+
+/// Commands that are typeset as their name in an upright font, e.g. `\sin`.
+pub(super) fn is_function(command: &str) -> bool {
+    matches!(
+        command,
+        "sin"
+            | "cos"
+            | "tan"
+            | "cot"
+            | "sec"
+            | "csc"
+            | "arcsin"
+            | "arccos"
+            | "arctan"
+            | "sinh"
+            | "cosh"
+            | "tanh"
+            | "log"
+            | "ln"
+            | "lg"
+            | "exp"
+            | "lim"
+            | "liminf"
+            | "limsup"
+            | "max"
+            | "min"
+            | "sup"
+            | "inf"
+            | "arg"
+            | "det"
+            | "dim"
+            | "ker"
+            | "deg"
+            | "gcd"
+            | "hom"
+            | "Pr"
+            | "mod"
+            | "bmod"
+    )
+}
+
+/// Horizontal space inserted by spacing commands such as `\,` or `\quad`.
+pub(super) fn space(command: &str) -> Option<&'static str> {
+    Some(match command {
+        "!" => "",
+        "," | ":" | ">" | ";" | " " | "enspace" => " ",
+        "quad" => "\u{2003}",
+        "qquad" => "\u{2003}\u{2003}",
+        _ => return None,
+    })
+}
+
+/// Commands that only affect the size or spacing in proper TeX output,
+/// so they are safe to ignore.
+pub(super) fn is_ignored(command: &str) -> bool {
+    matches!(
+        command,
+        "displaystyle"
+            | "textstyle"
+            | "scriptstyle"
+            | "limits"
+            | "nolimits"
+            | "big"
+            | "Big"
+            | "bigg"
+            | "Bigg"
+            | "bigl"
+            | "bigr"
+            | "Bigl"
+            | "Bigr"
+            | "biggl"
+            | "biggr"
+            | "Biggl"
+            | "Biggr"
+    )
+}
+
+/// A combining character that places an accent over the preceding character.
+pub(super) fn accent(command: &str) -> Option<char> {
+    Some(match command {
+        "hat" | "widehat" => '\u{0302}',
+        "tilde" | "widetilde" => '\u{0303}',
+        "bar" => '\u{0304}',
+        "overline" => '\u{0305}',
+        "dot" => '\u{0307}',
+        "ddot" => '\u{0308}',
+        "vec" => '\u{20D7}',
+        "not" => '\u{0338}',
+        _ => return None,
+    })
+}
+
+/// Environments that only align their lines, which we can render by dropping the alignment.
+pub(super) fn is_aligning_environment(name: &str) -> bool {
+    matches!(
+        name.trim_end_matches('*'),
+        "aligned" | "align" | "gathered" | "gather" | "split" | "equation" | "multline"
+    )
+}
+
+pub(super) fn superscript(c: char) -> Option<String> {
+    if let Some(digit) = c.to_digit(10) {
+        return Some(Superscript(digit).to_string());
+    }
+    let superscript = match c {
+        '+' => '⁺',
+        '-' | '−' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'a' => 'ᵃ',
+        'b' => 'ᵇ',
+        'c' => 'ᶜ',
+        'd' => 'ᵈ',
+        'e' => 'ᵉ',
+        'f' => 'ᶠ',
+        'g' => 'ᵍ',
+        'h' => 'ʰ',
+        'i' => 'ⁱ',
+        'j' => 'ʲ',
+        'k' => 'ᵏ',
+        'l' => 'ˡ',
+        'm' => 'ᵐ',
+        'n' => 'ⁿ',
+        'o' => 'ᵒ',
+        'p' => 'ᵖ',
+        'r' => 'ʳ',
+        's' => 'ˢ',
+        't' => 'ᵗ',
+        'u' => 'ᵘ',
+        'v' => 'ᵛ',
+        'w' => 'ʷ',
+        'x' => 'ˣ',
+        'y' => 'ʸ',
+        'z' => 'ᶻ',
+        'A' => 'ᴬ',
+        'B' => 'ᴮ',
+        'D' => 'ᴰ',
+        'E' => 'ᴱ',
+        'G' => 'ᴳ',
+        'H' => 'ᴴ',
+        'I' => 'ᴵ',
+        'J' => 'ᴶ',
+        'K' => 'ᴷ',
+        'L' => 'ᴸ',
+        'M' => 'ᴹ',
+        'N' => 'ᴺ',
+        'O' => 'ᴼ',
+        'P' => 'ᴾ',
+        'R' => 'ᴿ',
+        'T' => 'ᵀ',
+        'U' => 'ᵁ',
+        'V' => 'ⱽ',
+        'W' => 'ᵂ',
+        'β' => 'ᵝ',
+        'γ' => 'ᵞ',
+        'δ' => 'ᵟ',
+        'θ' => 'ᶿ',
+        'ι' => 'ᶥ',
+        'ϕ' | 'φ' => 'ᵠ',
+        'χ' => 'ᵡ',
+        '′' => '′',
+        '∘' => '°',
+        _ => return None,
+    };
+    Some(superscript.to_string())
+}
+
+pub(super) fn subscript(c: char) -> Option<String> {
+    if let Some(digit) = c.to_digit(10) {
+        return Some(Subscript(digit).to_string());
+    }
+    let subscript = match c {
+        '+' => '₊',
+        '-' | '−' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'h' => 'ₕ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        'p' => 'ₚ',
+        'r' => 'ᵣ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'u' => 'ᵤ',
+        'v' => 'ᵥ',
+        'x' => 'ₓ',
+        'β' => 'ᵦ',
+        'γ' => 'ᵧ',
+        'ρ' => 'ᵨ',
+        'ϕ' | 'φ' => 'ᵩ',
+        'χ' => 'ᵪ',
+        _ => return None,
+    };
+    Some(subscript.to_string())
+}
+
+/// A single character for common fractions such as `½`.
+pub(super) fn vulgar_fraction(numerator: &str, denominator: &str) -> Option<&'static str> {
+    Some(match (numerator, denominator) {
+        ("1", "2") => "½",
+        ("1", "3") => "⅓",
+        ("2", "3") => "⅔",
+        ("1", "4") => "¼",
+        ("3", "4") => "¾",
+        ("1", "5") => "⅕",
+        ("2", "5") => "⅖",
+        ("3", "5") => "⅗",
+        ("4", "5") => "⅘",
+        ("1", "6") => "⅙",
+        ("5", "6") => "⅚",
+        ("1", "7") => "⅐",
+        ("1", "8") => "⅛",
+        ("3", "8") => "⅜",
+        ("5", "8") => "⅝",
+        ("7", "8") => "⅞",
+        ("1", "9") => "⅑",
+        ("1", "10") => "⅒",
+        _ => return None,
+    })
+}
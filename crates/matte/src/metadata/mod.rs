@@ -0,0 +1,163 @@
+//! Front matter, i.e. YAML (`---`) or TOML (`+++`) metadata at the start of a document.
+
+use crate::supported_parser_options;
+use pulldown_cmark::{Event, MetadataBlockKind, Parser, Tag, TagEnd};
+use std::fmt;
+
+mod toml;
+mod yaml;
+
+/// The top-level entries of a metadata block, in the order they appear in the source.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    entries: Vec<(String, MetadataValue)>,
+}
+
+/// A value in a metadata block.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum MetadataValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    List(Vec<MetadataValue>),
+    Map(Vec<(String, MetadataValue)>),
+}
+
+impl Metadata {
+    /// Parses the contents of a metadata block.
+    /// Returns `None` if the block is not valid YAML or TOML
+    /// or if it isn't a map at the top level.
+    pub fn parse(kind: MetadataBlockKind, source: &str) -> Option<Self> {
+        let entries = match kind {
+            MetadataBlockKind::YamlStyle => yaml::parse(source)?,
+            MetadataBlockKind::PlusesStyle => toml::parse(source)?,
+        };
+        Some(Self { entries })
+    }
+
+    /// Finds and parses the metadata block at the start of a markdown document.
+    pub fn from_markdown(markdown: &str) -> Option<Self> {
+        let mut events = Parser::new_ext(markdown, supported_parser_options());
+        let Some(Event::Start(Tag::MetadataBlock(kind))) = events.next() else {
+            return None;
+        };
+        let source: String = events
+            .take_while(|e| !matches!(e, Event::End(TagEnd::MetadataBlock(_))))
+            .filter_map(|e| match e {
+                Event::Text(text) => Some(text),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .concat();
+        Self::parse(kind, &source)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&MetadataValue> {
+        self.entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// The document's title, taken from the `title` entry.
+    pub fn title(&self) -> Option<&str> {
+        match self.get("title")? {
+            MetadataValue::String(title) => Some(title),
+            _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &MetadataValue)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Lists are separated by commas, maps are written as `key: value` pairs.
+impl fmt::Display for MetadataValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataValue::String(value) => write!(f, "{value}"),
+            MetadataValue::Integer(value) => write!(f, "{value}"),
+            MetadataValue::Float(value) => write!(f, "{value}"),
+            MetadataValue::Boolean(value) => write!(f, "{value}"),
+            MetadataValue::List(values) => {
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                Ok(())
+            }
+            MetadataValue::Map(entries) => {
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key}: {value}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_yaml_front_matter() {
+        let metadata = Metadata::from_markdown(
+            "---\ntitle: Hello\nauthors:\n  - Jane\n  - John\ndraft: false\n---\n\n# Text",
+        )
+        .unwrap();
+        assert_eq!(Some("Hello"), metadata.title());
+        assert_eq!(
+            Some(&MetadataValue::List(vec![
+                MetadataValue::String("Jane".to_owned()),
+                MetadataValue::String("John".to_owned())
+            ])),
+            metadata.get("authors")
+        );
+        assert_eq!(
+            vec!["title", "authors", "draft"],
+            metadata.iter().map(|(k, _)| k).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn parses_toml_front_matter() {
+        let metadata = Metadata::parse(
+            MetadataBlockKind::PlusesStyle,
+            "title = \"Hello\"\nweight = 3\n[extra]\ntoc = true\n",
+        )
+        .unwrap();
+        assert_eq!(Some("Hello"), metadata.title());
+        assert_eq!(Some(&MetadataValue::Integer(3)), metadata.get("weight"));
+        assert_eq!("toc: true", metadata.get("extra").unwrap().to_string());
+    }
+
+    #[test]
+    fn rejects_invalid_metadata() {
+        assert_eq!(
+            None,
+            Metadata::parse(MetadataBlockKind::YamlStyle, "- a list")
+        );
+        assert_eq!(
+            None,
+            Metadata::parse(MetadataBlockKind::PlusesStyle, "title =")
+        );
+    }
+
+    #[test]
+    fn documents_without_front_matter_have_no_metadata() {
+        assert_eq!(None, Metadata::from_markdown("# Title"));
+    }
+}
@@ -0,0 +1,27 @@
+use super::MetadataValue;
+use ::toml::{Table, Value};
+
+pub(super) fn parse(source: &str) -> Option<Vec<(String, MetadataValue)>> {
+    source.parse::<Table>().ok().map(entries)
+}
+
+fn entries(table: Table) -> Vec<(String, MetadataValue)> {
+    table
+        .into_iter()
+        .map(|(key, value)| (key, value_from_toml(value)))
+        .collect()
+}
+
+fn value_from_toml(value: Value) -> MetadataValue {
+    match value {
+        Value::String(value) => MetadataValue::String(value),
+        Value::Integer(value) => MetadataValue::Integer(value),
+        Value::Float(value) => MetadataValue::Float(value),
+        Value::Boolean(value) => MetadataValue::Boolean(value),
+        Value::Datetime(value) => MetadataValue::String(value.to_string()),
+        Value::Array(values) => {
+            MetadataValue::List(values.into_iter().map(value_from_toml).collect())
+        }
+        Value::Table(table) => MetadataValue::Map(entries(table)),
+    }
+}
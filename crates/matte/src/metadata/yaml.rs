@@ -0,0 +1,45 @@
+use super::MetadataValue;
+use yaml_rust::{Yaml, YamlLoader};
+
+pub(super) fn parse(source: &str) -> Option<Vec<(String, MetadataValue)>> {
+    let documents = YamlLoader::load_from_str(source).ok()?;
+    match documents.into_iter().next() {
+        Some(Yaml::Hash(hash)) => Some(entries(hash)),
+        // An empty block has no entries.
+        None | Some(Yaml::Null) => Some(Vec::new()),
+        Some(_) => None,
+    }
+}
+
+fn entries(hash: yaml_rust::yaml::Hash) -> Vec<(String, MetadataValue)> {
+    hash.into_iter()
+        .filter_map(|(key, value)| Some((key_to_string(key)?, value_from_yaml(value)?)))
+        .collect()
+}
+
+fn key_to_string(key: Yaml) -> Option<String> {
+    match key {
+        Yaml::String(key) | Yaml::Real(key) => Some(key),
+        Yaml::Integer(key) => Some(key.to_string()),
+        Yaml::Boolean(key) => Some(key.to_string()),
+        _ => None,
+    }
+}
+
+/// Nulls, aliases and invalid values are skipped.
+fn value_from_yaml(value: Yaml) -> Option<MetadataValue> {
+    match value {
+        Yaml::String(value) => Some(MetadataValue::String(value)),
+        Yaml::Integer(value) => Some(MetadataValue::Integer(value)),
+        Yaml::Real(value) => match value.parse() {
+            Ok(value) => Some(MetadataValue::Float(value)),
+            Err(_) => Some(MetadataValue::String(value)),
+        },
+        Yaml::Boolean(value) => Some(MetadataValue::Boolean(value)),
+        Yaml::Array(values) => Some(MetadataValue::List(
+            values.into_iter().filter_map(value_from_yaml).collect(),
+        )),
+        Yaml::Hash(hash) => Some(MetadataValue::Map(entries(hash))),
+        Yaml::Alias(_) | Yaml::Null | Yaml::BadValue => None,
+    }
+}
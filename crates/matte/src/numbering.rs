@@ -0,0 +1,460 @@
+//! Numbering patterns modeled on [Typst's numbering](https://typst.app/docs/reference/model/numbering/).
+
+use crate::counting::SectionCounter;
+use pulldown_cmark::HeadingLevel;
+use std::fmt;
+use std::str::FromStr;
+
+/// A pattern such as `"1.a.i"`, `"I.1"` or `"A)"` that turns numbers like `[1, 2, 3]` into `1.b.iii`.
+///
+/// The counting symbols are `1` (arabic), `a` and `A` (alphabetic), `i` and `I` (roman),
+/// `一` (CJK) and `①` (circled digits), everything else is text around the numbers.
+/// The last counting symbol is repeated, along with the text before it, for deeper numbers.
+/// So `"1."` numbers `[1, 2, 3]` as `1.2.3.`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberingPattern {
+    /// The counting symbols with the text before them.
+    pieces: Vec<(String, Counter)>,
+    suffix: String,
+}
+
+impl NumberingPattern {
+    pub fn apply(&self, numbers: &[usize]) -> String {
+        let mut output = String::new();
+        let mut numbers = numbers.iter();
+        for ((prefix, counter), &n) in self.pieces.iter().zip(&mut numbers) {
+            output.push_str(prefix);
+            output.push_str(&counter.format(n));
+        }
+        for ((prefix, counter), &n) in self.pieces.last().into_iter().cycle().zip(numbers) {
+            // Without a separator the numbers would run together.
+            if prefix.is_empty() {
+                output.push_str(&self.suffix);
+            } else {
+                output.push_str(prefix);
+            }
+            output.push_str(&counter.format(n));
+        }
+        output.push_str(&self.suffix);
+        output
+    }
+}
+
+impl FromStr for NumberingPattern {
+    type Err = InvalidNumberingPattern;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        for c in pattern.chars() {
+            match Counter::from_symbol(c) {
+                Some(counter) => pieces.push((std::mem::take(&mut text), counter)),
+                None => text.push(c),
+            }
+        }
+        if pieces.is_empty() {
+            return Err(InvalidNumberingPattern(pattern.to_owned()));
+        }
+        Ok(Self {
+            pieces,
+            suffix: text,
+        })
+    }
+}
+
+/// A numbering pattern without any counting symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidNumberingPattern(String);
+
+impl fmt::Display for InvalidNumberingPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "numbering pattern `{}` has no counting symbol such as `1`, `a` or `i`",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidNumberingPattern {}
+
+/// How headings are numbered, e.g. `1.2. ` in front of the second H3 in the first H2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadingNumbering {
+    first_level: HeadingLevel,
+    pattern: Option<NumberingPattern>,
+    /// Patterns that replace `pattern` for a heading level.
+    levels: [Option<NumberingPattern>; 6],
+}
+
+impl Default for HeadingNumbering {
+    /// Numbers headings as `1.2.` starting at H2,
+    /// as documents usually have a single H1 as their title.
+    fn default() -> Self {
+        Self::new(NumberingPattern::from_str("1.").unwrap()).starting_at(HeadingLevel::H2)
+    }
+}
+
+impl HeadingNumbering {
+    /// Numbers all headings with the given pattern.
+    pub fn new(pattern: NumberingPattern) -> Self {
+        Self {
+            first_level: HeadingLevel::H1,
+            pattern: Some(pattern),
+            levels: Default::default(),
+        }
+    }
+
+    /// Headings are not numbered.
+    pub fn none() -> Self {
+        Self {
+            first_level: HeadingLevel::H1,
+            pattern: None,
+            levels: Default::default(),
+        }
+    }
+
+    /// Leaves out the levels above `level`, e.g. for documents with a single H1 as their title.
+    pub fn starting_at(self, level: HeadingLevel) -> Self {
+        Self {
+            first_level: level,
+            ..self
+        }
+    }
+
+    /// Uses a different pattern for headings of the given level.
+    pub fn with_level(mut self, level: HeadingLevel, pattern: NumberingPattern) -> Self {
+        self.levels[index(level)] = Some(pattern);
+        self
+    }
+
+    /// The number of the current section followed by a space,
+    /// or an empty string if the section is not numbered.
+    pub fn format(&self, section: &SectionCounter) -> String {
+        let counters = section.as_slice();
+        let Some(numbers) = counters.get(index(self.first_level)..) else {
+            return String::new();
+        };
+        let level = counters.len() - 1;
+        let pattern = self.levels[level].as_ref().or(self.pattern.as_ref());
+        match pattern {
+            // No numbering for sections with leading zeroes.
+            Some(pattern) if !numbers.is_empty() && !numbers.starts_with(&[0]) => {
+                let mut output = pattern.apply(numbers);
+                output.push(' ');
+                output
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+fn index(level: HeadingLevel) -> usize {
+    level as usize - HeadingLevel::H1 as usize
+}
+
+/// How the items of ordered lists are numbered, e.g. `1.`, `a.` and `i.` for nested lists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListNumbering {
+    /// The patterns for ordered lists by how deeply they are nested, repeated once all are used.
+    patterns: Vec<NumberingPattern>,
+    nested: bool,
+}
+
+impl Default for ListNumbering {
+    /// Numbers items as `1.` regardless of how deeply their list is nested.
+    fn default() -> Self {
+        Self::new(NumberingPattern::from_str("1.").unwrap())
+    }
+}
+
+impl ListNumbering {
+    /// Numbers the items of all ordered lists with the given pattern.
+    pub fn new(pattern: NumberingPattern) -> Self {
+        Self {
+            patterns: vec![pattern],
+            nested: false,
+        }
+    }
+
+    /// Uses the first pattern for the outermost ordered lists, the second one for lists inside them and so on.
+    /// Falls back to [the default](ListNumbering::default) if there are no patterns.
+    pub fn by_depth(patterns: impl IntoIterator<Item = NumberingPattern>) -> Self {
+        let patterns: Vec<_> = patterns.into_iter().collect();
+        if patterns.is_empty() {
+            Self::default()
+        } else {
+            Self {
+                patterns,
+                nested: false,
+            }
+        }
+    }
+
+    /// Includes the numbers of the items that a list is nested in, e.g. `2.1.3.`
+    /// for the third item in a list inside of the first item in a list inside of item 2.
+    /// The first pattern numbers all items, so with `"1.a.i."` that item is `2.a.iii.`.
+    pub fn nested(self) -> Self {
+        Self {
+            nested: true,
+            ..self
+        }
+    }
+
+    /// The marker of a list item, `numbers` are the item's number
+    /// preceded by the numbers of the items that its list is nested in.
+    pub(crate) fn format(&self, numbers: &[usize]) -> String {
+        let depth = numbers.len().saturating_sub(1);
+        if self.nested {
+            self.patterns[0].apply(numbers)
+        } else {
+            self.patterns[depth % self.patterns.len()].apply(&numbers[depth..])
+        }
+    }
+}
+
+/// The symbols that a number is written with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Counter {
+    Arabic,
+    LowerAlpha,
+    UpperAlpha,
+    LowerRoman,
+    UpperRoman,
+    Cjk,
+    Circled,
+}
+
+impl Counter {
+    fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol {
+            '1' => Some(Counter::Arabic),
+            'a' => Some(Counter::LowerAlpha),
+            'A' => Some(Counter::UpperAlpha),
+            'i' => Some(Counter::LowerRoman),
+            'I' => Some(Counter::UpperRoman),
+            '一' => Some(Counter::Cjk),
+            '①' => Some(Counter::Circled),
+            _ => None,
+        }
+    }
+
+    /// Writes a number, falling back to arabic numerals for numbers that the counter can't show.
+    pub(crate) fn format(self, n: usize) -> String {
+        let formatted = match self {
+            Counter::Arabic => None,
+            Counter::LowerAlpha => alphabetic(n, b'a'),
+            Counter::UpperAlpha => alphabetic(n, b'A'),
+            Counter::LowerRoman => roman(n).map(|roman| roman.to_lowercase()),
+            Counter::UpperRoman => roman(n),
+            Counter::Cjk => cjk(n),
+            Counter::Circled => circled(n).map(String::from),
+        };
+        formatted.unwrap_or_else(|| n.to_string())
+    }
+}
+
+/// `a` to `z`, followed by `aa`, `ab` and so on.
+fn alphabetic(mut n: usize, a: u8) -> Option<String> {
+    if n == 0 {
+        return None;
+    }
+    let mut letters = Vec::new();
+    while n > 0 {
+        n -= 1;
+        letters.push(char::from(a + (n % 26) as u8));
+        n /= 26;
+    }
+    Some(letters.into_iter().rev().collect())
+}
+
+fn roman(mut n: usize) -> Option<String> {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    if n == 0 || n >= 4000 {
+        return None;
+    }
+    let mut output = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            output.push_str(numeral);
+            n -= value;
+        }
+    }
+    Some(output)
+}
+
+/// Chinese numerals, e.g. `十二` for 12 and `一百零五` for 105.
+fn cjk(n: usize) -> Option<String> {
+    if n == 0 {
+        return Some("〇".to_owned());
+    }
+    if n >= 100_000_000 {
+        return None;
+    }
+    let (high, low) = (n / 10_000, n % 10_000);
+    let mut output = String::new();
+    if high > 0 {
+        write_cjk_group(high, &mut output);
+        output.push('万');
+        if low > 0 && low < 1000 {
+            output.push('零');
+        }
+    }
+    write_cjk_group(low, &mut output);
+    // 一十二 is written as 十二.
+    if (10..20).contains(&n) {
+        output.remove(0);
+    }
+    Some(output)
+}
+
+/// Writes a number below 10,000.
+fn write_cjk_group(n: usize, output: &mut String) {
+    const DIGITS: [char; 10] = ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+    const UNITS: [&str; 4] = ["千", "百", "十", ""];
+    let mut started = false;
+    let mut pending_zero = false;
+    for (position, unit) in UNITS.iter().enumerate() {
+        let digit = n / 10_usize.pow(3 - position as u32) % 10;
+        if digit == 0 {
+            pending_zero = started;
+            continue;
+        }
+        started = true;
+        if pending_zero {
+            output.push('零');
+            pending_zero = false;
+        }
+        output.push(DIGITS[digit]);
+        output.push_str(unit);
+    }
+}
+
+fn circled(n: usize) -> Option<char> {
+    let code_point = match n {
+        0 => 0x24EA,
+        1..=20 => 0x2460 + n - 1,
+        21..=35 => 0x3251 + n - 21,
+        36..=50 => 0x32B1 + n - 36,
+        _ => return None,
+    };
+    char::from_u32(code_point as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(pattern: &str, numbers: &[usize]) -> String {
+        pattern.parse::<NumberingPattern>().unwrap().apply(numbers)
+    }
+
+    #[test]
+    fn applies_patterns() {
+        assert_eq!("1.2.3.", apply("1.", &[1, 2, 3]));
+        assert_eq!("1.b.iii", apply("1.a.i", &[1, 2, 3]));
+        assert_eq!("1.b", apply("1.a.i", &[1, 2]));
+        assert_eq!("IV.2", apply("I.1", &[4, 2]));
+        assert_eq!("C)", apply("A)", &[3]));
+        assert_eq!("(1.1)", apply("(1.1)", &[1, 1]));
+        assert_eq!("§ 2-1-4", apply("§ 1-1", &[2, 1, 4]));
+        assert_eq!("②", apply("①", &[2]));
+        assert_eq!("第十二章", apply("第一章", &[12]));
+    }
+
+    #[test]
+    fn rejects_patterns_without_counting_symbols() {
+        assert!("§".parse::<NumberingPattern>().is_err());
+    }
+
+    #[test]
+    fn formats_counters() {
+        let format = |counter: Counter, numbers: &[usize]| -> Vec<String> {
+            numbers.iter().map(|&n| counter.format(n)).collect()
+        };
+        assert_eq!(
+            vec!["a", "z", "aa", "az", "ba"],
+            format(Counter::LowerAlpha, &[1, 26, 27, 52, 53])
+        );
+        assert_eq!(
+            vec!["i", "iv", "ix", "xiv", "mcmxcix", "4000"],
+            format(Counter::LowerRoman, &[1, 4, 9, 14, 1999, 4000])
+        );
+        assert_eq!(
+            vec![
+                "一",
+                "十",
+                "十一",
+                "二十",
+                "一百零五",
+                "一百一十",
+                "一千零一",
+                "一万",
+                "二万零三十"
+            ],
+            format(
+                Counter::Cjk,
+                &[1, 10, 11, 20, 105, 110, 1001, 10_000, 20_030]
+            )
+        );
+        assert_eq!(
+            vec!["①", "⑳", "㉑", "㊿", "51"],
+            format(Counter::Circled, &[1, 20, 21, 50, 51])
+        );
+    }
+
+    #[test]
+    fn numbers_list_items() {
+        let patterns = ["1.", "a)", "i."].map(|p| NumberingPattern::from_str(p).unwrap());
+        let numbering = ListNumbering::by_depth(patterns.clone());
+        assert_eq!("2.", numbering.format(&[2]));
+        assert_eq!("a)", numbering.format(&[2, 1]));
+        assert_eq!("iii.", numbering.format(&[2, 1, 3]));
+        assert_eq!("4.", numbering.format(&[2, 1, 3, 4]));
+
+        let numbering = ListNumbering::default().nested();
+        assert_eq!("2.1.3.", numbering.format(&[2, 1, 3]));
+        let numbering = ListNumbering::new(NumberingPattern::from_str("1.a.i.").unwrap()).nested();
+        assert_eq!("2.a.iii.", numbering.format(&[2, 1, 3]));
+    }
+
+    #[test]
+    fn numbers_headings() {
+        fn section(levels: &[HeadingLevel]) -> SectionCounter {
+            let mut section = SectionCounter::default();
+            for &level in levels {
+                section.update(level);
+            }
+            section
+        }
+        use HeadingLevel::*;
+
+        let numbering = HeadingNumbering::default();
+        assert_eq!("", numbering.format(&section(&[H1])));
+        assert_eq!("2. ", numbering.format(&section(&[H1, H2, H2])));
+        assert_eq!("1.1. ", numbering.format(&section(&[H1, H2, H3])));
+        assert_eq!("", numbering.format(&section(&[H1, H3])));
+
+        let numbering =
+            HeadingNumbering::new("I.".parse().unwrap()).with_level(H2, "1.A)".parse().unwrap());
+        assert_eq!("II. ", numbering.format(&section(&[H1, H1])));
+        assert_eq!("1.B) ", numbering.format(&section(&[H1, H2, H2])));
+        assert_eq!("I.II.I. ", numbering.format(&section(&[H1, H2, H2, H3])));
+
+        assert_eq!("", HeadingNumbering::none().format(&section(&[H1])));
+    }
+}
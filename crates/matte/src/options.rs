@@ -1,9 +1,9 @@
+use crate::syntax_highlighting;
 use crate::themes::Theme;
-use std::marker::PhantomData;
+use crate::{CodeRunner, Diagnostics, HeadingNumbering, ListNumbering};
+use std::sync::Arc;
 use url::Url;
 
-// TODO: Typst has a wonderful numbering system:
-// https://github.com/typst/typst/blob/23746ee18901e08852306f35639298ad234d3481/crates/typst/src/model/numbering.rs
 #[derive(Debug)]
 #[non_exhaustive]
 pub struct Options {
@@ -11,17 +11,47 @@ pub struct Options {
     pub columns: u16,
     pub text_max_columns: usize,
     pub symbol_repertoire: SymbolRepertoire,
-    // pub rule_style: RuleStyle,
-    // pub show_metadata_blocks: bool,
+    /// How to draw horizontal rules, the theme decides if this is `None`.
+    pub rule_style: Option<RuleStyle>,
     pub hyperlinks: bool,
     /// Absolute URL that will be used as base for resolving
     /// relative links found in the document.
     pub base_url: Option<Url>,
     pub footnote_definition_placement: FootnoteDefinitionPlacement,
+    pub table_layout: TableLayout,
+    /// How to show images, images are represented by their alt text if this is `None`.
+    /// Only local images are supported.
+    pub image_protocol: Option<ImageProtocol>,
+    /// How to show metadata blocks (front matter) at the start of the document.
+    pub metadata_blocks: MetadataBlocks,
+    /// The theme used for highlighting code blocks.
+    pub syntax_theme: SyntaxTheme,
+    /// Where to report problems with the document, such as unknown code block languages.
+    pub diagnostics: Diagnostics,
+    /// What to do with lines of code that are too long.
+    pub code_wrapping: CodeWrapping,
+    /// Runs code blocks that have the `run` modifier, these are only highlighted if this is `None`.
+    pub code_runner: Option<Arc<dyn CodeRunner>>,
+    /// How themes that number headings, such as the default theme, number them.
+    pub heading_numbering: HeadingNumbering,
+    /// How the items of ordered lists are numbered.
+    pub list_numbering: ListNumbering,
 
     pub theme: Theme,
 }
 
+/// What kind of document is rendered, some defaults only suit certain kinds of documents.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DocumentProfile {
+    /// Prose that is structured by its headings, such as documentation.
+    #[default]
+    Document,
+    /// Release notes with a heading per version, such as a `CHANGELOG.md`.
+    /// Section numbers next to version numbers are only confusing, so headings are not numbered.
+    Changelog,
+}
+
 /// Where to place footnote definitions.
 #[derive(Debug, Copy, Clone, Default)]
 pub enum FootnoteDefinitionPlacement {
@@ -32,6 +62,95 @@ pub enum FootnoteDefinitionPlacement {
     InPlace,
 }
 
+/// How to lay out tables.
+#[derive(Debug, Copy, Clone, Default)]
+pub enum TableLayout {
+    /// Draw a grid if all columns fit into the available width,
+    /// otherwise fall back to [`TableLayout::Records`].
+    #[default]
+    Auto,
+    /// Always draw a grid, wrapping and cutting off cell contents as needed.
+    Grid,
+    /// Print each row as a block of `header: value` lines.
+    Records,
+}
+
+/// What to do with lines of code that are wider than the available width.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum CodeWrapping {
+    /// Wrap after whitespace or between tokens and mark continuation rows with `↪`.
+    #[default]
+    Wrap,
+    /// Cut off long lines with `…`, e.g. for pagers that scroll horizontally.
+    Truncate,
+}
+
+/// How to show metadata blocks, see [`crate::Metadata`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum MetadataBlocks {
+    /// Metadata is not part of the document's content, so it's hidden by default.
+    #[default]
+    Hidden,
+    /// A header with the title followed by the remaining entries as `key: value` lines.
+    /// Falls back to [`MetadataBlocks::CodeBlock`] if the metadata can't be parsed.
+    Header,
+    /// The source of the metadata block as a highlighted code block.
+    CodeBlock,
+}
+
+/// How to show images: Using a terminal graphics protocol or as text art.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageProtocol {
+    /// The [Kitty graphics protocol](https://sw.kovidgoyal.net/kitty/graphics-protocol/).
+    Kitty,
+    /// The [inline images protocol](https://iterm2.com/documentation-images.html) of iTerm2.
+    Iterm2,
+    /// [Sixel](https://en.wikipedia.org/wiki/Sixel) graphics.
+    Sixel,
+    /// Text art made of colored "▀" half blocks, with two pixels per cell.
+    /// Works in any terminal that supports true color.
+    HalfBlocks,
+    /// Text art made of braille patterns, with eight dots per cell
+    /// but only a single color per cell.
+    Braille,
+}
+
+/// Which of the themes bundled with [bat](https://github.com/sharkdp/bat) to highlight code with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SyntaxTheme {
+    /// Uses the terminal's own palette, so it works on light and dark backgrounds alike.
+    #[default]
+    Ansi,
+    /// bat's default light or dark theme, depending on the terminal's background.
+    Automatic(Background),
+    /// A theme by name, e.g. `Monokai Extended` or `GitHub`.
+    /// Falls back to [`SyntaxTheme::Ansi`] if there's no such theme.
+    Named(String),
+}
+
+impl SyntaxTheme {
+    /// The names of all bundled themes.
+    pub fn names() -> Vec<String> {
+        syntax_highlighting::theme_names()
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            SyntaxTheme::Ansi => "ansi",
+            SyntaxTheme::Automatic(Background::Light) => "Monokai Extended Light",
+            SyntaxTheme::Automatic(Background::Dark) => "Monokai Extended",
+            SyntaxTheme::Named(name) => name,
+        }
+    }
+}
+
+/// The terminal's background color.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Background {
+    Light,
+    Dark,
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
 pub struct SymbolRepertoire(SymbolRepertoireImpl);
 
@@ -66,12 +185,30 @@ impl Options {
             columns,
             text_max_columns: 100,
             symbol_repertoire: SymbolRepertoire::unicode(true),
+            rule_style: None,
             hyperlinks: true,
             base_url: None,
             footnote_definition_placement: FootnoteDefinitionPlacement::default(),
+            table_layout: TableLayout::default(),
+            image_protocol: None,
+            metadata_blocks: MetadataBlocks::default(),
+            syntax_theme: SyntaxTheme::default(),
+            diagnostics: Diagnostics::default(),
+            code_wrapping: CodeWrapping::default(),
+            code_runner: None,
+            heading_numbering: HeadingNumbering::default(),
+            list_numbering: ListNumbering::default(),
             theme: Theme::default(),
         }
     }
+
+    /// Changes the defaults that don't suit documents of the given kind.
+    pub fn apply_profile(&mut self, profile: DocumentProfile) {
+        match profile {
+            DocumentProfile::Document => {}
+            DocumentProfile::Changelog => self.heading_numbering = HeadingNumbering::none(),
+        }
+    }
 }
 
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
@@ -83,27 +220,49 @@ enum SymbolRepertoireImpl {
     Ascii,
 }
 
-#[derive(Debug)]
-pub struct RuleStyle(PhantomData<()>);
+/// How to draw horizontal rules (thematic breaks).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RuleStyle(RuleStyleImpl);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum RuleStyleImpl {
+    Line,
+    Dinkus,
+    Ornament(char),
+}
 
 impl RuleStyle {
-    /// A horizonal line.
+    /// A horizonal line: "◈───◈".
     pub const fn line() -> Self {
-        todo!()
+        Self(RuleStyleImpl::Line)
     }
 
     /// Three spaced asterisks: "∗ ∗ ∗".
     pub const fn dinkus() -> Self {
-        todo!()
+        Self(RuleStyleImpl::Dinkus)
     }
 
     /// A fleuron: "❧".
     pub const fn fleuron() -> Self {
-        todo!()
+        Self::ornament('❧')
     }
 
     /// An ornamental symbol e.g. a dingbat or a fleuron.
-    pub const fn ornament(_c: char) -> Self {
-        todo!()
+    pub const fn ornament(c: char) -> Self {
+        Self(RuleStyleImpl::Ornament(c))
+    }
+
+    /// The text that is centered on the rule's line,
+    /// `None` for a line that spans the entire width.
+    /// Ornaments that are not ASCII fall back to a dinkus in the ASCII repertoire.
+    pub(crate) fn decoration(self, symbol_repertoire: SymbolRepertoire) -> Option<String> {
+        match self.0 {
+            RuleStyleImpl::Line => None,
+            RuleStyleImpl::Ornament(c) if c.is_ascii() || symbol_repertoire.is_unicode() => {
+                Some(c.to_string())
+            }
+            RuleStyleImpl::Dinkus if symbol_repertoire.is_unicode() => Some("∗ ∗ ∗".to_owned()),
+            RuleStyleImpl::Dinkus | RuleStyleImpl::Ornament(_) => Some("* * *".to_owned()),
+        }
     }
 }
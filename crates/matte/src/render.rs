@@ -7,12 +7,15 @@ use trait_set::trait_set;
 
 mod code_block;
 mod footnote_def;
+mod metadata_block;
 mod paragraph;
 mod rule;
 mod table;
 
+pub use code_block::CodeBlockStyle;
 pub(crate) use code_block::*;
 pub(crate) use footnote_def::*;
+pub(crate) use metadata_block::*;
 pub(crate) use paragraph::*;
 pub(crate) use rule::*;
 pub(crate) use table::*;
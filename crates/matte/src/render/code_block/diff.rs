@@ -0,0 +1,138 @@
+use crate::style::StyledStr;
+use crate::syntax_highlighting::{unhighlighted, HighlightedLine, Highlighter};
+use anstyle::AnsiColor::{Cyan, Green, Red};
+use anstyle::Style;
+
+/// Highlights a diff of code in `language`, e.g. of a `diff-rust` code block.
+/// The old (unchanged and `-` lines) and the new version (unchanged and `+` lines)
+/// are each highlighted as one piece of code, so that constructs spanning multiple lines
+/// are highlighted correctly without the removed lines affecting the added ones.
+/// The `+` and `-` markers are kept as a colored gutter.
+/// Returns `None` if `language` is not known.
+pub(super) fn highlight_diff(
+    code: &str,
+    language: &str,
+    highlighter: &Highlighter,
+) -> Option<Vec<HighlightedLine>> {
+    let lines: Vec<_> = code.lines().map(DiffLine::parse).collect();
+    let mut old = highlighter
+        .highlight(&version(&lines, '-'), Some(language))?
+        .into_iter();
+    let mut new = highlighter
+        .highlight(&version(&lines, '+'), Some(language))?
+        .into_iter();
+
+    let lines = lines
+        .into_iter()
+        .map(|line| match line {
+            DiffLine::Code(marker, _) => {
+                let mut line = match marker {
+                    '-' => old.next(),
+                    '+' => new.next(),
+                    _ => {
+                        old.next();
+                        new.next()
+                    }
+                }
+                .unwrap_or_default();
+                line.insert(0, StyledStr::new(marker.to_string(), marker_style(marker)));
+                line
+            }
+            DiffLine::Other(text) => unhighlighted(text, Cyan.on_default())
+                .pop()
+                .unwrap_or_default(),
+        })
+        .collect();
+    Some(lines)
+}
+
+/// The code of the unchanged lines and the lines with the given `marker`.
+fn version(lines: &[DiffLine<'_>], marker: char) -> String {
+    let mut code = String::new();
+    for line in lines {
+        if let DiffLine::Code(m, line) = line {
+            if *m == ' ' || *m == marker {
+                code.push_str(line);
+                code.push('\n');
+            }
+        }
+    }
+    code
+}
+
+enum DiffLine<'a> {
+    /// An added, removed or unchanged line of code.
+    Code(char, &'a str),
+    /// Anything else, e.g. a hunk header such as `@@ -1,2 +1,3 @@`.
+    Other(&'a str),
+}
+
+impl<'a> DiffLine<'a> {
+    fn parse(line: &'a str) -> Self {
+        match line.chars().next() {
+            Some(marker @ ('+' | '-' | ' ')) => DiffLine::Code(marker, &line[1..]),
+            // Editors like to strip the trailing space of unchanged empty lines.
+            None => DiffLine::Code(' ', ""),
+            Some(_) => DiffLine::Other(line),
+        }
+    }
+}
+
+fn marker_style(marker: char) -> Style {
+    match marker {
+        '+' => Green.on_default(),
+        '-' => Red.on_default(),
+        _ => Style::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Diagnostics, SyntaxTheme};
+
+    #[test]
+    fn highlights_changed_lines_with_markers() {
+        let code = "@@ -1 +1 @@\n-fn a() {}\n+fn b() {}\n\n fn c() {}\n";
+        let lines = highlight_diff(
+            code,
+            "rust",
+            &Highlighter::new(SyntaxTheme::Ansi, Diagnostics::default()),
+        )
+        .unwrap();
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| line.iter().map(|run| &**run).collect())
+            .collect();
+        assert_eq!(
+            vec!["@@ -1 +1 @@", "-fn a() {}", "+fn b() {}", " ", " fn c() {}"],
+            text
+        );
+        assert_eq!(Some(Cyan.into()), lines[0][0].1.get_fg_color());
+        assert_eq!(Some(Red.into()), lines[1][0].1.get_fg_color());
+        assert_eq!(Some(Green.into()), lines[2][0].1.get_fg_color());
+        let keyword = lines[2].iter().find(|run| &***run == "fn").unwrap();
+        assert!(keyword.1.get_fg_color().is_some());
+    }
+
+    #[test]
+    fn highlights_old_and_new_lines_separately() {
+        let highlighter = Highlighter::new(SyntaxTheme::Ansi, Diagnostics::default());
+        let code = "-let s = \"unclosed\n+let n = 1;\n let m = 2;\n";
+        let lines = highlight_diff(code, "rust", &highlighter).unwrap();
+        let expected = highlighter
+            .highlight("let n = 1;\nlet m = 2;\n", Some("rust"))
+            .unwrap();
+        let runs = |line: &[StyledStr<'_>]| -> Vec<(String, Style)> {
+            line.iter().map(|run| (run.to_string(), run.1)).collect()
+        };
+        assert_eq!(runs(&expected[0]), runs(&lines[1][1..]));
+        assert_eq!(runs(&expected[1]), runs(&lines[2][1..]));
+    }
+
+    #[test]
+    fn unknown_inner_language() {
+        let highlighter = Highlighter::new(SyntaxTheme::Ansi, Diagnostics::default());
+        assert!(highlight_diff("+x", "no-such-language", &highlighter).is_none());
+    }
+}
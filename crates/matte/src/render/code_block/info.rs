@@ -0,0 +1,90 @@
+use std::ops::RangeInclusive;
+
+/// A fenced code block's info string, e.g. `rust,ignore {3,5-7}`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct CodeInfo<'a> {
+    /// The language including its modifiers, e.g. `rust,ignore`.
+    pub(crate) language: Option<&'a str>,
+    pub(crate) emphasized: LineRanges,
+}
+
+impl<'a> CodeInfo<'a> {
+    pub(crate) fn parse(info: &'a str) -> Self {
+        let info = info.trim();
+        let language = info
+            .split(|c: char| c.is_whitespace() || c == '{')
+            .next()
+            .filter(|language| !language.is_empty());
+        let emphasized = info
+            .split_once('{')
+            .and_then(|(_, rest)| rest.split_once('}'))
+            .map(|(ranges, _)| LineRanges::parse(ranges))
+            .unwrap_or_default();
+        Self {
+            language,
+            emphasized,
+        }
+    }
+}
+
+/// The (1-based) lines of a code block that are emphasized.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct LineRanges(Vec<RangeInclusive<usize>>);
+
+impl LineRanges {
+    /// Parses comma separated line numbers and ranges such as `3,5-7`.
+    /// Anything that is not a line number or range is ignored.
+    fn parse(ranges: &str) -> Self {
+        let ranges = ranges
+            .split(',')
+            .map(str::trim)
+            .filter_map(|range| match range.split_once('-') {
+                Some((start, end)) => Some(start.trim().parse().ok()?..=end.trim().parse().ok()?),
+                None => range.parse().ok().map(|line| line..=line),
+            })
+            .collect();
+        Self(ranges)
+    }
+
+    pub(crate) fn contains(&self, line: usize) -> bool {
+        self.0.iter().any(|range| range.contains(&line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_language_and_line_ranges() {
+        let info = CodeInfo::parse("rust {3,5-7}");
+        assert_eq!(Some("rust"), info.language);
+        assert_eq!(LineRanges(vec![3..=3, 5..=7]), info.emphasized);
+    }
+
+    #[test]
+    fn keeps_modifiers() {
+        let info = CodeInfo::parse("rust,ignore{1}");
+        assert_eq!(Some("rust,ignore"), info.language);
+        assert!(info.emphasized.contains(1));
+        assert!(!info.emphasized.contains(2));
+    }
+
+    #[test]
+    fn line_ranges_without_language() {
+        let info = CodeInfo::parse("{ 2 - 3, x }");
+        assert_eq!(None, info.language);
+        assert_eq!(LineRanges(vec![2..=3]), info.emphasized);
+    }
+
+    #[test]
+    fn no_line_ranges() {
+        assert_eq!(
+            CodeInfo {
+                language: Some("diff-rust"),
+                emphasized: LineRanges::default()
+            },
+            CodeInfo::parse("diff-rust title=\"a {b}\"")
+        );
+    }
+}
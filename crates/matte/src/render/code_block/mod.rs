@@ -0,0 +1,685 @@
+use self::diff::highlight_diff;
+pub(crate) use self::info::CodeInfo;
+use self::info::LineRanges;
+use self::wrap::{truncate, wrap};
+use super::prelude::*;
+use crate::ansi;
+use crate::block::Block;
+use crate::diagram::{self, is_diagram_language};
+use crate::fmt_utils::Repeat;
+use crate::prefix::Prefix;
+use crate::style::{StyleExt as _, StyleStack, StyledStr};
+use crate::syntax_highlighting::{unhighlighted, HighlightedLine};
+use crate::textwrap::{self, DisplayWidth};
+use crate::ThemeProvider as _;
+use crate::{CodeWrapping, Diagnostic};
+use anstyle::AnsiColor::Red;
+use matte_language_names::{diff_language, modifiers};
+use pulldown_cmark::CodeBlockKind;
+use unicode_width::UnicodeWidthStr as _;
+
+mod diff;
+mod info;
+mod wrap;
+
+pub(crate) struct CodeBlock<'a> {
+    pub(crate) kind: CodeBlockKind<'a>,
+}
+
+impl Block for CodeBlock<'_> {
+    fn kind(&self) -> BlockKind {
+        BlockKind::CodeBlock
+    }
+
+    fn render<'e>(
+        self,
+        events: &mut impl Events<'e>,
+        ctx: &Context<'_, 'e, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        // TODO: yes, yes we could use a buffer that only buffers until the next line...
+        let mut code = String::new();
+
+        for event in terminated!(events, Event::End(TagEnd::CodeBlock)) {
+            reachable! {
+                let Event::Text(text) = event {
+                    code.push_str(&text);
+                }
+            }
+        }
+
+        let info = match &self.kind {
+            CodeBlockKind::Indented => CodeInfo::default(),
+            CodeBlockKind::Fenced(info) => CodeInfo::parse(info),
+        };
+
+        if let Some(diagram) = info
+            .language
+            .and_then(label)
+            .and_then(|language| draw_diagram(language, &code, ctx))
+        {
+            for line in diagram {
+                w.write_prefix(ctx)?;
+                writeln!(w, "{}", StyledStr(line.into(), ctx.style()))?;
+            }
+            return Ok(());
+        }
+
+        write_code(&code, &info, ctx, w)?;
+        match info.language {
+            Some(language) if modifiers(language).any(|m| m == "run") => {
+                run_code(&code, language, ctx, w)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Draws mermaid and DOT diagrams, returns `None` to show their source instead.
+fn draw_diagram(language: &str, code: &str, ctx: &Context<'_, '_, '_>) -> Option<Vec<String>> {
+    let unicode = ctx.options().symbol_repertoire.is_unicode();
+    diagram::render(language, code, ctx.available_width(), unicode)
+}
+
+/// Runs the code with the [`crate::CodeRunner`] from the options
+/// and writes its output beneath the code.
+fn run_code(
+    code: &str,
+    info: &str,
+    ctx: &Context<'_, '_, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let Some(runner) = &ctx.options().code_runner else {
+        return Ok(());
+    };
+    let Some(output) = label(info).and_then(|language| runner.run(language, code)) else {
+        return Ok(());
+    };
+
+    let ctx = ctx.block(Prefix::uniform("  "), Style::new().dimmed());
+    let lines = unhighlighted(&sanitize(&output.stdout), Style::new())
+        .into_iter()
+        .chain(unhighlighted(&sanitize(&output.stderr), Red.on_default()))
+        .chain(
+            output
+                .failure
+                .map(|failure| vec![StyledStr::new(failure, Style::new().italic())]),
+        )
+        .collect();
+    let style = CodeBlockStyle::default();
+    let rows = decorate(
+        lines,
+        &LineRanges::default(),
+        None,
+        style,
+        symbols(&ctx),
+        ctx.options().code_wrapping,
+        ctx.available_width(),
+    );
+    for row in rows {
+        w.write_prefix(&ctx)?;
+        write_row(row, ctx.style(), w)?;
+    }
+    Ok(())
+}
+
+/// The output of a command can contain anything, but we only want text.
+fn sanitize(output: &str) -> String {
+    ansi::strip(output)
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\t'))
+        .collect()
+}
+
+/// How a theme draws code blocks.
+///
+/// The default is plain code without a box, line numbers or styles,
+/// unlike [`ThemeProvider::code_block_style`](crate::ThemeProvider::code_block_style)
+/// which defaults to a box with a dimmed border.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct CodeBlockStyle {
+    /// Draws a rounded box around the code with the language in the top border.
+    pub boxed: bool,
+    /// Numbers the lines of code in a gutter to the left of the code.
+    pub line_numbers: bool,
+    /// Used for the box and the gutter with the line numbers.
+    pub border_style: Style,
+    /// Used for the lines that an info string such as `rust {3,5-7}` emphasizes.
+    pub emphasis_style: Style,
+}
+
+impl CodeBlockStyle {
+    pub fn with_boxed(self, boxed: bool) -> Self {
+        Self { boxed, ..self }
+    }
+
+    pub fn with_line_numbers(self, line_numbers: bool) -> Self {
+        Self {
+            line_numbers,
+            ..self
+        }
+    }
+
+    pub fn with_border_style(self, border_style: Style) -> Self {
+        Self {
+            border_style,
+            ..self
+        }
+    }
+
+    pub fn with_emphasis_style(self, emphasis_style: Style) -> Self {
+        Self {
+            emphasis_style,
+            ..self
+        }
+    }
+}
+
+/// Writes highlighted code, e.g. of a code block or a metadata block.
+pub(crate) fn write_code(
+    code: &str,
+    info: &CodeInfo<'_>,
+    ctx: &Context<'_, '_, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let style = ctx.theme().code_block_style(&ctx.theme_context());
+    let language = info.language;
+    let highlighted = match language.and_then(diff_language) {
+        Some(inner) => highlight_diff(code, inner, ctx.highlighter()),
+        None => ctx.highlighter().highlight(code, language).or_else(|| {
+            // Diagrams that couldn't be drawn aren't in an unknown language,
+            // there's just no syntax definition for e.g. mermaid.
+            let diagram = language.and_then(label).is_some_and(is_diagram_language);
+            diagram.then(|| unhighlighted(code, Style::new()))
+        }),
+    };
+    let (lines, label) = match highlighted {
+        Some(lines) => {
+            let label = language
+                .and_then(label)
+                .map(|label| StyledStr::new(label.to_owned(), Style::new()));
+            (lines, label)
+        }
+        None => {
+            let language = language.and_then(label).unwrap_or_default();
+            let diagnostic = Diagnostic::UnknownLanguage {
+                language: language.to_owned(),
+            };
+            let label = StyledStr::new(diagnostic.to_string(), Style::new().dimmed());
+            ctx.options().diagnostics.report(diagnostic);
+            let lines = unhighlighted(code, Style::new());
+            if style.boxed {
+                (lines, Some(label))
+            } else {
+                w.write_prefix(ctx)?;
+                write_row(vec![label], ctx.style(), w)?;
+                (lines, None)
+            }
+        }
+    };
+
+    let rows = decorate(
+        lines,
+        &info.emphasized,
+        label,
+        style,
+        symbols(ctx),
+        ctx.options().code_wrapping,
+        ctx.available_width(),
+    );
+    for row in rows {
+        w.write_prefix(ctx)?;
+        write_row(row, ctx.style(), w)?;
+    }
+    Ok(())
+}
+
+type Row = Vec<StyledStr<'static>>;
+
+fn write_row(row: Row, style: Style, w: &mut impl Write) -> io::Result<()> {
+    let mut style_stack = StyleStack::new(style);
+    for run in row {
+        style_stack.push(run.1.on_top_of(style_stack.head()));
+        write!(w, "{}", StyledStr(run.0, style_stack.head()))?;
+        style_stack.pop();
+    }
+    writeln!(w)
+}
+
+/// The language name without modifiers, e.g. `rust` for `rust,ignore`.
+fn label(language: &str) -> Option<&str> {
+    language.split(',').next().filter(|label| !label.is_empty())
+}
+
+fn symbols(ctx: &Context<'_, '_, '_>) -> &'static BoxSymbols {
+    if ctx.options().symbol_repertoire.is_unicode() {
+        &UNICODE_BOX
+    } else {
+        &ASCII_BOX
+    }
+}
+
+const BORDER_AND_PADDING_WIDTH: usize = 4;
+
+struct BoxSymbols {
+    top_left: &'static str,
+    top_right: &'static str,
+    bottom_left: &'static str,
+    bottom_right: &'static str,
+    horizontal: &'static str,
+    vertical: &'static str,
+    /// Marks rows that continue a wrapped line.
+    continuation: &'static str,
+}
+
+const UNICODE_BOX: BoxSymbols = BoxSymbols {
+    top_left: "╭",
+    top_right: "╮",
+    bottom_left: "╰",
+    bottom_right: "╯",
+    horizontal: "─",
+    vertical: "│",
+    continuation: "↪",
+};
+
+const ASCII_BOX: BoxSymbols = BoxSymbols {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    horizontal: "-",
+    vertical: "|",
+    continuation: ">",
+};
+
+/// Adds line numbers and a box to highlighted code so that it fits into `width` columns.
+/// Lines that are too long are wrapped or truncated, continuation rows are marked
+/// with a continuation symbol in the gutter instead of a line number.
+/// Emphasized lines get the emphasis style's background across the whole width of the code.
+fn decorate(
+    lines: Vec<HighlightedLine>,
+    emphasized: &LineRanges,
+    label: Option<StyledStr<'static>>,
+    style: CodeBlockStyle,
+    symbols: &BoxSymbols,
+    wrapping: CodeWrapping,
+    width: usize,
+) -> Vec<Row> {
+    let border = style.border_style;
+    let gutter_width = if style.line_numbers {
+        lines.len().max(1).to_string().len() + 1
+    } else {
+        0
+    };
+    let border_width = if style.boxed {
+        BORDER_AND_PADDING_WIDTH
+    } else {
+        0
+    };
+    let code_width = width.saturating_sub(gutter_width + border_width).max(1);
+
+    let mut rows = Vec::new();
+    for (index, line) in lines.into_iter().enumerate() {
+        let emphasized = emphasized.contains(index + 1);
+        let line = if emphasized {
+            line.into_iter()
+                .map(|run| run.on_top_of(style.emphasis_style))
+                .collect()
+        } else {
+            line
+        };
+        let line_rows = match wrapping {
+            CodeWrapping::Wrap => {
+                // Without line numbers, continuation rows need a gutter of their own.
+                let continuation_width = if gutter_width > 0 {
+                    code_width
+                } else {
+                    code_width
+                        .saturating_sub(symbols.continuation.width() + 1)
+                        .max(1)
+                };
+                wrap(line, code_width, continuation_width)
+            }
+            CodeWrapping::Truncate => vec![truncate(line, code_width)],
+        };
+        for (row_index, (mut row, row_width)) in line_rows.into_iter().enumerate() {
+            let gutter = match (row_index, gutter_width) {
+                (0, 0) => None,
+                (0, _) => Some(StyledStr::new(
+                    format!("{:>1$} ", index + 1, gutter_width - 1),
+                    border,
+                )),
+                (_, 0) => Some(StyledStr::new(
+                    format!("{} ", symbols.continuation),
+                    border.dimmed(),
+                )),
+                (_, _) => Some(StyledStr::new(
+                    format!("{:>1$} ", symbols.continuation, gutter_width - 1),
+                    border.dimmed(),
+                )),
+            };
+            let gutter_width = gutter.as_ref().map_or(0, |gutter| gutter.width());
+            row.splice(0..0, gutter);
+            rows.push((row, gutter_width + row_width, emphasized));
+        }
+    }
+
+    let widest_row = rows.iter().map(|(_, width, _)| *width).max().unwrap_or(0);
+    if !style.boxed {
+        return rows
+            .into_iter()
+            .map(|(mut row, row_width, emphasized)| {
+                if emphasized {
+                    let fill = Repeat(widest_row - row_width, " ").to_string();
+                    row.push(StyledStr::new(fill, style.emphasis_style));
+                }
+                row
+            })
+            .collect();
+    }
+
+    let label = label.map(DisplayWidth::from);
+    let label_width = label.as_ref().map_or(0, |label| label.width() + 2);
+    let inner_width = widest_row
+        .max(label_width)
+        .min(width.saturating_sub(BORDER_AND_PADDING_WIDTH));
+
+    let BoxSymbols {
+        top_left,
+        top_right,
+        bottom_left,
+        bottom_right,
+        horizontal,
+        vertical,
+        continuation: _,
+    } = symbols;
+    let top = match label {
+        Some(label) => {
+            let text = textwrap::truncate(&label, inner_width.saturating_sub(2), "…").into_owned();
+            let fill = (inner_width + 2).saturating_sub(text.width() + 3);
+            vec![
+                StyledStr::new(format!("{top_left}{horizontal} "), border),
+                StyledStr::new(text, label.value().1.on_top_of(border)),
+                StyledStr::new(format!(" {}{top_right}", Repeat(fill, horizontal)), border),
+            ]
+        }
+        None => vec![StyledStr::new(
+            format!(
+                "{top_left}{}{top_right}",
+                Repeat(inner_width + 2, horizontal)
+            ),
+            border,
+        )],
+    };
+    let bottom = format!(
+        "{bottom_left}{}{bottom_right}",
+        Repeat(inner_width + 2, horizontal)
+    );
+
+    let rows = rows.into_iter().map(|(row, row_width, emphasized)| {
+        let fill = inner_width.saturating_sub(row_width);
+        let padding = if emphasized {
+            style.emphasis_style
+        } else {
+            Style::new()
+        };
+        let mut boxed = vec![
+            StyledStr::new(*vertical, border),
+            StyledStr::new(" ", padding),
+        ];
+        boxed.extend(row);
+        boxed.push(StyledStr::new(format!("{} ", Repeat(fill, " ")), padding));
+        boxed.push(StyledStr::new(*vertical, border));
+        boxed
+    });
+
+    [top]
+        .into_iter()
+        .chain(rows)
+        .chain([vec![StyledStr::new(bottom, border)]])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Diagnostics;
+
+    fn boxed(line_numbers: bool) -> CodeBlockStyle {
+        CodeBlockStyle {
+            boxed: true,
+            line_numbers,
+            border_style: Style::new(),
+            emphasis_style: Style::new().invert(),
+        }
+    }
+
+    fn plain(code: &str) -> Vec<HighlightedLine> {
+        code.lines()
+            .map(|line| vec![StyledStr::new(line.to_owned(), Style::new())])
+            .collect()
+    }
+
+    fn text(rows: Vec<Row>) -> Vec<String> {
+        rows.iter()
+            .map(|row| row.iter().map(|run| &**run).collect())
+            .collect()
+    }
+
+    #[test]
+    fn box_fits_wide_characters() {
+        assert_eq!(
+            vec!["╭─ text ─╮", "│ 日本語 │", "│ abc    │", "╰────────╯",],
+            text(decorate(
+                plain("日本語\nabc\n"),
+                &LineRanges::default(),
+                Some("text".into()),
+                boxed(false),
+                &UNICODE_BOX,
+                CodeWrapping::Wrap,
+                80
+            ))
+        );
+    }
+
+    #[test]
+    fn long_lines_are_wrapped_without_line_number() {
+        assert_eq!(
+            vec![
+                "+----------+",
+                "| 1 abcdef |",
+                "| > gh     |",
+                "| 2 i      |",
+                "+----------+",
+            ],
+            text(decorate(
+                plain("abcdefgh\ni\n"),
+                &LineRanges::default(),
+                None,
+                boxed(true),
+                &ASCII_BOX,
+                CodeWrapping::Wrap,
+                12
+            ))
+        );
+    }
+
+    #[test]
+    fn emphasized_lines_fill_the_box() {
+        let reverse = Style::new().invert();
+        let rows = decorate(
+            plain("a\nbcd\n"),
+            &CodeInfo::parse("{1}").emphasized,
+            None,
+            boxed(false),
+            &ASCII_BOX,
+            CodeWrapping::Wrap,
+            80,
+        );
+        let styles = |row: &Row| row.iter().map(|run| run.1).collect::<Vec<_>>();
+        assert_eq!(
+            vec![Style::new(), reverse, reverse, reverse, Style::new()],
+            styles(&rows[1])
+        );
+        assert_eq!(
+            vec![
+                Style::new(),
+                Style::new(),
+                Style::new(),
+                Style::new(),
+                Style::new()
+            ],
+            styles(&rows[2])
+        );
+        assert_eq!(vec!["+-----+", "| a   |", "| bcd |", "+-----+"], text(rows));
+    }
+
+    #[test]
+    fn emphasized_lines_without_box_are_padded_to_widest_line() {
+        let style = CodeBlockStyle {
+            emphasis_style: Style::new().invert(),
+            ..CodeBlockStyle::default()
+        };
+        let rows = decorate(
+            plain("abc\nd\n"),
+            &CodeInfo::parse("{2}").emphasized,
+            None,
+            style,
+            &ASCII_BOX,
+            CodeWrapping::Wrap,
+            80,
+        );
+        assert_eq!(Style::new().invert(), rows[1][1].1);
+        assert_eq!(vec!["abc", "d  "], text(rows));
+    }
+
+    #[test]
+    fn continuation_rows_are_marked() {
+        assert_eq!(
+            vec!["let x = ", "↪ 1;"],
+            text(decorate(
+                plain("let x = 1;"),
+                &LineRanges::default(),
+                None,
+                CodeBlockStyle::default(),
+                &UNICODE_BOX,
+                CodeWrapping::Wrap,
+                8
+            ))
+        );
+    }
+
+    #[test]
+    fn long_lines_are_truncated() {
+        assert_eq!(
+            vec!["+--------+", "| 1 abc… |", "| 2 d    |", "+--------+"],
+            text(decorate(
+                plain("abcdef\nd\n"),
+                &LineRanges::default(),
+                None,
+                boxed(true),
+                &ASCII_BOX,
+                CodeWrapping::Truncate,
+                10
+            ))
+        );
+    }
+
+    #[test]
+    fn box_honors_prefix() {
+        let mut output = Vec::new();
+        let markdown = "- item\n\n  ```rust\n  日本\n  ```\n";
+        let events = pulldown_cmark::Parser::new(markdown);
+        let options = crate::Options {
+            symbol_repertoire: crate::SymbolRepertoire::ascii(),
+            ..crate::Options::plain_text(20)
+        };
+        crate::render(events, &mut output, options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let code_lines: Vec<_> = output
+            .lines()
+            .skip_while(|line| !line.contains("rust"))
+            .collect();
+        assert_eq!(3, code_lines.len());
+        for line in code_lines {
+            let border = line.find(['+', '|']).unwrap();
+            assert_eq!(
+                2,
+                ansi::strip(&line[..border]).width(),
+                "{line:?} has no prefix"
+            );
+            assert_eq!(
+                12,
+                ansi::strip(line).width(),
+                "{line:?} has the wrong width"
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_languages_are_reported() {
+        let diagnostics = Diagnostics::default();
+        let mut output = Vec::new();
+        let events = pulldown_cmark::Parser::new("```xyz\nlet x = 1;\n```\n");
+        let options = crate::Options {
+            theme: crate::Theme::mdcat(),
+            diagnostics: diagnostics.clone(),
+            ..crate::Options::plain_text(40)
+        };
+        crate::render(events, &mut output, options).unwrap();
+        assert_eq!(
+            "\x1b[2munknown language: xyz\x1b[0m\nlet x = 1;\n",
+            String::from_utf8(output).unwrap()
+        );
+        assert_eq!(
+            vec![Diagnostic::UnknownLanguage {
+                language: "xyz".to_owned()
+            }],
+            diagnostics.take()
+        );
+    }
+
+    #[derive(Debug)]
+    struct EchoRunner;
+
+    impl crate::CodeRunner for EchoRunner {
+        fn run(&self, language: &str, code: &str) -> Option<crate::CodeOutput> {
+            Some(crate::CodeOutput {
+                stdout: format!("{language}: {code}"),
+                stderr: "\x1b[31moops\x1b[0m\n".to_owned(),
+                failure: Some("exit status: 1".to_owned()),
+            })
+        }
+    }
+
+    fn render_with_runner(markdown: &str) -> String {
+        let mut output = Vec::new();
+        let options = crate::Options {
+            theme: crate::Theme::mdcat(),
+            code_runner: Some(std::sync::Arc::new(EchoRunner)),
+            ..crate::Options::plain_text(40)
+        };
+        crate::render(pulldown_cmark::Parser::new(markdown), &mut output, options).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn runs_code_blocks_with_run_modifier() {
+        let output = render_with_runner("```sh,run\necho hi\n```\n");
+        let lines: Vec<_> = output.lines().map(ansi::strip).collect();
+        assert_eq!(
+            vec!["echo hi", "  sh: echo hi", "  oops", "  exit status: 1"],
+            lines
+        );
+    }
+
+    #[test]
+    fn only_runs_code_blocks_with_run_modifier() {
+        let output = render_with_runner("```sh\necho hi\n```\n");
+        assert_eq!(
+            vec!["echo hi"],
+            output.lines().map(ansi::strip).collect::<Vec<_>>()
+        );
+    }
+}
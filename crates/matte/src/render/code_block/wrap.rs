@@ -0,0 +1,208 @@
+use super::Row;
+use crate::style::StyledStr;
+use crate::syntax_highlighting::HighlightedLine;
+use anstyle::Style;
+use std::mem;
+use unicode_width::{UnicodeWidthChar as _, UnicodeWidthStr as _};
+
+/// Splits a line into rows that are at most `width` columns wide,
+/// continuation rows are at most `continuation_width` columns wide.
+/// Lines are wrapped after whitespace or between tokens (runs of the same style),
+/// tokens that don't fit into a row on their own are broken between characters.
+pub(super) fn wrap(
+    line: HighlightedLine,
+    width: usize,
+    continuation_width: usize,
+) -> Vec<(Row, usize)> {
+    let mut rows = vec![(Row::new(), 0)];
+    for (token, style) in tokens(line) {
+        let available = if rows.len() == 1 {
+            width
+        } else {
+            continuation_width
+        };
+        let (_, row_width) = rows.last().expect("there is always at least one row");
+        let token_width = token.trim_end().width();
+        if *row_width > 0
+            && row_width + token_width > available
+            && token_width <= continuation_width
+        {
+            rows.push((Row::new(), 0));
+        }
+
+        let mut text = String::new();
+        for c in token.chars() {
+            let char_width = c.width().unwrap_or(0);
+            let available = if rows.len() == 1 {
+                width
+            } else {
+                continuation_width
+            };
+            let (row, row_width) = rows.last_mut().expect("there is always at least one row");
+            if *row_width > 0 && *row_width + char_width > available {
+                // Continuation rows don't start with the whitespace that we wrapped at.
+                if c.is_whitespace() {
+                    continue;
+                }
+                push_run(row, mem::take(&mut text), style);
+                rows.push((Row::new(), 0));
+            }
+            text.push(c);
+            rows.last_mut().expect("there is always at least one row").1 += char_width;
+        }
+        let (row, _) = rows.last_mut().expect("there is always at least one row");
+        push_run(row, text, style);
+    }
+    rows
+}
+
+/// Tokens of the same run end up next to each other again, so they are merged.
+fn push_run(row: &mut Row, text: String, style: Style) {
+    match row.last_mut() {
+        _ if text.is_empty() => {}
+        Some(last) if last.1 == style => last.0 = format!("{}{text}", last.0).into(),
+        _ => row.push(StyledStr::new(text, style)),
+    }
+}
+
+/// Cuts off a line that is wider than `width` columns, marking the cut with an ellipsis.
+pub(super) fn truncate(line: HighlightedLine, width: usize) -> (Row, usize) {
+    let line_width = line.iter().map(|run| run.width()).sum();
+    if line_width <= width {
+        return (line, line_width);
+    }
+
+    let budget = width.saturating_sub(1);
+    let mut row = Row::new();
+    let mut row_width = 0;
+    let mut ellipsis_style = Style::new();
+    'runs: for run in line {
+        ellipsis_style = run.1;
+        let mut text = String::new();
+        for c in run.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if row_width + char_width > budget {
+                row.push(StyledStr::new(text, run.1));
+                break 'runs;
+            }
+            text.push(c);
+            row_width += char_width;
+        }
+        row.push(StyledStr::new(text, run.1));
+    }
+    row.retain(|run| !run.is_empty());
+    row.push(StyledStr::new("…", ellipsis_style));
+    (row, row_width + 1)
+}
+
+/// Splits a line into tokens that end after whitespace or where the style changes.
+/// The highlighter splits e.g. strings into their quotes and their content,
+/// so adjacent runs with the same style are merged first.
+fn tokens(line: HighlightedLine) -> Vec<(String, Style)> {
+    let mut tokens: Vec<(String, Style)> = Vec::new();
+    for run in line {
+        for c in run.chars() {
+            match tokens.last_mut() {
+                Some((token, style))
+                    if *style == run.1
+                        && (c.is_whitespace() || !token.ends_with(char::is_whitespace)) =>
+                {
+                    token.push(c)
+                }
+                _ => tokens.push((c.to_string(), run.1)),
+            }
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(rows: &[(Row, usize)]) -> Vec<(Vec<(&str, Style)>, usize)> {
+        rows.iter()
+            .map(|(row, width)| {
+                let runs: Vec<_> = row.iter().map(|run| (&**run, run.1)).collect();
+                (runs, *width)
+            })
+            .collect()
+    }
+
+    fn plain(line: &str) -> HighlightedLine {
+        vec![StyledStr::new(line.to_owned(), Style::new())]
+    }
+
+    #[test]
+    fn wrapping_keeps_styles() {
+        let bold = Style::new().bold();
+        let rows = wrap(vec![StyledStr::new("abc", bold), "de".into()], 2, 2);
+        assert_eq!(
+            vec![
+                (vec![("ab", bold)], 2),
+                (vec![("c", bold)], 1),
+                (vec![("de", Style::new())], 2),
+            ],
+            runs(&rows)
+        );
+    }
+
+    #[test]
+    fn wraps_after_whitespace() {
+        let rows = wrap(plain("let answer = 42;"), 10, 8);
+        assert_eq!(
+            vec![
+                (vec![("let answer", Style::new())], 10),
+                (vec![("= 42;", Style::new())], 5),
+            ],
+            runs(&rows)
+        );
+    }
+
+    #[test]
+    fn wraps_between_tokens() {
+        let bold = Style::new().bold();
+        let line = vec![
+            StyledStr::new("foo", Style::new()),
+            StyledStr::new("(", bold),
+            StyledStr::new("bar", Style::new()),
+        ];
+        let rows = wrap(line, 5, 5);
+        assert_eq!(
+            vec![
+                (vec![("foo", Style::new()), ("(", bold)], 4),
+                (vec![("bar", Style::new())], 3),
+            ],
+            runs(&rows)
+        );
+    }
+
+    #[test]
+    fn drops_whitespace_at_wrap() {
+        let rows = wrap(plain("ab  cd"), 3, 3);
+        assert_eq!(
+            vec![
+                (vec![("ab ", Style::new())], 3),
+                (vec![("cd", Style::new())], 2),
+            ],
+            runs(&rows)
+        );
+    }
+
+    #[test]
+    fn truncates_with_ellipsis() {
+        let bold = Style::new().bold();
+        let line = vec![
+            StyledStr::new("abc", Style::new()),
+            StyledStr::new("日本", bold),
+        ];
+        assert_eq!(
+            vec![(vec![("abc", Style::new()), ("日", bold), ("…", bold)], 6)],
+            runs(&[truncate(line, 6)])
+        );
+        assert_eq!(
+            vec![(vec![("abc", Style::new())], 3)],
+            runs(&[truncate(plain("abc"), 3)])
+        );
+    }
+}
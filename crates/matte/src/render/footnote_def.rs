@@ -4,6 +4,7 @@ use crate::chars::NO_BREAK_SPACE;
 use crate::lookahead::Lookaheadable;
 use crate::prefix::Prefix;
 use crate::style::StyledStr;
+use crate::themes::ThemeProvider as _;
 use crate::FootnoteDefinitionPlacement::*;
 use fmtastic::Superscript;
 use pulldown_cmark::CowStr;
@@ -17,6 +18,10 @@ impl Block for FootnoteDef<'_> {
         BlockKind::FootnoteDefinition
     }
 
+    fn has_nested_blocks(&self) -> bool {
+        true
+    }
+
     fn is_blank(&self, state: &Context<'_, '_, '_>) -> bool {
         !matches!(state.options().footnote_definition_placement, InPlace)
     }
@@ -33,7 +38,7 @@ impl Block for FootnoteDef<'_> {
         }
 
         let number = ctx.footnotes().get_number(&self.reference);
-        let ctx = ctx.block(prefix(number), Style::new().dimmed());
+        let ctx = ctx.block(prefix(number), style(ctx));
 
         terminated_for! {
             for event in terminated!(events, Event::End(TagEnd::FootnoteDefinition)) {
@@ -60,7 +65,7 @@ pub(super) fn render_collected_footnotes(
         for footnote in footnotes {
             let mut events = Lookaheadable::new(footnote.events.into_iter());
             while let Some(event) = events.next() {
-                let ctx = ctx.block(prefix(footnote.number), Style::new().dimmed());
+                let ctx = ctx.block(prefix(footnote.number), style(ctx));
                 render_block_from_event(event, &mut events, &ctx, w)?
             }
         }
@@ -74,6 +79,10 @@ fn write_divider(w: &mut impl Write, ctx: &Context<'_, '_, '_>) -> io::Result<()
     writeln!(w, "──────")
 }
 
+fn style(ctx: &Context<'_, '_, '_>) -> Style {
+    ctx.theme().footnote_definition_style(&ctx.theme_context())
+}
+
 fn prefix(number: usize) -> Prefix {
     Prefix::continued(StyledStr::new(
         format!("{}{NO_BREAK_SPACE}", Superscript(number)),
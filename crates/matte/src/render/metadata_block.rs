@@ -0,0 +1,104 @@
+use super::prelude::*;
+use super::{write_code, CodeInfo};
+use crate::block::Block;
+use crate::fmt_utils::Repeat;
+use crate::inline::Inline;
+use crate::prefix::Prefix;
+use crate::style::StyledStr;
+use crate::{Metadata, MetadataBlocks, ThemeProvider as _};
+use pulldown_cmark::{HeadingLevel, MetadataBlockKind};
+use unicode_width::UnicodeWidthStr as _;
+
+pub(crate) struct MetadataBlock {
+    pub(crate) kind: MetadataBlockKind,
+    pub(crate) source: String,
+}
+
+impl MetadataBlock {
+    pub(crate) fn collect<'e>(kind: MetadataBlockKind, events: &mut impl Events<'e>) -> Self {
+        let source = terminated!(events, Event::End(TagEnd::MetadataBlock(..)))
+            .filter_map(|event| match event {
+                Event::Text(text) => Some(text),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .concat();
+        Self { kind, source }
+    }
+}
+
+impl Block for MetadataBlock {
+    fn kind(&self) -> BlockKind {
+        BlockKind::MetadataBlock
+    }
+
+    fn render<'e>(
+        self,
+        _events: &mut impl Events<'e>,
+        ctx: &Context<'_, 'e, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        match ctx.options().metadata_blocks {
+            MetadataBlocks::Hidden => Ok(()),
+            MetadataBlocks::Header => match Metadata::parse(self.kind, &self.source) {
+                Some(metadata) => write_header(&metadata, ctx, w),
+                None => self.write_source(ctx, w),
+            },
+            MetadataBlocks::CodeBlock => self.write_source(ctx, w),
+        }
+    }
+
+    fn is_blank(&self, ctx: &Context<'_, '_, '_>) -> bool {
+        ctx.options().metadata_blocks == MetadataBlocks::Hidden || self.source.trim().is_empty()
+    }
+}
+
+impl MetadataBlock {
+    fn write_source(&self, ctx: &Context<'_, '_, '_>, w: &mut impl Write) -> io::Result<()> {
+        let language = match self.kind {
+            MetadataBlockKind::YamlStyle => "yaml",
+            MetadataBlockKind::PlusesStyle => "toml",
+        };
+        let info = CodeInfo {
+            language: Some(language),
+            ..CodeInfo::default()
+        };
+        write_code(&self.source, &info, ctx, w)
+    }
+}
+
+/// The title is shown like a top-level heading,
+/// followed by the remaining entries with their values lined up.
+fn write_header(
+    metadata: &Metadata,
+    ctx: &Context<'_, '_, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let title = metadata.title();
+    if let Some(title) = title {
+        let style = ctx
+            .theme()
+            .heading_style(HeadingLevel::H1, &ctx.theme_context());
+        let ctx = ctx.block(None, style);
+        w.inline_writer(&ctx)
+            .write_all([Inline::Text(title.into())])?;
+    }
+
+    let entries: Vec<_> = metadata
+        .iter()
+        .filter(|(key, _)| title.is_none() || *key != "title")
+        .collect();
+    let key_width = entries
+        .iter()
+        .map(|(key, _)| key.width())
+        .max()
+        .unwrap_or(0);
+    for (key, value) in entries {
+        let label = format!("{key}:{}", Repeat(key_width - key.width() + 1, " "));
+        let prefix = Prefix::continued(StyledStr::new(label, Style::new().bold()));
+        let ctx = ctx.block(prefix, Style::new());
+        w.inline_writer(&ctx)
+            .write_all([Inline::Text(value.to_string().into())])?;
+    }
+    Ok(())
+}
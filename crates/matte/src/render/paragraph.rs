@@ -1,6 +1,13 @@
 use super::prelude::*;
 use crate::block::Block;
+use crate::fmt_utils::Repeat;
+use crate::graphics::Graphic;
+use crate::html;
 use crate::inline::into_inlines;
+use crate::math;
+use crate::style::StyleExt as _;
+use pulldown_cmark::{CowStr, Tag};
+use unicode_width::UnicodeWidthStr as _;
 
 pub(crate) struct Paragraph;
 
@@ -15,10 +22,133 @@ impl Block for Paragraph {
         ctx: &Context<'_, 'e, '_>,
         w: &mut impl Write,
     ) -> io::Result<()> {
-        let writer = w.inline_writer(ctx);
-        writer.write_all(
-            terminated!(events, Event::End(TagEnd::Paragraph))
-                .flat_map(|event| into_inlines(event, ctx)),
-        )
+        let events = terminated!(events, Event::End(TagEnd::Paragraph)).collect();
+        write_inlines(events, ctx, w)
     }
 }
+
+/// Writes the inline events of a paragraph or list item,
+/// showing display math and images on their own.
+pub(crate) fn write_inlines<'e>(
+    events: Vec<Event<'e>>,
+    ctx: &Context<'_, 'e, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    ctx.open_elements().clear();
+    let parts = split_blocks(events, ctx);
+    if parts.is_empty() {
+        return w.inline_writer(ctx).write_all(std::iter::empty());
+    }
+    for (index, part) in parts.into_iter().enumerate() {
+        if index > 0 {
+            w.write_blank_line(ctx)?;
+        }
+        match part {
+            Part::Text(events) => w.inline_writer(ctx).write_all(
+                events
+                    .into_iter()
+                    .flat_map(|event| into_inlines(event, ctx)),
+            )?,
+            Part::DisplayMath(tex) => write_display_math(&tex, ctx, w)?,
+            Part::Graphic(graphic, alt_text) => {
+                graphic.write(ctx, w)?;
+                if graphic.has_caption() {
+                    write_caption(alt_text, ctx, w)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+enum Part<'e> {
+    Text(Vec<Event<'e>>),
+    DisplayMath(CowStr<'e>),
+    Graphic(Graphic, Vec<Event<'e>>),
+}
+
+/// Display math and images are shown as their own blocks, so we split
+/// the paragraph into the text before and after each of them.
+fn split_blocks<'e>(events: Vec<Event<'e>>, ctx: &Context<'_, '_, '_>) -> Vec<Part<'e>> {
+    let mut parts = Vec::new();
+    let mut text = Vec::new();
+    let mut events = events.into_iter();
+    while let Some(event) = events.next() {
+        match event {
+            Event::DisplayMath(tex) => {
+                push_text(&mut parts, std::mem::take(&mut text));
+                parts.push(Part::DisplayMath(tex));
+            }
+            Event::Start(Tag::Image { ref dest_url, .. }) => {
+                if let Some(graphic) = Graphic::load(dest_url, ctx) {
+                    let alt_text =
+                        terminated!(events.by_ref(), Event::End(TagEnd::Image)).collect();
+                    push_text(&mut parts, std::mem::take(&mut text));
+                    parts.push(Part::Graphic(graphic, alt_text));
+                } else {
+                    text.push(event);
+                }
+            }
+            Event::InlineHtml(ref html) => match inline_graphic(html, ctx) {
+                Some((graphic, alt_text)) => {
+                    push_text(&mut parts, std::mem::take(&mut text));
+                    parts.push(Part::Graphic(graphic, vec![Event::Text(alt_text.into())]));
+                }
+                None => text.push(event),
+            },
+            event => text.push(event),
+        }
+    }
+    push_text(&mut parts, text);
+    parts
+}
+
+fn inline_graphic(html: &str, ctx: &Context<'_, '_, '_>) -> Option<(Graphic, String)> {
+    let (src, alt_text) = html::inline_image(html)?;
+    Some((Graphic::load(&src, ctx)?, alt_text))
+}
+
+fn push_text<'e>(parts: &mut Vec<Part<'e>>, mut text: Vec<Event<'e>>) {
+    let is_break = |e: &Event<'_>| matches!(e, Event::SoftBreak | Event::HardBreak);
+    while text.last().is_some_and(is_break) {
+        text.pop();
+    }
+    let start = text.iter().position(|e| !is_break(e)).unwrap_or(text.len());
+    if start < text.len() {
+        parts.push(Part::Text(text.split_off(start)));
+    }
+}
+
+fn write_caption<'e>(
+    alt_text: Vec<Event<'e>>,
+    ctx: &Context<'_, 'e, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    if alt_text.is_empty() {
+        return Ok(());
+    }
+    let ctx = ctx.block(None, Style::new().italic());
+    w.inline_writer(&ctx).write_all(
+        alt_text
+            .into_iter()
+            .flat_map(|event| into_inlines(event, &ctx)),
+    )
+}
+
+fn write_display_math(tex: &str, ctx: &Context<'_, '_, '_>, w: &mut impl Write) -> io::Result<()> {
+    let text = math::to_unicode(tex).unwrap_or_else(|| tex.trim().to_owned());
+    let style = math::style().on_top_of(ctx.style());
+    for line in text.lines().map(str::trim) {
+        let width = line.width();
+        if width <= ctx.text_width() {
+            let padding = (ctx.text_width() - width) / 2;
+            w.write_prefix(ctx)?;
+            writeln!(w, "{}{style}{line}{Reset}", Repeat(padding, " "))?;
+        } else {
+            // Too wide to center, so we wrap it like any other text instead.
+            w.inline_writer(ctx)
+                .write_all([style.into(), CowStr::from(line).into()])?;
+        }
+    }
+    Ok(())
+}
@@ -1,6 +1,8 @@
 use super::prelude::*;
 use crate::block::Block;
 use crate::fmt_utils::Repeat;
+use crate::ThemeProvider as _;
+use unicode_width::UnicodeWidthStr as _;
 
 pub(crate) struct Rule;
 
@@ -15,20 +17,26 @@ impl Block for Rule {
         ctx: &Context<'_, 'e, '_>,
         w: &mut impl Write,
     ) -> io::Result<()> {
-        // let decoration = "∗ ∗ ∗";
-        // let padding_size = state
-        //     .available_columns()
-        //     .saturating_sub(display_width(decoration))
-        //     / 2;
-
-        // writeln!(
-        //     state.writer(),
-        //     "{pad}{decoration}{pad}",
-        //     pad = Repeat(padding_size, " "),
-        // )
-
-        let columns = ctx.available_width().saturating_sub(2);
+        let rule_style = match ctx.options().rule_style {
+            Some(rule_style) => rule_style,
+            None => ctx.theme().rule_style(&ctx.theme_context()),
+        };
+        let symbol_repertoire = ctx.options().symbol_repertoire;
         w.write_prefix(ctx)?;
-        writeln!(w, "◈{}◈", Repeat(columns, "─"))
+        match rule_style.decoration(symbol_repertoire) {
+            Some(decoration) => {
+                let padding = ctx.text_width().saturating_sub(decoration.width()) / 2;
+                writeln!(w, "{}{decoration}", Repeat(padding, " "))
+            }
+            None => {
+                let (end, line) = if symbol_repertoire.is_unicode() {
+                    ("◈", "─")
+                } else {
+                    ("+", "-")
+                };
+                let columns = ctx.available_width().saturating_sub(2);
+                writeln!(w, "{end}{}{end}", Repeat(columns, line))
+            }
+        }
     }
 }
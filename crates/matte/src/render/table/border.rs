@@ -0,0 +1,75 @@
+use crate::context::Context;
+use crate::options::SymbolRepertoire;
+use crate::themes::ThemeProvider as _;
+use anstyle::Style;
+
+#[derive(Debug, Copy, Clone)]
+pub(super) enum Border {
+    Top,
+    Header,
+    Bottom,
+}
+
+/// The characters used to draw a table's grid.
+#[derive(Debug)]
+pub(super) struct Borders {
+    pub(super) vertical: &'static str,
+    /// Marks text that had to be cut off to fit into a column.
+    pub(super) ellipsis: &'static str,
+    top: Rule,
+    header: Rule,
+    bottom: Rule,
+}
+
+/// Left, horizontal, junction and right characters of a horizontal rule.
+type Rule = (&'static str, &'static str, &'static str, &'static str);
+
+impl Borders {
+    pub(super) fn for_symbols(symbols: SymbolRepertoire) -> Self {
+        if symbols.is_unicode() {
+            Self::UNICODE
+        } else {
+            Self::ASCII
+        }
+    }
+
+    /// The width taken up by borders and cell padding
+    /// in a table with the given number of columns.
+    pub(super) fn width(columns: usize) -> usize {
+        const PADDING_AND_SEPARATOR_WIDTH: usize = 3;
+        1 + columns * PADDING_AND_SEPARATOR_WIDTH
+    }
+
+    pub(super) fn style(ctx: &Context<'_, '_, '_>) -> Style {
+        ctx.theme().table_border_style(&ctx.theme_context())
+    }
+
+    /// A thin horizontal line.
+    pub(super) fn horizontal(&self) -> &'static str {
+        self.top.1
+    }
+
+    pub(super) fn rule(&self, border: Border) -> Rule {
+        match border {
+            Border::Top => self.top,
+            Border::Header => self.header,
+            Border::Bottom => self.bottom,
+        }
+    }
+
+    const UNICODE: Self = Self {
+        vertical: "│",
+        ellipsis: "…",
+        top: ("╭", "─", "┬", "╮"),
+        header: ("╞", "═", "╪", "╡"),
+        bottom: ("╰", "─", "┴", "╯"),
+    };
+
+    const ASCII: Self = Self {
+        vertical: "|",
+        ellipsis: "...",
+        top: ("+", "-", "+", "+"),
+        header: ("+", "=", "+", "+"),
+        bottom: ("+", "-", "+", "+"),
+    };
+}
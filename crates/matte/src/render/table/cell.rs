@@ -0,0 +1,72 @@
+use super::columns::ColumnWidth;
+use crate::inline::{write_lines, Inline, WrittenLine};
+use anstyle::Style;
+use pulldown_cmark::Alignment;
+
+#[derive(Debug, Default)]
+pub(super) struct Cell<'e> {
+    inlines: Vec<Inline<'e>>,
+}
+
+impl<'e> FromIterator<Inline<'e>> for Cell<'e> {
+    fn from_iter<T: IntoIterator<Item = Inline<'e>>>(iter: T) -> Self {
+        Self {
+            inlines: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Cell<'_> {
+    pub(super) fn width(&self) -> ColumnWidth {
+        ColumnWidth {
+            // Laying out with a width of one puts every word on its own line.
+            min: self.widest_line(1),
+            max: self.widest_line(usize::MAX),
+        }
+    }
+
+    /// Wraps the cell's inlines into lines that are at most `max_width` wide.
+    /// Words that are too long to fit on a line are cut off.
+    pub(super) fn layout(&self, style: Style, max_width: usize, ellipsis: &str) -> Vec<CellLine> {
+        self.lines(style, max_width, Some(ellipsis))
+    }
+
+    fn widest_line(&self, max_width: usize) -> usize {
+        self.lines(Style::new(), max_width, None)
+            .iter()
+            .map(|l| l.width)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn lines(&self, style: Style, max_width: usize, ellipsis: Option<&str>) -> Vec<CellLine> {
+        write_lines(style, max_width, ellipsis, self.inlines.iter().cloned())
+            .into_iter()
+            .map(|WrittenLine { text, width }| CellLine { text, width })
+            .collect()
+    }
+}
+
+/// A single line of a cell, including escape sequences for styles and links.
+#[derive(Debug)]
+pub(super) struct CellLine {
+    pub(super) text: String,
+    pub(super) width: usize,
+}
+
+impl CellLine {
+    /// Number of spaces needed before the text to align it in a column.
+    pub(super) fn padding(&self, column_width: usize, alignment: Alignment) -> usize {
+        let fill = column_width.saturating_sub(self.width);
+        match alignment {
+            Alignment::None | Alignment::Left => 0,
+            Alignment::Center => fill / 2,
+            Alignment::Right => fill,
+        }
+    }
+
+    /// Number of spaces needed after the text to align it in a column.
+    pub(super) fn fill(&self, column_width: usize, alignment: Alignment) -> usize {
+        column_width.saturating_sub(self.width) - self.padding(column_width, alignment)
+    }
+}
@@ -0,0 +1,135 @@
+/// The width constraints of a single column.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub(super) struct ColumnWidth {
+    /// Width of the longest word, anything narrower cuts off words.
+    pub(super) min: usize,
+    /// Width of the longest line when the text is not wrapped.
+    pub(super) max: usize,
+}
+
+impl ColumnWidth {
+    pub(super) fn union(self, other: ColumnWidth) -> ColumnWidth {
+        ColumnWidth {
+            min: self.min.max(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+}
+
+/// Whether the columns fit into `available` columns without cutting off words.
+pub(super) fn fits(columns: &[ColumnWidth], available: usize) -> bool {
+    columns.iter().map(|c| c.min).sum::<usize>() <= available
+}
+
+/// Picks a width for each column so that all columns together fit into `available` columns.
+///
+/// Columns get their natural width if possible. Otherwise every column gets at least its minimum width
+/// and the remaining space is shared in proportion to how much each column would like to grow.
+pub(super) fn solve(columns: &[ColumnWidth], available: usize) -> Vec<usize> {
+    let max: usize = columns.iter().map(|c| c.max).sum();
+    let min: usize = columns.iter().map(|c| c.min).sum();
+
+    if max <= available {
+        columns.iter().map(|c| c.max).collect()
+    } else if min <= available {
+        grow(columns, available - min)
+    } else {
+        // Words are cut off at this point anyways, so we don't let
+        // a single long word take up the space of all other columns.
+        let fair_share = (available / columns.len().max(1)).max(1);
+        let clamped: Vec<_> = columns
+            .iter()
+            .map(|c| ColumnWidth {
+                min: c.min.min(fair_share),
+                max: c.max,
+            })
+            .collect();
+        let min: usize = clamped.iter().map(|c| c.min).sum();
+        if min <= available {
+            grow(&clamped, available - min)
+        } else {
+            vec![1; columns.len()]
+        }
+    }
+}
+
+fn grow(columns: &[ColumnWidth], extra: usize) -> Vec<usize> {
+    let growth: Vec<_> = columns.iter().map(|c| c.max - c.min).collect();
+    distribute(extra, &growth)
+        .into_iter()
+        .zip(columns)
+        .map(|(extra, c)| c.min + extra)
+        .collect()
+}
+
+/// Splits `total` in proportion to `weights` using the largest remainder method,
+/// so that the parts always add up to `total`.
+fn distribute(total: usize, weights: &[usize]) -> Vec<usize> {
+    let weight_sum: usize = weights.iter().sum();
+    if weight_sum == 0 {
+        return vec![0; weights.len()];
+    }
+
+    let mut parts: Vec<_> = weights.iter().map(|w| total * w / weight_sum).collect();
+    let mut remainders: Vec<_> = weights
+        .iter()
+        .enumerate()
+        .map(|(index, w)| (total * w % weight_sum, index))
+        .collect();
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let rest = total - parts.iter().sum::<usize>();
+    for (_, index) in remainders.into_iter().take(rest) {
+        parts[index] += 1;
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses_natural_width_when_table_fits() {
+        let columns = [width(3, 10), width(5, 20)];
+        assert_eq!(vec![10, 20], solve(&columns, 40));
+    }
+
+    #[test]
+    fn shrinks_columns_in_proportion_to_their_growth() {
+        let columns = [width(5, 10), width(10, 60)];
+        assert_eq!(vec![7, 28], solve(&columns, 35));
+    }
+
+    #[test]
+    fn columns_without_growth_keep_their_width() {
+        let columns = [width(8, 8), width(10, 80)];
+        assert_eq!(vec![8, 32], solve(&columns, 40));
+    }
+
+    #[test]
+    fn long_words_do_not_crowd_out_other_columns() {
+        let columns = [width(4, 30), width(34, 34)];
+        assert_eq!(vec![7, 13], solve(&columns, 20));
+    }
+
+    #[test]
+    fn every_column_is_at_least_one_wide() {
+        let columns = [width(1, 1), width(100, 100)];
+        assert_eq!(vec![1, 9], solve(&columns, 10));
+        let columns = [width(5, 5), width(5, 5), width(5, 5)];
+        assert_eq!(vec![1, 1, 1], solve(&columns, 2));
+    }
+
+    #[test]
+    fn distributed_parts_add_up() {
+        for total in 0..50 {
+            let parts = distribute(total, &[1, 2, 3, 7]);
+            assert_eq!(total, parts.iter().sum::<usize>());
+        }
+    }
+
+    fn width(min: usize, max: usize) -> ColumnWidth {
+        ColumnWidth { min, max }
+    }
+}
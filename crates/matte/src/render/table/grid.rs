@@ -0,0 +1,112 @@
+use super::border::{Border, Borders};
+use super::cell::{Cell, CellLine};
+use super::{header_style, Row};
+use crate::fmt_utils::Repeat;
+use crate::render::prelude::*;
+use pulldown_cmark::Alignment;
+
+pub(super) struct Grid<'a> {
+    pub(super) alignments: &'a [Alignment],
+    pub(super) widths: &'a [usize],
+    pub(super) borders: &'a Borders,
+}
+
+impl Grid<'_> {
+    pub(super) fn write(
+        &self,
+        head: &[Cell<'_>],
+        body: &[Row<'_>],
+        ctx: &Context<'_, '_, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        self.write_border(Border::Top, ctx, w)?;
+        self.write_row(head, header_style(ctx), ctx, w)?;
+        if !body.is_empty() {
+            self.write_border(Border::Header, ctx, w)?;
+        }
+        for row in body {
+            self.write_row(row, ctx.style(), ctx, w)?;
+        }
+        self.write_border(Border::Bottom, ctx, w)
+    }
+
+    fn write_border(
+        &self,
+        border: Border,
+        ctx: &Context<'_, '_, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        let (left, horizontal, junction, right) = self.borders.rule(border);
+        let style = Borders::style(ctx);
+        w.write_prefix(ctx)?;
+        write!(w, "{style}{left}")?;
+        for (index, width) in self.widths.iter().enumerate() {
+            if index > 0 {
+                write!(w, "{junction}")?;
+            }
+            write!(w, "{}", Repeat(width + 2, horizontal))?;
+        }
+        writeln!(w, "{right}{Reset}")
+    }
+
+    fn write_row(
+        &self,
+        row: &[Cell<'_>],
+        style: Style,
+        ctx: &Context<'_, '_, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        let cells: Vec<_> = self
+            .widths
+            .iter()
+            .enumerate()
+            .map(|(index, width)| {
+                row.get(index)
+                    .map(|c| c.layout(style, *width, self.borders.ellipsis))
+            })
+            .map(Option::unwrap_or_default)
+            .collect();
+        let height = cells.iter().map(Vec::len).max().unwrap_or(0).max(1);
+
+        for line in 0..height {
+            self.write_line(&cells, line, ctx, w)?;
+        }
+        Ok(())
+    }
+
+    fn write_line(
+        &self,
+        cells: &[Vec<CellLine>],
+        line: usize,
+        ctx: &Context<'_, '_, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        let vertical = self.borders.vertical;
+        let style = Borders::style(ctx);
+
+        w.write_prefix(ctx)?;
+        write!(w, "{style}{vertical}{Reset}")?;
+        for (index, (cell, width)) in cells.iter().zip(self.widths).enumerate() {
+            let alignment = self
+                .alignments
+                .get(index)
+                .copied()
+                .unwrap_or(Alignment::None);
+            let (before, text, after) = match cell.get(line) {
+                Some(l) => (
+                    l.padding(*width, alignment),
+                    &*l.text,
+                    l.fill(*width, alignment),
+                ),
+                None => (0, "", *width),
+            };
+            write!(
+                w,
+                " {}{text}{} {style}{vertical}{Reset}",
+                Repeat(before, " "),
+                Repeat(after, " ")
+            )?;
+        }
+        writeln!(w)
+    }
+}
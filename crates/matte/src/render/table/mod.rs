@@ -0,0 +1,125 @@
+use super::prelude::*;
+use crate::block::Block;
+use crate::inline::into_inlines;
+use crate::options::TableLayout;
+use crate::style::StyleExt as _;
+use crate::themes::ThemeProvider as _;
+use border::Borders;
+use cell::Cell;
+use columns::ColumnWidth;
+use grid::Grid;
+use pulldown_cmark::{Alignment, Tag};
+
+mod border;
+mod cell;
+mod columns;
+mod grid;
+mod records;
+
+pub(crate) struct Table {
+    pub(crate) alignments: Vec<Alignment>,
+}
+
+impl Block for Table {
+    fn kind(&self) -> BlockKind {
+        BlockKind::Table
+    }
+
+    fn render<'e>(
+        self,
+        events: &mut impl Events<'e>,
+        ctx: &Context<'_, 'e, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        let (head, body) = collect_table(events, ctx);
+        let columns = self
+            .alignments
+            .len()
+            .max(head.len())
+            .max(body.iter().map(Vec::len).max().unwrap_or(0));
+        if columns == 0 {
+            return Ok(());
+        }
+
+        let borders = Borders::for_symbols(ctx.options().symbol_repertoire);
+        let widths = column_widths(&head, &body, columns);
+        let available = ctx
+            .available_width()
+            .saturating_sub(Borders::width(columns));
+
+        let records = match ctx.options().table_layout {
+            TableLayout::Auto => !columns::fits(&widths, available),
+            TableLayout::Grid => false,
+            TableLayout::Records => true,
+        };
+
+        if records {
+            records::write(&head, &body, &borders, ctx, w)
+        } else {
+            let grid = Grid {
+                alignments: &self.alignments,
+                widths: &columns::solve(&widths, available),
+                borders: &borders,
+            };
+            grid.write(&head, &body, ctx, w)
+        }
+    }
+}
+
+type Row<'e> = Vec<Cell<'e>>;
+
+fn collect_table<'e>(
+    events: &mut impl Events<'e>,
+    ctx: &Context<'_, 'e, '_>,
+) -> (Row<'e>, Vec<Row<'e>>) {
+    let mut head = Row::default();
+    let mut body = Vec::default();
+
+    terminated_for! {
+        for event in terminated!(events, Event::End(TagEnd::Table)) {
+            match event {
+                Event::Start(Tag::TableHead) => head = collect_row(events, ctx),
+                Event::Start(Tag::TableRow) => body.push(collect_row(events, ctx)),
+                event => unreachable!("Unexpected event {event:?} in table"),
+            }
+        }
+    }
+
+    (head, body)
+}
+
+fn collect_row<'e>(events: &mut impl Events<'e>, ctx: &Context<'_, 'e, '_>) -> Row<'e> {
+    let mut row = Row::default();
+    terminated_for! {
+        for event in terminated!(events, Event::End(TagEnd::TableHead | TagEnd::TableRow)) {
+            reachable! {
+                let Event::Start(Tag::TableCell) = event {
+                    row.push(collect_cell(events, ctx));
+                }
+            }
+        }
+    }
+    row
+}
+
+fn collect_cell<'e>(events: &mut impl Events<'e>, ctx: &Context<'_, 'e, '_>) -> Cell<'e> {
+    ctx.open_elements().clear();
+    terminated!(events, Event::End(TagEnd::TableCell))
+        .flat_map(|event| into_inlines(event, ctx))
+        .collect()
+}
+
+fn header_style(ctx: &Context<'_, '_, '_>) -> Style {
+    let style = ctx.theme().table_header_style(&ctx.theme_context());
+    style.on_top_of(ctx.style())
+}
+
+fn column_widths(head: &[Cell<'_>], body: &[Row<'_>], columns: usize) -> Vec<ColumnWidth> {
+    let mut widths = vec![ColumnWidth { min: 1, max: 1 }; columns];
+    for row in std::iter::once(head).chain(body.iter().map(Vec::as_slice)) {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = width.union(cell.width());
+        }
+    }
+    widths
+}
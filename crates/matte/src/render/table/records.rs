@@ -0,0 +1,117 @@
+use super::border::Borders;
+use super::cell::{Cell, CellLine};
+use super::columns::{self, ColumnWidth};
+use super::{header_style, Row};
+use crate::fmt_utils::Repeat;
+use crate::render::prelude::*;
+
+/// Width of the `: ` between a header and its value.
+const SEPARATOR_WIDTH: usize = 2;
+
+/// Writes each row as a block of `header: value` lines,
+/// for tables that have too many columns to fit next to each other.
+pub(super) fn write(
+    head: &[Cell<'_>],
+    body: &[Row<'_>],
+    borders: &Borders,
+    ctx: &Context<'_, '_, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    let widths = columns::solve(
+        &[
+            column_width(head.iter()),
+            column_width(body.iter().flatten()),
+        ],
+        ctx.available_width().saturating_sub(SEPARATOR_WIDTH),
+    );
+    let record = Record {
+        head,
+        key_width: widths[0],
+        value_width: widths[1],
+        borders,
+    };
+
+    // A table without a body still shows its headers.
+    let empty = [Row::default()];
+    let rows = if body.is_empty() { &empty[..] } else { body };
+
+    for (index, row) in rows.iter().enumerate() {
+        if index > 0 {
+            write_separator(borders, ctx, w)?;
+        }
+        record.write(row, ctx, w)?;
+    }
+    Ok(())
+}
+
+fn column_width<'a, 'e: 'a>(cells: impl Iterator<Item = &'a Cell<'e>>) -> ColumnWidth {
+    cells
+        .map(Cell::width)
+        .fold(ColumnWidth::default(), ColumnWidth::union)
+}
+
+fn write_separator(
+    borders: &Borders,
+    ctx: &Context<'_, '_, '_>,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    w.write_prefix(ctx)?;
+    writeln!(
+        w,
+        "{}{}{Reset}",
+        Borders::style(ctx),
+        Repeat(ctx.available_width(), borders.horizontal())
+    )
+}
+
+struct Record<'a, 'e> {
+    head: &'a [Cell<'e>],
+    key_width: usize,
+    value_width: usize,
+    borders: &'a Borders,
+}
+
+impl Record<'_, '_> {
+    fn write(
+        &self,
+        row: &[Cell<'_>],
+        ctx: &Context<'_, '_, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        for index in 0..self.head.len().max(row.len()) {
+            let key = self
+                .head
+                .get(index)
+                .map(|c| c.layout(header_style(ctx), self.key_width, self.borders.ellipsis));
+            let value = row
+                .get(index)
+                .map(|c| c.layout(ctx.style(), self.value_width, self.borders.ellipsis));
+            self.write_field(&key.unwrap_or_default(), &value.unwrap_or_default(), ctx, w)?;
+        }
+        Ok(())
+    }
+
+    fn write_field(
+        &self,
+        key: &[CellLine],
+        value: &[CellLine],
+        ctx: &Context<'_, '_, '_>,
+        w: &mut impl Write,
+    ) -> io::Result<()> {
+        let height = key.len().max(value.len()).max(1);
+        for line in 0..height {
+            let (key_text, key_width) = key.get(line).map_or(("", 0), |l| (&*l.text, l.width));
+            let separator = if line == 0 { ":" } else { " " };
+            let value_text = value.get(line).map_or("", |l| &*l.text);
+            let fill = if value_text.is_empty() {
+                0
+            } else {
+                self.key_width.saturating_sub(key_width) + 1
+            };
+
+            w.write_prefix(ctx)?;
+            writeln!(w, "{key_text}{separator}{}{value_text}", Repeat(fill, " "))?;
+        }
+        Ok(())
+    }
+}
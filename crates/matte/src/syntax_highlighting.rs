@@ -1,51 +1,270 @@
-use anstyle::{Reset, Style};
+use crate::style::StyledStr;
+use crate::{Diagnostic, Diagnostics, SyntaxTheme};
+use anstyle::{Ansi256Color, AnsiColor, Color, Effects, RgbColor, Style};
 use bat::assets::HighlightingAssets;
-use bat::config::Config as BatConfig;
-use bat::controller::Controller as BatController;
-use bat::input::Input;
-use bat::WrappingMode;
 use matte_language_names::lookup_bat_language;
-use std::borrow::Cow;
-
-// TODO: can we detect if bat supports a given language
-// so we can gracefully fall back to plain?
-pub(crate) fn highlight(code: &str, options: &Options<'_>) -> String {
-    try_highlight(code, options)
-        .unwrap_or_else(|_error| format!("{}{code}{Reset}", Style::new().italic()))
-}
-
-fn try_highlight(code: &str, options: &Options<'_>) -> bat::error::Result<String> {
-    let assets = HighlightingAssets::from_binary(); // TODO: re-use
-    let config = bat_config(options, &assets);
-    let controller = BatController::new(&config, &assets);
-    let inputs = vec![Input::from_reader(Box::new(code.as_bytes()))];
-    let mut output = String::new();
-    controller
-        .run_with_error_handler(inputs, Some(&mut output), |e, _w| _ = dbg!(e))
-        .inspect_err(|e| _ = dbg!(e))
-        .map(|_| output)
-}
-
-#[derive(Debug)]
-pub(crate) struct Options<'a> {
-    pub(crate) available_columns: usize,
-    pub(crate) language: Option<Cow<'a, str>>,
-}
-
-// TODO: use theme appropriate for dark/light mode
-// TODO: make theme configurable
-fn bat_config<'a>(options: &'a Options<'_>, assets: &'a HighlightingAssets) -> BatConfig<'a> {
-    let language = options
-        .language
-        .as_ref()
-        .and_then(|l| lookup_bat_language(l, assets));
-    BatConfig {
-        language,
-        term_width: options.available_columns,
-        colored_output: true,
-        true_color: true,
-        wrapping_mode: WrappingMode::Character,
-        theme: "ansi".to_owned(),
-        ..Default::default()
+use std::cell::OnceCell;
+use std::fmt;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{self, FontStyle};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+use unicode_width::UnicodeWidthChar as _;
+
+/// A line of highlighted code, split into runs of text with the same style.
+pub(crate) type HighlightedLine = Vec<StyledStr<'static>>;
+
+const TAB_WIDTH: usize = 4;
+
+/// Highlights code using the syntaxes and themes bundled with bat.
+/// Loading these is expensive, so they are loaded once
+/// on first use and then shared by all code blocks of a document.
+pub(crate) struct Highlighter {
+    assets: OnceCell<HighlightingAssets>,
+    theme: SyntaxTheme,
+    diagnostics: Diagnostics,
+}
+
+impl fmt::Debug for Highlighter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Highlighter")
+            .field("assets_loaded", &self.assets.get().is_some())
+            .field("theme", &self.theme)
+            .finish()
+    }
+}
+
+impl Highlighter {
+    pub(crate) fn new(theme: SyntaxTheme, diagnostics: Diagnostics) -> Self {
+        Self {
+            assets: OnceCell::new(),
+            theme,
+            diagnostics,
+        }
+    }
+
+    /// Highlights `code` line by line. The lines don't include line endings.
+    /// Returns `None` if `language` is not known, code without a language
+    /// is highlighted as plain text and code that fails to highlight is returned in italics.
+    pub(crate) fn highlight(
+        &self,
+        code: &str,
+        language: Option<&str>,
+    ) -> Option<Vec<HighlightedLine>> {
+        let assets = self.assets.get_or_init(|| self.load_assets());
+        let Ok(syntax_set) = assets.get_syntax_set() else {
+            return Some(unhighlighted(code, Style::new().italic()));
+        };
+        let syntax = match language {
+            Some(language) => find_syntax(language, assets, syntax_set)?,
+            None => syntax_set.find_syntax_plain_text(),
+        };
+        let theme = theme(&self.theme, assets);
+        let highlighted = try_highlight(code, syntax, syntax_set, theme)
+            .unwrap_or_else(|| unhighlighted(code, Style::new().italic()));
+        Some(highlighted)
+    }
+
+    /// Checks the theme while loading the assets, so that an unknown theme is reported once.
+    fn load_assets(&self) -> HighlightingAssets {
+        let assets = HighlightingAssets::from_binary();
+        if !has_theme(&assets, self.theme.name()) {
+            self.diagnostics.report(Diagnostic::UnknownSyntaxTheme {
+                name: self.theme.name().to_owned(),
+            });
+        }
+        assets
+    }
+}
+
+fn try_highlight(
+    code: &str,
+    syntax: &SyntaxReference,
+    syntax_set: &SyntaxSet,
+    theme: &highlighting::Theme,
+) -> Option<Vec<HighlightedLine>> {
+    let mut highlighter = HighlightLines::new(syntax, theme);
+    LinesWithEndings::from(code)
+        .map(|line| {
+            let line = expand_tabs(line);
+            let regions = highlighter.highlight_line(&line, syntax_set).ok()?;
+            Some(
+                regions
+                    .into_iter()
+                    .map(|(style, text)| (style, text.trim_end_matches(['\n', '\r'])))
+                    .filter(|(_, text)| !text.is_empty())
+                    .map(|(style, text)| StyledStr::new(text.to_owned(), to_style(style)))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Splits `code` into lines without highlighting it.
+pub(crate) fn unhighlighted(code: &str, style: Style) -> Vec<HighlightedLine> {
+    code.lines()
+        .map(|line| vec![StyledStr::new(expand_tabs(line), style)])
+        .collect()
+}
+
+/// The names of the themes bundled with bat.
+pub(crate) fn theme_names() -> Vec<String> {
+    HighlightingAssets::from_binary()
+        .themes()
+        .map(str::to_owned)
+        .collect()
+}
+
+/// bat prints a warning when asked for a theme that it doesn't know,
+/// so we check that the theme exists first.
+fn theme<'a>(theme: &SyntaxTheme, assets: &'a HighlightingAssets) -> &'a highlighting::Theme {
+    let name = theme.name();
+    if has_theme(assets, name) {
+        assets.get_theme(name)
+    } else {
+        assets.get_theme(SyntaxTheme::Ansi.name())
+    }
+}
+
+fn has_theme(assets: &HighlightingAssets, name: &str) -> bool {
+    assets.themes().any(|t| t == name)
+}
+
+fn find_syntax<'a>(
+    language: &str,
+    assets: &HighlightingAssets,
+    syntax_set: &'a SyntaxSet,
+) -> Option<&'a SyntaxReference> {
+    let name = lookup_bat_language(language, assets)?;
+    syntax_set
+        .syntaxes()
+        .iter()
+        .find(|syntax| syntax.name.eq_ignore_ascii_case(name))
+        .or_else(|| syntax_set.find_syntax_by_token(name))
+}
+
+/// Tabs are replaced with spaces, otherwise we'd have no way of knowing how wide they are.
+fn expand_tabs(line: &str) -> String {
+    let mut expanded = String::with_capacity(line.len());
+    let mut column = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = TAB_WIDTH - column % TAB_WIDTH;
+            expanded.push_str(&" ".repeat(spaces));
+            column += spaces;
+        } else {
+            expanded.push(c);
+            column += c.width().unwrap_or(0);
+        }
+    }
+    expanded
+}
+
+/// Converts a syntect style the same way bat does.
+/// Italics are not used, as not all terminals support them.
+fn to_style(style: highlighting::Style) -> Style {
+    let mut effects = Effects::new();
+    if style.font_style.contains(FontStyle::BOLD) {
+        effects |= Effects::BOLD;
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        effects |= Effects::UNDERLINE;
+    }
+    Style::new()
+        .fg_color(to_color(style.foreground))
+        .effects(effects)
+}
+
+fn to_color(color: highlighting::Color) -> Option<Color> {
+    match color.a {
+        // Themes such as `ansi` encode one of the terminal's palette colors
+        // as #RRGGBBAA with AA set to 00 and RR set to the palette index...
+        0 => Some(match color.r {
+            0x00 => AnsiColor::Black.into(),
+            0x01 => AnsiColor::Red.into(),
+            0x02 => AnsiColor::Green.into(),
+            0x03 => AnsiColor::Yellow.into(),
+            0x04 => AnsiColor::Blue.into(),
+            0x05 => AnsiColor::Magenta.into(),
+            0x06 => AnsiColor::Cyan.into(),
+            0x07 => AnsiColor::White.into(),
+            n => Ansi256Color(n).into(),
+        }),
+        // ...and the terminal's default color with AA set to 01.
+        1 => None,
+        _ => Some(RgbColor(color.r, color.g, color.b).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &HighlightedLine) -> String {
+        line.iter().map(|run| &**run).collect()
+    }
+
+    #[test]
+    fn highlights_into_styled_runs() {
+        let lines = Highlighter::new(SyntaxTheme::Ansi, Diagnostics::default())
+            .highlight("fn main() {}\n", Some("rust"))
+            .unwrap();
+        assert_eq!(1, lines.len());
+        assert_eq!("fn main() {}", text(&lines[0]));
+        let keyword = lines[0].iter().find(|run| &***run == "fn").unwrap();
+        assert!(keyword.1.get_fg_color().is_some());
+    }
+
+    #[test]
+    fn unknown_languages_are_not_highlighted() {
+        let highlighter = Highlighter::new(SyntaxTheme::Ansi, Diagnostics::default());
+        assert!(highlighter
+            .highlight("a", Some("no-such-language"))
+            .is_none());
+    }
+
+    #[test]
+    fn code_without_language_is_plain() {
+        let lines = Highlighter::new(SyntaxTheme::Ansi, Diagnostics::default())
+            .highlight("a\n\nb", None)
+            .unwrap();
+        assert_eq!(
+            vec!["a", "", "b"],
+            lines.iter().map(text).collect::<Vec<_>>()
+        );
+        assert!(lines.iter().flatten().all(|run| run.1.is_plain()));
+    }
+
+    #[test]
+    fn unknown_themes_fall_back_to_ansi() {
+        let theme = SyntaxTheme::Named("no-such-theme".to_owned());
+        let diagnostics = Diagnostics::default();
+        let highlighter = Highlighter::new(theme, diagnostics.clone());
+        let lines = highlighter.highlight("fn main() {}", Some("rust")).unwrap();
+        highlighter.highlight("fn main() {}", Some("rust")).unwrap();
+        let keyword = lines[0].iter().find(|run| &***run == "fn").unwrap();
+        assert_eq!(Some(AnsiColor::Magenta.into()), keyword.1.get_fg_color());
+        assert_eq!(
+            vec![Diagnostic::UnknownSyntaxTheme {
+                name: "no-such-theme".to_owned()
+            }],
+            diagnostics.take()
+        );
+    }
+
+    #[test]
+    fn named_themes_use_true_color() {
+        let theme = SyntaxTheme::Automatic(crate::Background::Dark);
+        let lines = Highlighter::new(theme, Diagnostics::default())
+            .highlight("fn main() {}", Some("rust"))
+            .unwrap();
+        let keyword = lines[0].iter().find(|run| &***run == "fn").unwrap();
+        assert!(matches!(keyword.1.get_fg_color(), Some(Color::Rgb(_))));
+    }
+
+    #[test]
+    fn expands_tabs_to_next_tab_stop() {
+        assert_eq!("a   b       c", expand_tabs("a\tb\t\tc"));
+        assert_eq!("日本    x", expand_tabs("日本\tx"));
     }
 }
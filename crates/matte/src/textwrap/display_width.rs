@@ -1,7 +1,9 @@
 use core::fmt;
+use pulldown_cmark::CowStr;
+use std::borrow::Cow;
 use std::cell::OnceCell;
 use std::ops::Deref;
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar as _, UnicodeWidthStr};
 
 #[derive(Debug, Default, Eq)]
 pub(crate) struct DisplayWidth<T> {
@@ -15,6 +17,16 @@ impl<T> DisplayWidth<T> {
     }
 }
 
+impl DisplayWidth<CowStr<'_>> {
+    /// Takes ownership of the text, keeping the cached width.
+    pub(crate) fn into_static(self) -> DisplayWidth<CowStr<'static>> {
+        DisplayWidth {
+            value: CowStr::from(self.value.into_string()),
+            width: self.width,
+        }
+    }
+}
+
 impl<T> UnicodeWidthStr for DisplayWidth<T>
 where
     T: Deref<Target = str>,
@@ -65,3 +77,50 @@ where
         self.value.fmt(f)
     }
 }
+
+/// Cuts off `s` at `max_width` columns, marking the cut with `ellipsis`.
+pub(crate) fn truncate<'a>(s: &'a str, max_width: usize, ellipsis: &str) -> Cow<'a, str> {
+    if s.width() <= max_width {
+        return Cow::Borrowed(s);
+    }
+    // An ellipsis that is wider than `max_width` would still overflow it.
+    let ellipsis = clip(ellipsis, max_width);
+    let clipped = clip(s, max_width - ellipsis.width());
+    Cow::Owned(format!("{clipped}{ellipsis}"))
+}
+
+/// The longest prefix of `s` that is at most `max_width` columns wide.
+pub(crate) fn clip(s: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    let end = s
+        .char_indices()
+        .find(|(_, c)| {
+            width += c.width().unwrap_or(0);
+            width > max_width
+        })
+        .map_or(s.len(), |(index, _)| index);
+    &s[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_is_noop_when_text_fits() {
+        assert_eq!("foo", truncate("foo", 3, "…"));
+    }
+
+    #[test]
+    fn truncate_clips_ellipsis_to_max_width() {
+        assert_eq!("ab…", truncate("abcdef", 3, "…"));
+        assert_eq!("..", truncate("abcdef", 2, "..."));
+        assert_eq!(".", truncate("abcdef", 1, "..."));
+        assert_eq!("", truncate("abcdef", 0, "..."));
+    }
+
+    #[test]
+    fn truncate_does_not_split_wide_characters() {
+        assert_eq!("日…", truncate("日本語", 4, "…"));
+    }
+}
@@ -1,6 +1,6 @@
 mod buffer;
 mod display_width;
-pub(crate) use display_width::DisplayWidth;
+pub(crate) use display_width::{clip, truncate, DisplayWidth};
 mod fragment;
 
 #[cfg(test)]
@@ -52,11 +52,10 @@ impl<'a, P> Chunk<'a, P> {
         Chunk::Text(DisplayWidth::from(s.into()))
     }
 
-    #[cfg(test)]
     pub(crate) fn into_static(self) -> Chunk<'static, P> {
         match self {
             Chunk::LineStart => Chunk::LineStart,
-            Chunk::Text(t) => Chunk::text(t.to_owned()), // This loses the cached display width, but that's ok for tests.
+            Chunk::Text(t) => Chunk::Text(t.into_static()),
             Chunk::Passthrough(p) => Chunk::Passthrough(p),
             Chunk::LineEnd => Chunk::LineEnd,
         }
@@ -0,0 +1,35 @@
+use crate::context::Context;
+use crate::counting::SectionCounter;
+use crate::Options;
+
+/// What a theme gets to know about the element that it styles.
+#[derive(Debug)]
+pub struct ThemeContext<'a> {
+    options: &'a Options,
+    section: SectionCounter,
+    list_depth: usize,
+}
+
+impl<'a> ThemeContext<'a> {
+    pub(crate) fn new(ctx: &'a Context<'_, '_, '_>) -> Self {
+        Self {
+            options: ctx.options(),
+            section: ctx.counters().section(),
+            list_depth: ctx.list_depth(),
+        }
+    }
+
+    pub fn options(&self) -> &'a Options {
+        self.options
+    }
+
+    /// The number of the current section, e.g. `[1, 2]` for the second H2 in the first H1.
+    pub fn section(&self) -> &SectionCounter {
+        &self.section
+    }
+
+    /// How deeply lists are nested, starting at `0` for the outermost list.
+    pub fn list_depth(&self) -> usize {
+        self.list_depth
+    }
+}
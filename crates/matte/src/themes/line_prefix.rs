@@ -0,0 +1,46 @@
+use crate::prefix::Prefix;
+use crate::style::StyledStr;
+use anstyle::Style;
+use std::borrow::Cow;
+
+/// The text in front of each line of a block, e.g. the `┃ ` of block quotes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinePrefix {
+    pub(super) text: Cow<'static, str>,
+    pub(super) style: Style,
+    pub(super) continued: bool,
+}
+
+impl LinePrefix {
+    /// The same prefix on every line.
+    pub fn uniform(text: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            text: text.into(),
+            style: Style::new(),
+            continued: false,
+        }
+    }
+
+    /// A prefix on the first line, the other lines are indented to line up with it.
+    pub fn continued(text: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            continued: true,
+            ..Self::uniform(text)
+        }
+    }
+
+    pub fn with_style(self, style: Style) -> Self {
+        Self { style, ..self }
+    }
+}
+
+impl From<LinePrefix> for Prefix {
+    fn from(prefix: LinePrefix) -> Self {
+        let value = StyledStr::new(prefix.text, prefix.style);
+        if prefix.continued {
+            Prefix::continued(value)
+        } else {
+            Prefix::uniform(value)
+        }
+    }
+}
@@ -5,30 +5,37 @@ use anstyle::AnsiColor::Blue;
 pub(super) struct MdcatTheme;
 
 impl ThemeProvider for MdcatTheme {
-    fn margin_size(&self, _a: &BlockKind, _b: &BlockKind, _ctx: &Context<'_, '_, '_>) -> usize {
-        1
-    }
-
-    fn block_quote_style(&self, _kind: Option<block_quote::Kind>, _ctx: &Context<'_, '_, '_>) -> Style {
+    fn block_quote_style(&self, _kind: Option<BlockQuoteKind>, _ctx: &ThemeContext<'_>) -> Style {
         Style::new().italic()
     }
 
-    fn block_quote_prefix(&self, _kind: Option<block_quote::Kind>, _ctx: &Context<'_, '_, '_>) -> Prefix {
-        Prefix::uniform("    ")
+    fn block_quote_prefix(
+        &self,
+        _kind: Option<BlockQuoteKind>,
+        _ctx: &ThemeContext<'_>,
+    ) -> LinePrefix {
+        LinePrefix::uniform("    ")
     }
 
-    fn heading_style(&self, _level: HeadingLevel, _ctx: &Context<'_, '_, '_>) -> Style {
+    fn heading_style(&self, _level: HeadingLevel, _ctx: &ThemeContext<'_>) -> Style {
         Blue.on_default().bold()
     }
 
-    fn heading_prefix(&self, level: HeadingLevel, _ctx: &Context<'_, '_, '_>) -> Prefix {
+    fn heading_prefix(&self, level: HeadingLevel, _ctx: &ThemeContext<'_>) -> LinePrefix {
         match level {
-            HeadingLevel::H1 => Prefix::continued("┈"),
-            HeadingLevel::H2 => Prefix::continued("┈┈"),
-            HeadingLevel::H3 => Prefix::continued("┈┈┈"),
-            HeadingLevel::H4 => Prefix::continued("┈┈┈┈"),
-            HeadingLevel::H5 => Prefix::continued("┈┈┈┈┈"),
-            HeadingLevel::H6 => Prefix::continued("┈┈┈┈┈┈"),
+            HeadingLevel::H1 => LinePrefix::continued("┈"),
+            HeadingLevel::H2 => LinePrefix::continued("┈┈"),
+            HeadingLevel::H3 => LinePrefix::continued("┈┈┈"),
+            HeadingLevel::H4 => LinePrefix::continued("┈┈┈┈"),
+            HeadingLevel::H5 => LinePrefix::continued("┈┈┈┈┈"),
+            HeadingLevel::H6 => LinePrefix::continued("┈┈┈┈┈┈"),
+        }
+    }
+
+    fn code_block_style(&self, ctx: &ThemeContext<'_>) -> CodeBlockStyle {
+        CodeBlockStyle {
+            emphasis_style: emphasis_style(ctx),
+            ..CodeBlockStyle::default()
         }
     }
 }
@@ -1,13 +1,18 @@
+mod context;
 mod default;
+mod line_prefix;
 mod mdcat;
+mod toml;
 
+pub use self::context::*;
+pub use self::line_prefix::*;
+pub use self::toml::ThemeError;
 use crate::block::BlockKind;
-use crate::block_quote;
-use crate::context::Context;
-use crate::prefix::Prefix;
-use crate::style::StyledStr;
-use anstyle::Style;
-use pulldown_cmark::HeadingLevel;
+use crate::render::CodeBlockStyle;
+use crate::{Background, RuleStyle, SyntaxTheme};
+use anstyle::AnsiColor::{Blue, Green, Magenta, Red, Yellow};
+use anstyle::{Ansi256Color, Style};
+use pulldown_cmark::{BlockQuoteKind, HeadingLevel};
 use std::fmt;
 use std::sync::Arc;
 
@@ -21,57 +26,242 @@ impl Default for Theme {
 }
 
 impl Theme {
+    /// A theme that uses the given provider for styling.
+    pub fn new(provider: impl ThemeProvider + 'static) -> Self {
+        Self(Arc::new(provider))
+    }
+
     pub fn mdcat() -> Theme {
         Self(Arc::new(mdcat::MdcatTheme))
     }
+
+    /// Reads a theme from TOML that overrides parts of a built-in `base` theme:
+    ///
+    /// ```toml
+    /// base = "default" # or "mdcat"
+    ///
+    /// # Blank lines between blocks, by the kind of block before or after them.
+    /// [margins]
+    /// default = 1
+    /// before = { h1 = 2, list = 0 }
+    /// after = { heading = 0 }
+    ///
+    /// # `style`, `prefix` and `prefix_style` apply to all headings,
+    /// # the levels `h1` to `h6` can override them.
+    /// [headings]
+    /// style = "blue"
+    ///
+    /// [headings.h1]
+    /// style = "magenta bold"
+    /// prefix = "▶ "
+    ///
+    /// # The same for block quotes and the alert kinds
+    /// # `note`, `tip`, `important`, `warning` and `caution`.
+    /// [block_quotes.warning]
+    /// prefix = "! "
+    /// prefix_style = "yellow bold"
+    ///
+    /// [lists]
+    /// bullets = ["→", "·"]
+    ///
+    /// [rules]
+    /// style = "dinkus" # or "line", "fleuron" or any character
+    ///
+    /// [code_blocks]
+    /// boxed = true
+    /// line_numbers = true
+    /// border_style = "dimmed"
+    /// emphasis_style = "on 236"
+    ///
+    /// [inline_code]
+    /// style = "cyan"
+    ///
+    /// [links]
+    /// style = "underline"
+    ///
+    /// [footnotes]
+    /// reference_style = "green"
+    /// definition_style = "dimmed"
+    ///
+    /// [tables]
+    /// border_style = "#808080"
+    /// header_style = "bold"
+    /// ```
+    ///
+    /// Styles are made up of the effects `bold`, `dimmed`, `italic`, `underline`,
+    /// `strikethrough` and `invert`, a color and a background color after `on`.
+    /// Colors are names such as `red` or `bright_red`, numbers in the 256-color palette or `#rrggbb`.
+    pub fn from_toml(source: &str) -> Result<Theme, ThemeError> {
+        toml::TomlTheme::parse(source).map(Self::new)
+    }
 }
 
-pub(crate) trait ThemeProvider: fmt::Debug {
-    fn margin_size(&self, a: &BlockKind, b: &BlockKind, ctx: &Context<'_, '_, '_>) -> usize;
+/// Decides how the elements of a document look.
+///
+/// Every method defaults to the look of the [default theme](Theme::default),
+/// so a custom theme only needs to implement what it changes.
+/// Wrapping a [`Theme`], which is a provider too, allows building on top of another theme.
+pub trait ThemeProvider: fmt::Debug {
+    /// The number of blank lines between a block `a` and the block `b` that follows it.
+    /// Blocks at the start of e.g. a block quote have no margin of their own.
+    fn margin_size(&self, _a: &BlockKind, _b: &BlockKind, _ctx: &ThemeContext<'_>) -> usize {
+        1
+    }
 
-    fn block_quote_style(
-        &self,
-        kind: Option<block_quote::Kind>,
-        ctx: &Context<'_, '_, '_>,
-    ) -> Style;
+    /// The style of a block quote's content, alerts such as `> [!NOTE]` have a kind.
+    fn block_quote_style(&self, _kind: Option<BlockQuoteKind>, _ctx: &ThemeContext<'_>) -> Style {
+        Style::new()
+    }
 
     fn block_quote_prefix(
         &self,
-        kind: Option<block_quote::Kind>,
-        ctx: &Context<'_, '_, '_>,
-    ) -> Prefix;
+        kind: Option<BlockQuoteKind>,
+        _ctx: &ThemeContext<'_>,
+    ) -> LinePrefix {
+        let style = kind.map(alert_style).unwrap_or_default();
+        LinePrefix::uniform("┃ ").with_style(style)
+    }
+
+    fn heading_style(&self, level: HeadingLevel, _ctx: &ThemeContext<'_>) -> Style {
+        match level {
+            HeadingLevel::H1 => Green.on_default().bold().underline(),
+            HeadingLevel::H2 => Green.on_default().bold(),
+            _ => Blue.on_default(),
+        }
+    }
+
+    fn heading_prefix(&self, _level: HeadingLevel, ctx: &ThemeContext<'_>) -> LinePrefix {
+        LinePrefix::continued(ctx.options().heading_numbering.format(ctx.section()))
+    }
+
+    /// The bullet of unordered list items at [`ThemeContext::list_depth`].
+    fn bullet(&self, ctx: &ThemeContext<'_>) -> &str {
+        let bullets: &[&str] = if ctx.options().symbol_repertoire.is_unicode() {
+            &["•", "◦", "▪", "‣"]
+        } else {
+            &["*", "-", "+"]
+        };
+        bullets[ctx.list_depth() % bullets.len()]
+    }
+
+    /// Used unless [`Options::rule_style`](crate::Options::rule_style) is set.
+    fn rule_style(&self, _ctx: &ThemeContext<'_>) -> RuleStyle {
+        RuleStyle::line()
+    }
+
+    fn code_block_style(&self, ctx: &ThemeContext<'_>) -> CodeBlockStyle {
+        CodeBlockStyle {
+            boxed: true,
+            line_numbers: false,
+            border_style: Style::new().dimmed(),
+            emphasis_style: emphasis_style(ctx),
+        }
+    }
+
+    fn inline_code_style(&self, _ctx: &ThemeContext<'_>) -> Style {
+        Yellow.on_default().italic()
+    }
+
+    fn link_style(&self, _ctx: &ThemeContext<'_>) -> Style {
+        Style::new()
+    }
+
+    fn footnote_reference_style(&self, _ctx: &ThemeContext<'_>) -> Style {
+        Green.on_default()
+    }
+
+    fn footnote_definition_style(&self, _ctx: &ThemeContext<'_>) -> Style {
+        Style::new().dimmed()
+    }
 
-    fn heading_style(&self, level: HeadingLevel, ctx: &Context<'_, '_, '_>) -> Style;
+    fn table_border_style(&self, _ctx: &ThemeContext<'_>) -> Style {
+        Style::new().dimmed()
+    }
 
-    fn heading_prefix(&self, level: HeadingLevel, ctx: &Context<'_, '_, '_>) -> Prefix;
+    fn table_header_style(&self, _ctx: &ThemeContext<'_>) -> Style {
+        Style::new().bold()
+    }
 }
 
 impl ThemeProvider for Theme {
-    fn margin_size(&self, a: &BlockKind, b: &BlockKind, ctx: &Context<'_, '_, '_>) -> usize {
+    fn margin_size(&self, a: &BlockKind, b: &BlockKind, ctx: &ThemeContext<'_>) -> usize {
         self.0.margin_size(a, b, ctx)
     }
 
-    fn block_quote_style(
-        &self,
-        kind: Option<block_quote::Kind>,
-        ctx: &Context<'_, '_, '_>,
-    ) -> Style {
+    fn block_quote_style(&self, kind: Option<BlockQuoteKind>, ctx: &ThemeContext<'_>) -> Style {
         self.0.block_quote_style(kind, ctx)
     }
 
     fn block_quote_prefix(
         &self,
-        kind: Option<block_quote::Kind>,
-        ctx: &Context<'_, '_, '_>,
-    ) -> Prefix {
+        kind: Option<BlockQuoteKind>,
+        ctx: &ThemeContext<'_>,
+    ) -> LinePrefix {
         self.0.block_quote_prefix(kind, ctx)
     }
 
-    fn heading_style(&self, level: HeadingLevel, ctx: &Context<'_, '_, '_>) -> Style {
+    fn heading_style(&self, level: HeadingLevel, ctx: &ThemeContext<'_>) -> Style {
         self.0.heading_style(level, ctx)
     }
 
-    fn heading_prefix(&self, level: HeadingLevel, ctx: &Context<'_, '_, '_>) -> Prefix {
+    fn heading_prefix(&self, level: HeadingLevel, ctx: &ThemeContext<'_>) -> LinePrefix {
         self.0.heading_prefix(level, ctx)
     }
+
+    fn bullet(&self, ctx: &ThemeContext<'_>) -> &str {
+        self.0.bullet(ctx)
+    }
+
+    fn rule_style(&self, ctx: &ThemeContext<'_>) -> RuleStyle {
+        self.0.rule_style(ctx)
+    }
+
+    fn code_block_style(&self, ctx: &ThemeContext<'_>) -> CodeBlockStyle {
+        self.0.code_block_style(ctx)
+    }
+
+    fn inline_code_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        self.0.inline_code_style(ctx)
+    }
+
+    fn link_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        self.0.link_style(ctx)
+    }
+
+    fn footnote_reference_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        self.0.footnote_reference_style(ctx)
+    }
+
+    fn footnote_definition_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        self.0.footnote_definition_style(ctx)
+    }
+
+    fn table_border_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        self.0.table_border_style(ctx)
+    }
+
+    fn table_header_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        self.0.table_header_style(ctx)
+    }
+}
+
+/// The color of an alert such as `> [!TIP]`.
+pub(crate) fn alert_style(kind: BlockQuoteKind) -> Style {
+    match kind {
+        BlockQuoteKind::Note => Blue.on_default(),
+        BlockQuoteKind::Tip => Green.on_default(),
+        BlockQuoteKind::Important => Magenta.on_default(),
+        BlockQuoteKind::Warning => Yellow.on_default(),
+        BlockQuoteKind::Caution => Red.on_default(),
+    }
+}
+
+/// A subtle background for emphasized lines of code that
+/// stays readable with both light and dark terminal backgrounds.
+fn emphasis_style(ctx: &ThemeContext<'_>) -> Style {
+    let background = match ctx.options().syntax_theme {
+        SyntaxTheme::Automatic(Background::Light) => Ansi256Color(254),
+        _ => Ansi256Color(236),
+    };
+    Style::new().bg_color(Some(background.into()))
 }
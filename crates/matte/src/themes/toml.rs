@@ -0,0 +1,636 @@
+//! Themes described by a TOML file, see [`Theme::from_toml`].
+
+use super::*;
+use anstyle::{AnsiColor, Color, RgbColor};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::error;
+
+#[derive(Debug)]
+pub(super) struct TomlTheme {
+    base: Theme,
+    margins: Margins,
+    /// One per heading level.
+    headings: [Block; 6],
+    block_quotes: Block,
+    /// One per alert kind.
+    alerts: [Block; 5],
+    bullets: Option<Vec<String>>,
+    rule_style: Option<RuleStyle>,
+    code_blocks: CodeBlocks,
+    inline_code: Element,
+    links: Element,
+    footnotes: Footnotes,
+    tables: Tables,
+}
+
+impl TomlTheme {
+    pub(super) fn parse(source: &str) -> Result<Self, ThemeError> {
+        let file: ThemeFile = ::toml::from_str(source).map_err(ThemeError)?;
+        let headings = file.headings;
+        let all = Block {
+            style: headings.style,
+            prefix: headings.prefix,
+            prefix_style: headings.prefix_style,
+        };
+        let quotes = file.block_quotes;
+        let quote = Block {
+            style: quotes.style,
+            prefix: quotes.prefix,
+            prefix_style: quotes.prefix_style,
+        };
+        Ok(Self {
+            base: match file.base {
+                Base::Default => Theme::default(),
+                Base::Mdcat => Theme::mdcat(),
+            },
+            margins: file.margins,
+            headings: [
+                headings.h1,
+                headings.h2,
+                headings.h3,
+                headings.h4,
+                headings.h5,
+                headings.h6,
+            ]
+            .map(|level| level.or(&all)),
+            alerts: [
+                quotes.note,
+                quotes.tip,
+                quotes.important,
+                quotes.warning,
+                quotes.caution,
+            ]
+            .map(|alert| alert.or(&quote)),
+            block_quotes: quote,
+            bullets: file.lists.bullets.map(|bullets| bullets.0),
+            rule_style: file.rules.style.map(|style| style.0),
+            code_blocks: file.code_blocks,
+            inline_code: file.inline_code,
+            links: file.links,
+            footnotes: file.footnotes,
+            tables: file.tables,
+        })
+    }
+
+    fn heading(&self, level: HeadingLevel) -> &Block {
+        &self.headings[level as usize - HeadingLevel::H1 as usize]
+    }
+
+    fn block_quote(&self, kind: Option<BlockQuoteKind>) -> &Block {
+        match kind {
+            None => &self.block_quotes,
+            Some(BlockQuoteKind::Note) => &self.alerts[0],
+            Some(BlockQuoteKind::Tip) => &self.alerts[1],
+            Some(BlockQuoteKind::Important) => &self.alerts[2],
+            Some(BlockQuoteKind::Warning) => &self.alerts[3],
+            Some(BlockQuoteKind::Caution) => &self.alerts[4],
+        }
+    }
+}
+
+/// An invalid theme file, e.g. one with an unknown key.
+/// The error message points to the line and column of the problem.
+#[derive(Debug)]
+pub struct ThemeError(::toml::de::Error);
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid theme: {}", self.0)
+    }
+}
+
+impl error::Error for ThemeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl ThemeProvider for TomlTheme {
+    fn margin_size(&self, a: &BlockKind, b: &BlockKind, ctx: &ThemeContext<'_>) -> usize {
+        let margins = &self.margins;
+        match (margins.after.get(a), margins.before.get(b)) {
+            (Some(after), Some(before)) => after.max(before),
+            (Some(margin), None) | (None, Some(margin)) => margin,
+            (None, None) => (margins.default).unwrap_or_else(|| self.base.margin_size(a, b, ctx)),
+        }
+    }
+
+    fn block_quote_style(&self, kind: Option<BlockQuoteKind>, ctx: &ThemeContext<'_>) -> Style {
+        let style = self.block_quote(kind).style;
+        style.map_or_else(|| self.base.block_quote_style(kind, ctx), |s| s.0)
+    }
+
+    fn block_quote_prefix(
+        &self,
+        kind: Option<BlockQuoteKind>,
+        ctx: &ThemeContext<'_>,
+    ) -> LinePrefix {
+        self.block_quote(kind)
+            .line_prefix(self.base.block_quote_prefix(kind, ctx))
+    }
+
+    fn heading_style(&self, level: HeadingLevel, ctx: &ThemeContext<'_>) -> Style {
+        let style = self.heading(level).style;
+        style.map_or_else(|| self.base.heading_style(level, ctx), |s| s.0)
+    }
+
+    fn heading_prefix(&self, level: HeadingLevel, ctx: &ThemeContext<'_>) -> LinePrefix {
+        self.heading(level)
+            .line_prefix(self.base.heading_prefix(level, ctx))
+    }
+
+    fn bullet(&self, ctx: &ThemeContext<'_>) -> &str {
+        match &self.bullets {
+            Some(bullets) => &bullets[ctx.list_depth() % bullets.len()],
+            None => self.base.bullet(ctx),
+        }
+    }
+
+    fn rule_style(&self, ctx: &ThemeContext<'_>) -> RuleStyle {
+        (self.rule_style).unwrap_or_else(|| self.base.rule_style(ctx))
+    }
+
+    fn code_block_style(&self, ctx: &ThemeContext<'_>) -> CodeBlockStyle {
+        let base = self.base.code_block_style(ctx);
+        let style = &self.code_blocks;
+        CodeBlockStyle {
+            boxed: style.boxed.unwrap_or(base.boxed),
+            line_numbers: style.line_numbers.unwrap_or(base.line_numbers),
+            border_style: style.border_style.map_or(base.border_style, |s| s.0),
+            emphasis_style: style.emphasis_style.map_or(base.emphasis_style, |s| s.0),
+        }
+    }
+
+    fn inline_code_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        let style = self.inline_code.style;
+        style.map_or_else(|| self.base.inline_code_style(ctx), |s| s.0)
+    }
+
+    fn link_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        let style = self.links.style;
+        style.map_or_else(|| self.base.link_style(ctx), |s| s.0)
+    }
+
+    fn footnote_reference_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        let style = self.footnotes.reference_style;
+        style.map_or_else(|| self.base.footnote_reference_style(ctx), |s| s.0)
+    }
+
+    fn footnote_definition_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        let style = self.footnotes.definition_style;
+        style.map_or_else(|| self.base.footnote_definition_style(ctx), |s| s.0)
+    }
+
+    fn table_border_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        let style = self.tables.border_style;
+        style.map_or_else(|| self.base.table_border_style(ctx), |s| s.0)
+    }
+
+    fn table_header_style(&self, ctx: &ThemeContext<'_>) -> Style {
+        let style = self.tables.header_style;
+        style.map_or_else(|| self.base.table_header_style(ctx), |s| s.0)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    #[serde(default)]
+    base: Base,
+    #[serde(default)]
+    margins: Margins,
+    #[serde(default)]
+    headings: Headings,
+    #[serde(default)]
+    block_quotes: BlockQuotes,
+    #[serde(default)]
+    lists: Lists,
+    #[serde(default)]
+    rules: Rules,
+    #[serde(default)]
+    code_blocks: CodeBlocks,
+    #[serde(default)]
+    inline_code: Element,
+    #[serde(default)]
+    links: Element,
+    #[serde(default)]
+    footnotes: Footnotes,
+    #[serde(default)]
+    tables: Tables,
+}
+
+/// The built-in theme that a file builds on.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Base {
+    #[default]
+    Default,
+    Mdcat,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Margins {
+    /// Blank lines between blocks.
+    default: Option<usize>,
+    /// Blank lines before a kind of block, the larger one wins if `after` also has a margin.
+    #[serde(default)]
+    before: BlockMargins,
+    #[serde(default)]
+    after: BlockMargins,
+}
+
+/// Margins by the kind of block, a heading level wins over `heading`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlockMargins {
+    heading: Option<usize>,
+    h1: Option<usize>,
+    h2: Option<usize>,
+    h3: Option<usize>,
+    h4: Option<usize>,
+    h5: Option<usize>,
+    h6: Option<usize>,
+    paragraph: Option<usize>,
+    code_block: Option<usize>,
+    block_quote: Option<usize>,
+    list: Option<usize>,
+    rule: Option<usize>,
+    table: Option<usize>,
+    footnote_definition: Option<usize>,
+    html: Option<usize>,
+    metadata_block: Option<usize>,
+}
+
+impl BlockMargins {
+    fn get(&self, kind: &BlockKind) -> Option<usize> {
+        match kind {
+            BlockKind::Heading(level) => match level {
+                HeadingLevel::H1 => self.h1,
+                HeadingLevel::H2 => self.h2,
+                HeadingLevel::H3 => self.h3,
+                HeadingLevel::H4 => self.h4,
+                HeadingLevel::H5 => self.h5,
+                HeadingLevel::H6 => self.h6,
+            }
+            .or(self.heading),
+            BlockKind::Paragraph => self.paragraph,
+            BlockKind::CodeBlock => self.code_block,
+            BlockKind::BlockQuote => self.block_quote,
+            BlockKind::List => self.list,
+            BlockKind::Rule => self.rule,
+            BlockKind::Table => self.table,
+            BlockKind::FootnoteDefinition => self.footnote_definition,
+            BlockKind::Html => self.html,
+            BlockKind::MetadataBlock => self.metadata_block,
+        }
+    }
+}
+
+/// Styles for all headings, the levels `h1` to `h6` can override them.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Headings {
+    style: Option<StyleValue>,
+    prefix: Option<String>,
+    prefix_style: Option<StyleValue>,
+    #[serde(default)]
+    h1: Block,
+    #[serde(default)]
+    h2: Block,
+    #[serde(default)]
+    h3: Block,
+    #[serde(default)]
+    h4: Block,
+    #[serde(default)]
+    h5: Block,
+    #[serde(default)]
+    h6: Block,
+}
+
+/// Styles for all block quotes, alerts such as `> [!NOTE]` can override them.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlockQuotes {
+    style: Option<StyleValue>,
+    prefix: Option<String>,
+    prefix_style: Option<StyleValue>,
+    #[serde(default)]
+    note: Block,
+    #[serde(default)]
+    tip: Block,
+    #[serde(default)]
+    important: Block,
+    #[serde(default)]
+    warning: Block,
+    #[serde(default)]
+    caution: Block,
+}
+
+/// The style of a block's content and the prefix in front of its lines.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Block {
+    style: Option<StyleValue>,
+    prefix: Option<String>,
+    prefix_style: Option<StyleValue>,
+}
+
+impl Block {
+    /// Falls back to `other` for everything that this block leaves out.
+    fn or(self, other: &Block) -> Block {
+        Block {
+            style: self.style.or(other.style),
+            prefix: self.prefix.or_else(|| other.prefix.clone()),
+            prefix_style: self.prefix_style.or(other.prefix_style),
+        }
+    }
+
+    fn line_prefix(&self, base: LinePrefix) -> LinePrefix {
+        LinePrefix {
+            text: self.prefix.clone().map_or(base.text, Cow::Owned),
+            style: self.prefix_style.map_or(base.style, |s| s.0),
+            continued: base.continued,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Lists {
+    bullets: Option<Bullets>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rules {
+    style: Option<RuleValue>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CodeBlocks {
+    boxed: Option<bool>,
+    line_numbers: Option<bool>,
+    border_style: Option<StyleValue>,
+    emphasis_style: Option<StyleValue>,
+}
+
+/// An inline element such as a link.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Element {
+    style: Option<StyleValue>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Footnotes {
+    reference_style: Option<StyleValue>,
+    definition_style: Option<StyleValue>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Tables {
+    border_style: Option<StyleValue>,
+    header_style: Option<StyleValue>,
+}
+
+/// Bullets for each level of nesting, repeated for deeper levels.
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "Vec<String>")]
+struct Bullets(Vec<String>);
+
+impl TryFrom<Vec<String>> for Bullets {
+    type Error = &'static str;
+
+    fn try_from(bullets: Vec<String>) -> Result<Self, Self::Error> {
+        if bullets.is_empty() {
+            Err("bullets must not be an empty list")
+        } else {
+            Ok(Self(bullets))
+        }
+    }
+}
+
+/// `line`, `dinkus`, `fleuron` or any other character as an ornament.
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "String")]
+struct RuleValue(RuleStyle);
+
+impl TryFrom<String> for RuleValue {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut chars = value.chars();
+        match (value.as_str(), chars.next(), chars.next()) {
+            ("line", ..) => Ok(Self(RuleStyle::line())),
+            ("dinkus", ..) => Ok(Self(RuleStyle::dinkus())),
+            ("fleuron", ..) => Ok(Self(RuleStyle::fleuron())),
+            (_, Some(c), None) => Ok(Self(RuleStyle::ornament(c))),
+            _ => Err(format!(
+                "unknown rule style `{value}`, expected `line`, `dinkus`, `fleuron` or a character"
+            )),
+        }
+    }
+}
+
+/// A style such as `"bold yellow on #202020"`, made up of effects,
+/// a foreground color and a background color after `on`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(try_from = "String")]
+struct StyleValue(Style);
+
+impl TryFrom<String> for StyleValue {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        parse_style(&value).map(Self)
+    }
+}
+
+fn parse_style(value: &str) -> Result<Style, String> {
+    let mut style = Style::new();
+    let mut words = value.split_whitespace();
+    while let Some(word) = words.next() {
+        style = match word {
+            "bold" => style.bold(),
+            "dimmed" => style.dimmed(),
+            "italic" => style.italic(),
+            "underline" => style.underline(),
+            "strikethrough" => style.strikethrough(),
+            "invert" => style.invert(),
+            "on" => {
+                let color = words
+                    .next()
+                    .ok_or("expected a background color after `on`")?;
+                style.bg_color(Some(parse_color(color)?))
+            }
+            color => style.fg_color(Some(parse_color(color)?)),
+        };
+    }
+    Ok(style)
+}
+
+/// A color by its name, e.g. `bright_red`, its number in the 256-color palette or `#rrggbb`.
+fn parse_color(value: &str) -> Result<Color, String> {
+    use AnsiColor::*;
+    let color = match value {
+        "black" => Black.into(),
+        "red" => Red.into(),
+        "green" => Green.into(),
+        "yellow" => Yellow.into(),
+        "blue" => Blue.into(),
+        "magenta" => Magenta.into(),
+        "cyan" => Cyan.into(),
+        "white" => White.into(),
+        "bright_black" => BrightBlack.into(),
+        "bright_red" => BrightRed.into(),
+        "bright_green" => BrightGreen.into(),
+        "bright_yellow" => BrightYellow.into(),
+        "bright_blue" => BrightBlue.into(),
+        "bright_magenta" => BrightMagenta.into(),
+        "bright_cyan" => BrightCyan.into(),
+        "bright_white" => BrightWhite.into(),
+        _ => {
+            if let Ok(index) = value.parse::<u8>() {
+                Ansi256Color(index).into()
+            } else if let Some(rgb) = value.strip_prefix('#').and_then(parse_rgb) {
+                rgb.into()
+            } else {
+                return Err(format!("unknown color or effect `{value}`"));
+            }
+        }
+    };
+    Ok(color)
+}
+
+fn parse_rgb(hex: &str) -> Option<RgbColor> {
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+    Some(RgbColor(channel(0)?, channel(2)?, channel(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{Context, State};
+    use crate::Options;
+
+    #[test]
+    fn parses_styles() {
+        assert_eq!(Ok(Style::new()), parse_style(""));
+        assert_eq!(
+            Ok(Yellow.on_default().bold().italic()),
+            parse_style("bold italic yellow")
+        );
+        assert_eq!(
+            Ok(AnsiColor::BrightRed
+                .on(RgbColor(0x20, 0x20, 0x2f))
+                .underline()),
+            parse_style("underline bright_red on #20202F")
+        );
+        assert_eq!(
+            Ok(Style::new().bg_color(Some(Ansi256Color(236).into()))),
+            parse_style("on 236")
+        );
+        assert!(parse_style("blinking").is_err());
+        assert!(parse_style("red on").is_err());
+        assert!(parse_style("#12345").is_err());
+    }
+
+    #[test]
+    fn overrides_the_base_theme() {
+        let theme = TomlTheme::parse(
+            r#"
+            base = "mdcat"
+
+            [headings]
+            style = "red"
+
+            [headings.h2]
+            prefix = "§ "
+
+            [block_quotes]
+            prefix = "> "
+
+            [block_quotes.tip]
+            prefix_style = "green"
+
+            [lists]
+            bullets = ["-"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            Some(Red.on_default()),
+            theme.heading(HeadingLevel::H1).style.map(|s| s.0)
+        );
+        assert_eq!(
+            Some("§ "),
+            theme.heading(HeadingLevel::H2).prefix.as_deref()
+        );
+        assert_eq!(None, theme.heading(HeadingLevel::H3).prefix);
+        let tip = theme.block_quote(Some(BlockQuoteKind::Tip));
+        assert_eq!(Some("> "), tip.prefix.as_deref());
+        assert_eq!(Some(Green.on_default()), tip.prefix_style.map(|s| s.0));
+        assert_eq!(Some(vec!["-".to_owned()]), theme.bullets);
+    }
+
+    #[test]
+    fn resolves_margins() {
+        let theme = TomlTheme::parse(
+            r#"
+            [margins]
+            default = 3
+            before = { h1 = 2, heading = 1, list = 0 }
+            after = { heading = 0 }
+            "#,
+        )
+        .unwrap();
+        let without_default = TomlTheme::parse("[margins]\nbefore = { h1 = 2 }\n").unwrap();
+        let state = State::new(Options::plain_text(80));
+        let ctx = Context::new(&state);
+        let theme_ctx = ThemeContext::new(&ctx);
+        let margins = |theme: &TomlTheme, a, b| theme.margin_size(&a, &b, &theme_ctx);
+
+        let h1 = BlockKind::Heading(HeadingLevel::H1);
+        let h2 = BlockKind::Heading(HeadingLevel::H2);
+        assert_eq!(2, margins(&theme, BlockKind::Paragraph, h1));
+        assert_eq!(1, margins(&theme, BlockKind::Paragraph, h2));
+        assert_eq!(1, margins(&theme, h1, h2));
+        assert_eq!(0, margins(&theme, h1, BlockKind::Paragraph));
+        assert_eq!(0, margins(&theme, BlockKind::Paragraph, BlockKind::List));
+        assert_eq!(3, margins(&theme, BlockKind::Paragraph, BlockKind::Table));
+        // Without a default, the base theme decides.
+        assert_eq!(2, margins(&without_default, BlockKind::Paragraph, h1));
+        assert_eq!(
+            1,
+            margins(&without_default, BlockKind::Paragraph, BlockKind::Table)
+        );
+    }
+
+    #[test]
+    fn reports_unknown_keys_with_their_position() {
+        let error =
+            TomlTheme::parse("[headings]\nstyle = \"red\"\ncolour = \"blue\"\n").unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("line 3, column 1"), "{message}");
+        assert!(message.contains("unknown field `colour`"), "{message}");
+    }
+
+    #[test]
+    fn reports_invalid_values_with_their_position() {
+        let error = TomlTheme::parse("[links]\nstyle = \"underlined\"\n").unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("line 2, column 9"), "{message}");
+        assert!(
+            message.contains("unknown color or effect `underlined`"),
+            "{message}"
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::block::BlockKind;
 use crate::context::Context;
 use crate::inline::{InlineWriter, WritePrefixFn};
 use crate::prefix::PrefixChain;
@@ -13,7 +14,12 @@ pub(crate) trait WriteExt {
         ctx: &'p Context<'p, '_, '_>,
     ) -> InlineWriter<'a, '_, impl WritePrefixFn + 'p>;
 
-    fn write_block_start(&mut self, b: &Context<'_, '_, '_>) -> io::Result<()>;
+    fn write_block_start(
+        &mut self,
+        ctx: &Context<'_, '_, '_>,
+        kind: BlockKind,
+        leading: &[BlockKind],
+    ) -> io::Result<()>;
 }
 
 impl<W: io::Write> WriteExt for W {
@@ -38,10 +44,15 @@ impl<W: io::Write> WriteExt for W {
         })
     }
 
-    // TODO: Make this an actual margin control thing
-    fn write_block_start(&mut self, b: &Context<'_, '_, '_>) -> io::Result<()> {
-        if b.previous_block().is_some() {
-            self.write_blank_line(b)?;
+    /// Writes the margin between the previous block and a block of the given kind.
+    fn write_block_start(
+        &mut self,
+        ctx: &Context<'_, '_, '_>,
+        kind: BlockKind,
+        leading: &[BlockKind],
+    ) -> io::Result<()> {
+        for _ in 0..ctx.margin_before(kind, leading) {
+            self.write_blank_line(ctx)?;
         }
         Ok(())
     }
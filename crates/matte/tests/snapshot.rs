@@ -1,24 +1,57 @@
 use insta::{assert_snapshot, glob};
-use matte::{render, supported_parser_options, Options, Theme};
-use pulldown_cmark::{Event, MetadataBlockKind, Options as ParserOptions, Parser, Tag};
+use matte::anstyle::AnsiColor::{Cyan, Magenta};
+use matte::anstyle::Style;
+use matte::url::Url;
+use matte::{
+    render, supported_parser_options, CodeBlockStyle, CodeWrapping, ImageProtocol, LinePrefix,
+    ListNumbering, MetadataBlocks, Options, RuleStyle, Theme, ThemeContext, ThemeProvider,
+};
+use pulldown_cmark::{
+    BlockQuoteKind, Event, HeadingLevel, MetadataBlockKind, Options as ParserOptions, Parser, Tag,
+};
 use serde::Deserialize;
 use std::fs::read_to_string;
+use std::path::Path;
 
 #[test]
 fn test_snippets() {
     glob!("snippets/*.md", |path| {
         let markdown = read_to_string(path).unwrap();
-        let rendered = render_to_string(&markdown);
+        let rendered = render_to_string(&markdown, path);
         assert_snapshot!(rendered);
     })
 }
 
-fn render_to_string(input: &str) -> String {
+fn render_to_string(input: &str, path: &Path) -> String {
     let parser = Parser::new_ext(input, supported_parser_options());
     let mut buffer = Vec::new();
     let snippet_options = read_snippet_options(input);
     let mut options = Options::plain_text(snippet_options.width.unwrap_or(120));
-    options.theme = snippet_options.theme.unwrap_or_default().into();
+    options.theme = match snippet_options.theme_file {
+        Some(file) => {
+            let source = read_to_string(path.parent().unwrap().join(file)).unwrap();
+            Theme::from_toml(&source).unwrap()
+        }
+        None => snippet_options.theme.unwrap_or_default().into(),
+    };
+    options.base_url = Some(Url::from_file_path(path).unwrap());
+    options.image_protocol = snippet_options.images.map(Into::into);
+    options.metadata_blocks = snippet_options
+        .metadata_blocks
+        .map(Into::into)
+        .unwrap_or_default();
+    options.rule_style = snippet_options.rule_style.map(Into::into);
+    options.code_wrapping = snippet_options
+        .code_wrapping
+        .map(Into::into)
+        .unwrap_or_default();
+    if let Some(patterns) = snippet_options.list_numbering {
+        let patterns = patterns.iter().map(|p| p.parse().unwrap());
+        options.list_numbering = ListNumbering::by_depth(patterns);
+    }
+    if snippet_options.nested_list_numbering {
+        options.list_numbering = options.list_numbering.nested();
+    }
     render(parser, &mut buffer, options).unwrap();
     String::from_utf8(buffer).unwrap()
 }
@@ -50,6 +83,16 @@ fn extract_frontmatter(input: &str) -> String {
 struct SnippetOptions {
     width: Option<u16>,
     theme: Option<ThemeName>,
+    /// A TOML theme, relative to the snippet.
+    theme_file: Option<String>,
+    images: Option<ImageProtocolName>,
+    metadata_blocks: Option<MetadataBlocksName>,
+    rule_style: Option<RuleStyleName>,
+    code_wrapping: Option<CodeWrappingName>,
+    /// Numbering patterns for ordered lists by depth.
+    list_numbering: Option<Vec<String>>,
+    #[serde(default)]
+    nested_list_numbering: bool,
 }
 
 #[derive(Default, Deserialize)]
@@ -58,6 +101,7 @@ enum ThemeName {
     #[default]
     Default,
     Mdcat,
+    Branded,
 }
 
 impl From<ThemeName> for Theme {
@@ -65,6 +109,131 @@ impl From<ThemeName> for Theme {
         match value {
             ThemeName::Default => Theme::default(),
             ThemeName::Mdcat => Theme::mdcat(),
+            ThemeName::Branded => Theme::new(BrandedTheme),
+        }
+    }
+}
+
+/// An example of a custom theme, everything it doesn't implement looks like the default theme.
+#[derive(Debug)]
+struct BrandedTheme;
+
+impl ThemeProvider for BrandedTheme {
+    fn block_quote_prefix(
+        &self,
+        _kind: Option<BlockQuoteKind>,
+        _ctx: &ThemeContext<'_>,
+    ) -> LinePrefix {
+        LinePrefix::uniform("│ ").with_style(Magenta.on_default())
+    }
+
+    fn heading_style(&self, _level: HeadingLevel, _ctx: &ThemeContext<'_>) -> Style {
+        Magenta.on_default().bold()
+    }
+
+    fn heading_prefix(&self, level: HeadingLevel, _ctx: &ThemeContext<'_>) -> LinePrefix {
+        let marker = if level == HeadingLevel::H1 {
+            "▶ "
+        } else {
+            "▷ "
+        };
+        LinePrefix::continued(marker)
+    }
+
+    fn bullet(&self, ctx: &ThemeContext<'_>) -> &str {
+        ["→", "·"][ctx.list_depth() % 2]
+    }
+
+    fn rule_style(&self, _ctx: &ThemeContext<'_>) -> RuleStyle {
+        RuleStyle::dinkus()
+    }
+
+    fn code_block_style(&self, _ctx: &ThemeContext<'_>) -> CodeBlockStyle {
+        CodeBlockStyle::default()
+            .with_boxed(true)
+            .with_line_numbers(true)
+            .with_border_style(Magenta.on_default())
+    }
+
+    fn inline_code_style(&self, _ctx: &ThemeContext<'_>) -> Style {
+        Cyan.on_default()
+    }
+
+    fn link_style(&self, _ctx: &ThemeContext<'_>) -> Style {
+        Style::new().underline()
+    }
+
+    fn footnote_reference_style(&self, _ctx: &ThemeContext<'_>) -> Style {
+        Magenta.on_default()
+    }
+
+    fn table_border_style(&self, _ctx: &ThemeContext<'_>) -> Style {
+        Magenta.on_default()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ImageProtocolName {
+    HalfBlocks,
+    Braille,
+}
+
+impl From<ImageProtocolName> for ImageProtocol {
+    fn from(value: ImageProtocolName) -> Self {
+        match value {
+            ImageProtocolName::HalfBlocks => ImageProtocol::HalfBlocks,
+            ImageProtocolName::Braille => ImageProtocol::Braille,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum MetadataBlocksName {
+    Header,
+    CodeBlock,
+}
+
+impl From<MetadataBlocksName> for MetadataBlocks {
+    fn from(value: MetadataBlocksName) -> Self {
+        match value {
+            MetadataBlocksName::Header => MetadataBlocks::Header,
+            MetadataBlocksName::CodeBlock => MetadataBlocks::CodeBlock,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RuleStyleName {
+    Line,
+    Dinkus,
+    Fleuron,
+}
+
+impl From<RuleStyleName> for RuleStyle {
+    fn from(value: RuleStyleName) -> Self {
+        match value {
+            RuleStyleName::Line => RuleStyle::line(),
+            RuleStyleName::Dinkus => RuleStyle::dinkus(),
+            RuleStyleName::Fleuron => RuleStyle::fleuron(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CodeWrappingName {
+    Wrap,
+    Truncate,
+}
+
+impl From<CodeWrappingName> for CodeWrapping {
+    fn from(value: CodeWrappingName) -> Self {
+        match value {
+            CodeWrappingName::Wrap => CodeWrapping::Wrap,
+            CodeWrappingName::Truncate => CodeWrapping::Truncate,
         }
     }
 }
@@ -0,0 +1,151 @@
+//! Detects whether the terminal has a light or dark background.
+
+use matte::Background;
+use std::env;
+use std::io::{self, IsTerminal as _};
+
+/// Asks the terminal for its background color and falls back to `COLORFGBG`.
+/// Must be called before starting a pager, which would read the terminal's response.
+pub(crate) fn detect() -> Option<Background> {
+    // The response would end up in the input or output if it's not the terminal, e.g. `md < README.md`.
+    let interactive = io::stdin().is_terminal() && io::stdout().is_terminal();
+    interactive
+        .then(query_terminal)
+        .flatten()
+        .or_else(from_colorfgbg)
+}
+
+/// `COLORFGBG` is set by some terminals (e.g. rxvt and Konsole)
+/// to the palette indices of the foreground and background colors, e.g. `15;0`.
+fn from_colorfgbg() -> Option<Background> {
+    parse_colorfgbg(&env::var("COLORFGBG").ok()?)
+}
+
+fn parse_colorfgbg(value: &str) -> Option<Background> {
+    let background: u8 = value.rsplit(';').next()?.parse().ok()?;
+    match background {
+        0..=6 | 8 => Some(Background::Dark),
+        7 | 9..=15 => Some(Background::Light),
+        _ => None,
+    }
+}
+
+#[cfg(unix)]
+fn query_terminal() -> Option<Background> {
+    use rustix::termios::{tcgetattr, tcsetattr, LocalModes, OptionalActions};
+    use std::fs::File;
+
+    let tty = File::options()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let original = tcgetattr(&tty).ok()?;
+    let mut raw = original.clone();
+    raw.local_modes
+        .remove(LocalModes::ICANON | LocalModes::ECHO);
+    tcsetattr(&tty, OptionalActions::Now, &raw).ok()?;
+    let response = query_background_color(&tty);
+    _ = tcsetattr(&tty, OptionalActions::Now, &original);
+    parse_osc11_response(&response?)
+}
+
+#[cfg(not(unix))]
+fn query_terminal() -> Option<Background> {
+    None
+}
+
+/// Sends an OSC 11 query followed by a primary device attributes (DA1) query.
+/// Every terminal answers the latter, so we don't have to wait
+/// for the timeout when a terminal doesn't support OSC 11.
+#[cfg(unix)]
+fn query_background_color(mut tty: &std::fs::File) -> Option<String> {
+    use rustix::event::{poll, PollFd, PollFlags};
+    use std::io::{Read as _, Write as _};
+    use std::time::{Duration, Instant};
+
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    tty.write_all(b"\x1b]11;?\x1b\\\x1b[c").ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + TIMEOUT;
+    let mut response = Vec::new();
+    while !is_device_attributes_response(&response) {
+        let remaining = deadline.checked_duration_since(Instant::now())?;
+        let mut fds = [PollFd::new(&tty, PollFlags::IN)];
+        if poll(&mut fds, remaining.as_millis() as i32).ok()? == 0 {
+            return None;
+        }
+        let mut buffer = [0; 64];
+        let length = tty.read(&mut buffer).ok()?;
+        response.extend_from_slice(&buffer[..length]);
+    }
+    String::from_utf8(response).ok()
+}
+
+/// The DA1 response looks like `ESC [ ? 6 2 ; 2 2 c`.
+#[cfg(unix)]
+fn is_device_attributes_response(response: &[u8]) -> bool {
+    response.ends_with(b"c") && response.windows(3).any(|window| window == b"\x1b[?")
+}
+
+/// The response looks like `ESC ] 11 ; rgb:RRRR/GGGG/BBBB ST`,
+/// where each component has one to four hex digits.
+fn parse_osc11_response(response: &str) -> Option<Background> {
+    let start = response.find("\x1b]11;rgb:")? + "\x1b]11;rgb:".len();
+    let end = response[start..].find(['\x1b', '\x07'])? + start;
+    let mut components = response[start..end].split('/').map(|component| {
+        let is_valid =
+            (1..=4).contains(&component.len()) && component.bytes().all(|b| b.is_ascii_hexdigit());
+        if !is_valid {
+            return None;
+        }
+        let value = u16::from_str_radix(component, 16).ok()?;
+        let max = (1u32 << (4 * component.len())) - 1;
+        Some(f64::from(value) / f64::from(max))
+    });
+    let (r, g, b) = (
+        components.next()??,
+        components.next()??,
+        components.next()??,
+    );
+    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    if luminance > 0.5 {
+        Some(Background::Light)
+    } else {
+        Some(Background::Dark)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_osc11_responses() {
+        assert_eq!(
+            Some(Background::Dark),
+            parse_osc11_response("\x1b]11;rgb:1e1e/1e1e/2e2e\x1b\\\x1b[?62;22c")
+        );
+        assert_eq!(
+            Some(Background::Light),
+            parse_osc11_response("\x1b]11;rgb:ff/fa/f0\x07")
+        );
+        assert_eq!(None, parse_osc11_response("\x1b[?62;22c"));
+    }
+
+    #[test]
+    fn rejects_malformed_osc11_components() {
+        assert_eq!(None, parse_osc11_response("\x1b]11;rgb:ffffffff/0/0\x07"));
+        assert_eq!(None, parse_osc11_response("\x1b]11;rgb:ff//ff\x07"));
+        assert_eq!(None, parse_osc11_response("\x1b]11;rgb:+ff/ff/ff\x07"));
+    }
+
+    #[test]
+    fn parses_colorfgbg() {
+        assert_eq!(Some(Background::Dark), parse_colorfgbg("15;0"));
+        assert_eq!(Some(Background::Light), parse_colorfgbg("0;default;15"));
+        assert_eq!(None, parse_colorfgbg("default;default"));
+    }
+}
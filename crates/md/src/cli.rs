@@ -1,8 +1,105 @@
 use crate::input::InputArg;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use matte::{CodeWrapping, DocumentProfile, MetadataBlocks, SyntaxTheme};
+use std::convert::Infallible;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(version, about)]
 pub(crate) struct Args {
     pub(crate) input: InputArg,
+
+    /// How to show front matter, i.e. YAML or TOML metadata at the start of the document.
+    #[arg(long, value_enum, default_value_t = MetadataArg::Hidden)]
+    pub(crate) metadata: MetadataArg,
+
+    /// What kind of document this is: changelogs don't number their headings.
+    /// `auto` detects changelogs by their file name or their version headings.
+    #[arg(long, value_enum, default_value_t = ProfileArg::Auto)]
+    pub(crate) profile: ProfileArg,
+
+    /// A TOML file with a theme for headings, block quotes, lists and other elements.
+    #[arg(long, value_name = "PATH")]
+    pub(crate) theme: Option<PathBuf>,
+
+    /// The theme for highlighting code: `ansi`, `auto` to pick a light or dark theme
+    /// based on the terminal's background, or the name of any theme bundled with bat.
+    #[arg(long, value_name = "THEME", default_value = "ansi", value_parser = parse_syntax_theme)]
+    pub(crate) syntax_theme: SyntaxThemeArg,
+
+    /// What to do with lines of code that are too wide: wrap them onto continuation rows
+    /// or truncate them, which works well with pagers that scroll horizontally.
+    #[arg(long, value_enum, default_value_t = CodeWrapArg::Wrap)]
+    pub(crate) code_wrap: CodeWrapArg,
+
+    /// Run code blocks marked with `run` (e.g. ```` ```sh,run ````) and show their output.
+    /// The code runs in a sandbox without network access, which requires bubblewrap (`bwrap`)
+    /// on Linux and `sandbox-exec` on macOS. Only use this with documents that you trust.
+    #[arg(long)]
+    pub(crate) run: bool,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum SyntaxThemeArg {
+    Auto,
+    Theme(SyntaxTheme),
+}
+
+fn parse_syntax_theme(value: &str) -> Result<SyntaxThemeArg, Infallible> {
+    match value {
+        "auto" => Ok(SyntaxThemeArg::Auto),
+        "ansi" => Ok(SyntaxThemeArg::Theme(SyntaxTheme::Ansi)),
+        // Named themes are checked by the renderer, which loads bat's themes anyway.
+        name => Ok(SyntaxThemeArg::Theme(SyntaxTheme::Named(name.to_owned()))),
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub(crate) enum MetadataArg {
+    Hidden,
+    Header,
+    CodeBlock,
+}
+
+impl From<MetadataArg> for MetadataBlocks {
+    fn from(value: MetadataArg) -> Self {
+        match value {
+            MetadataArg::Hidden => MetadataBlocks::Hidden,
+            MetadataArg::Header => MetadataBlocks::Header,
+            MetadataArg::CodeBlock => MetadataBlocks::CodeBlock,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub(crate) enum ProfileArg {
+    Auto,
+    Document,
+    Changelog,
+}
+
+impl ProfileArg {
+    /// The profile to use, `None` if it should be detected.
+    pub(crate) fn profile(self) -> Option<DocumentProfile> {
+        match self {
+            ProfileArg::Auto => None,
+            ProfileArg::Document => Some(DocumentProfile::Document),
+            ProfileArg::Changelog => Some(DocumentProfile::Changelog),
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub(crate) enum CodeWrapArg {
+    Wrap,
+    Truncate,
+}
+
+impl From<CodeWrapArg> for CodeWrapping {
+    fn from(value: CodeWrapArg) -> Self {
+        match value {
+            CodeWrapArg::Wrap => CodeWrapping::Wrap,
+            CodeWrapArg::Truncate => CodeWrapping::Truncate,
+        }
+    }
 }
@@ -1,6 +1,18 @@
-use std::path::PathBuf;
+use matte::pulldown_cmark::{Event, HeadingLevel, Parser, Tag, TagEnd};
+use matte::DocumentProfile;
+use std::path::Path;
 
-pub(crate) fn is_changelog(path: &PathBuf) -> bool {
+/// Guesses the kind of document from its file name and, failing that,
+/// from its headings, so that e.g. a README that lists releases is treated as a changelog.
+pub(crate) fn detect_profile(path: Option<&Path>, markdown: &str) -> DocumentProfile {
+    if path.is_some_and(is_changelog) || has_version_headings(markdown) {
+        DocumentProfile::Changelog
+    } else {
+        DocumentProfile::Document
+    }
+}
+
+pub(crate) fn is_changelog(path: &Path) -> bool {
     path.file_stem()
         .and_then(|s| s.to_str())
         .is_some_and(is_changelog_file)
@@ -9,11 +21,139 @@ pub(crate) fn is_changelog(path: &PathBuf) -> bool {
 // Inspired by GitLab's changelog detection:
 // https://gitlab.com/gitlab-org/gitlab/-/blob/b26ef9a00781635e4359d1169473b3f3bcaeaadf/lib/gitlab/file_detector.rb#L12
 fn is_changelog_file(file_stem: &str) -> bool {
+    const NAMES: &[&str] = &[
+        "changelog",
+        "history",
+        "changes",
+        "news",
+        "release-notes",
+        "release_notes",
+        "releasenotes",
+    ];
     let file_stem = file_stem.to_lowercase();
-    file_stem.starts_with("changelog")
-        || file_stem.starts_with("history")
-        || file_stem.starts_with("changes")
-        || file_stem.starts_with("news")
-        || file_stem.starts_with("release-notes")
-        || file_stem.starts_with("release_notes")
+    NAMES.iter().any(|name| {
+        file_stem
+            .strip_prefix(name)
+            .is_some_and(is_changelog_suffix)
+    })
+}
+
+/// Changelogs can have a suffix such as `.old` or a version such as `-1.x` or `_v2`,
+/// but e.g. `newsletter` or `history-of-the-project` are not changelogs.
+fn is_changelog_suffix(suffix: &str) -> bool {
+    if suffix.is_empty() || suffix.starts_with('.') {
+        return true;
+    }
+    let Some(version) = suffix.strip_prefix(['-', '_']) else {
+        return false;
+    };
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    version.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// At least two and at least half of the section headings (H2 and below) start with a version.
+fn has_version_headings(markdown: &str) -> bool {
+    let mut headings = 0;
+    let mut version_headings = 0;
+    let mut heading_text = None::<String>;
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) if level != HeadingLevel::H1 => {
+                heading_text = Some(String::new());
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading_text) = &mut heading_text {
+                    heading_text.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some(text) = heading_text.take() {
+                    headings += 1;
+                    if is_version_heading(&text) {
+                        version_headings += 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    version_headings >= 2 && version_headings * 2 >= headings
+}
+
+/// Headings such as `1.2.0`, `[v1.2.0] - 2024-01-01`, `Version 2.0` or `Unreleased`.
+fn is_version_heading(text: &str) -> bool {
+    let text = text.trim_start().trim_start_matches('[');
+    let text = strip_prefix_ignore_case(text, "version ")
+        .or_else(|| strip_prefix_ignore_case(text, "release "))
+        .unwrap_or(text);
+    if strip_prefix_ignore_case(text, "unreleased").is_some() {
+        return true;
+    }
+    let version = text.strip_prefix(['v', 'V']).unwrap_or(text);
+    let version = version
+        .split(|c: char| c.is_whitespace() || c == ']')
+        .next()
+        .unwrap_or_default();
+    let mut parts = version.split('.');
+    let is_number = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    // Only the last part may have a suffix such as `-rc.1`.
+    parts.next().is_some_and(is_number)
+        && parts
+            .next()
+            .is_some_and(|part| is_number(part.split(['-', '+']).next().unwrap_or_default()))
+}
+
+fn strip_prefix_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &text[prefix.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_changelogs_by_name() {
+        assert!(is_changelog(Path::new("CHANGELOG.md")));
+        assert!(is_changelog(Path::new("docs/release_notes.markdown")));
+        assert!(is_changelog(Path::new("NEWS")));
+        assert!(is_changelog(Path::new("CHANGELOG.old.md")));
+        assert!(is_changelog(Path::new("changelog-1.x.md")));
+        assert!(is_changelog(Path::new("History_v2.md")));
+        assert!(!is_changelog(Path::new("README.md")));
+        assert!(!is_changelog(Path::new("newsletter.md")));
+        assert!(!is_changelog(Path::new("history-of-the-project.md")));
+        assert!(!is_changelog(Path::new("changes-to-the-api.md")));
+    }
+
+    #[test]
+    fn detects_version_headings() {
+        assert!(is_version_heading("1.2.0"));
+        assert!(is_version_heading("[v1.2.0-rc.1] - 2024-01-01"));
+        assert!(is_version_heading("Version 2.0"));
+        assert!(is_version_heading("[Unreleased]"));
+        assert!(!is_version_heading("2024 roadmap"));
+        assert!(!is_version_heading("1. Installation"));
+        assert!(!is_version_heading("Usage"));
+    }
+
+    #[test]
+    fn detects_profiles() {
+        let readme = "# tool\n\n## 1.1.0\n\n* Fixes\n\n## 1.0.0\n\n* Features\n";
+        assert_eq!(
+            DocumentProfile::Changelog,
+            detect_profile(Some(Path::new("README.md")), readme)
+        );
+        let readme = "# tool\n\n## Install\n\n## Usage\n\n## Changes in 1.0.0\n";
+        assert_eq!(
+            DocumentProfile::Document,
+            detect_profile(Some(Path::new("README.md")), readme)
+        );
+        assert_eq!(
+            DocumentProfile::Changelog,
+            detect_profile(Some(Path::new("CHANGES.md")), readme)
+        );
+        assert_eq!(DocumentProfile::Document, detect_profile(None, readme));
+    }
 }
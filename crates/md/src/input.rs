@@ -45,6 +45,13 @@ impl InputReader {
         }
     }
 
+    pub(crate) fn path(&self) -> Option<&Path> {
+        match self {
+            InputReader::Stdin(_) => None,
+            InputReader::File(_, path) => Some(path),
+        }
+    }
+
     pub(crate) fn base_url(&self) -> Result<Url, matte::file_uri::Error> {
         match self {
             InputReader::Stdin(_) => current_dir(),
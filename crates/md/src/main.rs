@@ -1,13 +1,22 @@
-use clap::Parser as _;
-use cli::Args;
+use clap::error::ErrorKind as ClapErrorKind;
+use clap::{CommandFactory as _, Parser as _};
+use cli::{Args, SyntaxThemeArg};
 use matte::pulldown_cmark::Parser;
-use matte::{render, supported_parser_options, Options};
+use matte::{
+    render, supported_parser_options, Diagnostic, Diagnostics, ImageProtocol, Options, SyntaxTheme,
+    Theme,
+};
 use output::Output;
 use pager::Pager;
 use paging::PagingChoice;
 use panic::setup_human_panic;
+use run::ShellRunner;
+use std::fs;
 use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::Arc;
 
+mod background;
 mod cli;
 mod file_detection;
 mod input;
@@ -15,6 +24,7 @@ mod output;
 mod pager;
 mod paging;
 mod panic;
+mod run;
 mod term;
 
 // TODO: nonprintables
@@ -26,6 +36,7 @@ fn main() {
     setup_human_panic();
 
     let args = Args::parse();
+    let theme = args.theme.as_deref().map(load_theme);
     let mut markdown = String::new();
     let mut input = args.input.open().unwrap();
     input.read_to_string(&mut markdown).unwrap();
@@ -35,17 +46,77 @@ fn main() {
         .unwrap_or(180);
     let mut parser = Parser::new_ext(&markdown, supported_parser_options());
 
+    // Querying the terminal while the pager is running would hand the response to the pager.
+    let syntax_theme = match args.syntax_theme {
+        SyntaxThemeArg::Auto => background::detect()
+            .map(SyntaxTheme::Automatic)
+            .unwrap_or_default(),
+        SyntaxThemeArg::Theme(theme) => theme,
+    };
     let mut output = Output::from_env(&input.name().to_string_lossy(), PagingChoice::Auto).unwrap();
     let mut options = Options::plain_text(width);
     options.base_url = Some(input.base_url().unwrap());
     options.hyperlinks = output.hyperlinks();
+    options.apply_profile(
+        args.profile
+            .profile()
+            .unwrap_or_else(|| file_detection::detect_profile(input.path(), &markdown)),
+    );
+    if let Some(theme) = theme {
+        options.theme = theme;
+    }
+    options.metadata_blocks = args.metadata.into();
+    options.syntax_theme = syntax_theme;
+    options.code_wrapping = args.code_wrap.into();
+    if args.run {
+        options.code_runner = Some(Arc::new(ShellRunner::default()));
+    }
+    // Images, even as text art, are only drawn straight to a terminal and not to pagers or files.
+    options.image_protocol = output
+        .graphics()
+        .then(|| {
+            // Text art only needs true colors, so it also works in multiplexers and over SSH.
+            term::image_protocol()
+                .or_else(|| term::supports_true_color().then_some(ImageProtocol::HalfBlocks))
+        })
+        .flatten();
     options.columns = options
         .columns
         .saturating_sub(output.decoration_width() as u16); // TODO: integers
 
+    let diagnostics = options.diagnostics.clone();
     match render(&mut parser, &mut output, options) {
         Ok(_) => {}
         Err(e) if e.kind() == ErrorKind::BrokenPipe => {}
         Err(e) => panic!("{e:?}"),
     }
+    // Waits for the pager, which would otherwise hide the warnings.
+    drop(output);
+    warn_about_unknown_syntax_theme(&diagnostics);
+}
+
+/// Named syntax themes are only checked when code is highlighted,
+/// so that bat's themes aren't loaded twice.
+fn warn_about_unknown_syntax_theme(diagnostics: &Diagnostics) {
+    for diagnostic in diagnostics.take() {
+        if let Diagnostic::UnknownSyntaxTheme { .. } = diagnostic {
+            let names = SyntaxTheme::names().join(", ");
+            eprintln!("md: {diagnostic}, available themes: auto, {names}");
+        }
+    }
+}
+
+/// Exits with a usage error if the theme can't be loaded.
+fn load_theme(path: &Path) -> Theme {
+    let theme = fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|source| Theme::from_toml(&source).map_err(|e| e.to_string()));
+    theme.unwrap_or_else(|message| {
+        Args::command()
+            .error(
+                ClapErrorKind::InvalidValue,
+                format!("can't load theme {}: {message}", path.display()),
+            )
+            .exit()
+    })
 }
@@ -1,7 +1,7 @@
 use crate::pager::Pager;
 use crate::paging::PagingChoice;
 use core::fmt;
-use std::io::{self, stdout, LineWriter, Stdout, Write as _};
+use std::io::{self, stdout, IsTerminal as _, LineWriter, Stdout, Write as _};
 use std::process::{Child, ChildStdin};
 use thiserror::Error;
 
@@ -45,6 +45,12 @@ impl Output {
         }
     }
 
+    /// Pagers don't pass through the escape sequences used for showing images
+    /// and they are just noise in files and pipes.
+    pub(crate) fn graphics(&self) -> bool {
+        matches!(self, Output::Stdout(stdout) if stdout.is_terminal())
+    }
+
     pub(crate) fn decoration_width(&self) -> usize {
         match self {
             Output::Stdout(_) => 0,
@@ -0,0 +1,316 @@
+//! Runs shell code blocks for `--run`.
+
+use matte::{CodeOutput, CodeRunner};
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Runs shell code blocks in a sandboxed subprocess.
+///
+/// The subprocess runs in an empty temporary directory, which is also its `$HOME`,
+/// with a minimal environment, no input and a timeout. The sandbox has no network access,
+/// can only read the system directories needed to run a shell and can only write to that directory.
+/// Code blocks are not run if no sandbox is available, see [`Sandbox`].
+#[derive(Debug)]
+pub(crate) struct ShellRunner {
+    timeout: Duration,
+    sandbox: Option<Sandbox>,
+}
+
+/// The environment variables passed on to code blocks.
+const ENVIRONMENT: &[&str] = &["PATH", "USER", "LANG", "LC_ALL", "TZ"];
+
+/// The directories a shell and common tools need, mounted read-only by bubblewrap.
+/// Home directories are deliberately missing so that e.g. SSH keys stay out of reach.
+const SYSTEM_DIRECTORIES: &[&str] = &["/usr", "/bin", "/sbin", "/lib", "/lib32", "/lib64", "/etc"];
+
+/// How long to wait for the output after the code exited or was killed.
+/// Processes that escaped from the process group could keep the pipes open forever.
+const OUTPUT_GRACE_PERIOD: Duration = Duration::from_millis(500);
+
+impl Default for ShellRunner {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            sandbox: Sandbox::detect(),
+        }
+    }
+}
+
+impl CodeRunner for ShellRunner {
+    fn run(&self, language: &str, code: &str) -> Option<CodeOutput> {
+        let shell = match language.to_ascii_lowercase().as_str() {
+            "sh" | "shell" => "sh",
+            "bash" => "bash",
+            "zsh" => "zsh",
+            _ => return None,
+        };
+        let Some(sandbox) = self.sandbox else {
+            return Some(CodeOutput {
+                failure: Some(format!("not run: {}", Sandbox::MISSING)),
+                ..CodeOutput::default()
+            });
+        };
+        Some(
+            self.run_shell(sandbox, shell, code)
+                .unwrap_or_else(|error| CodeOutput {
+                    failure: Some(format!("failed to run {shell}: {error}")),
+                    ..CodeOutput::default()
+                }),
+        )
+    }
+}
+
+impl ShellRunner {
+    fn run_shell(&self, sandbox: Sandbox, shell: &str, code: &str) -> io::Result<CodeOutput> {
+        let temp_dir = tempfile::Builder::new().prefix("md-run-").tempdir()?;
+        // The sandbox only knows the directory by its real path.
+        let directory = temp_dir.path().canonicalize()?;
+        let mut command = sandbox.command(&directory, shell);
+        // A process group of its own lets us kill processes started by the code as well.
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let mut child = command
+            .arg("-c")
+            .arg(code)
+            .current_dir(&directory)
+            .env_clear()
+            .envs(
+                ENVIRONMENT
+                    .iter()
+                    .filter_map(|k| Some((k, env::var_os(k)?))),
+            )
+            .env("HOME", &directory)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+        let status = wait_with_timeout(&mut child, self.timeout)?;
+        // Background processes, e.g. `sleep 600 &`, would otherwise keep the pipes open.
+        kill(&mut child);
+
+        Ok(CodeOutput {
+            stdout: stdout.recv_timeout(OUTPUT_GRACE_PERIOD).unwrap_or_default(),
+            stderr: stderr.recv_timeout(OUTPUT_GRACE_PERIOD).unwrap_or_default(),
+            failure: match status {
+                Some(status) if status.success() => None,
+                Some(status) => Some(status.to_string()),
+                None => Some(format!("timed out after {:?}", self.timeout)),
+            },
+        })
+    }
+}
+
+/// Isolates code blocks from the rest of the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Sandbox {
+    /// [bubblewrap](https://github.com/containers/bubblewrap) on Linux.
+    Bubblewrap,
+    /// Seatbelt, through `sandbox-exec`, on macOS.
+    Seatbelt,
+    /// Runs code without isolation, for testing the runner itself.
+    #[cfg(test)]
+    Unconfined,
+}
+
+impl Sandbox {
+    const MISSING: &'static str = if cfg!(target_os = "macos") {
+        "sandbox-exec is not available"
+    } else {
+        "running code blocks requires bubblewrap (bwrap)"
+    };
+
+    fn detect() -> Option<Self> {
+        if cfg!(target_os = "macos") {
+            find_program("sandbox-exec").then_some(Sandbox::Seatbelt)
+        } else if cfg!(target_os = "linux") {
+            find_program("bwrap").then_some(Sandbox::Bubblewrap)
+        } else {
+            None
+        }
+    }
+
+    /// A command that runs `shell` in the sandbox, the arguments for the shell follow.
+    fn command(self, directory: &Path, shell: &str) -> Command {
+        match self {
+            Sandbox::Bubblewrap => {
+                let mut command = Command::new("bwrap");
+                for directory in SYSTEM_DIRECTORIES {
+                    // On merged /usr systems, e.g. /bin is a symlink which bubblewrap follows.
+                    command.args(["--ro-bind-try", directory, directory]);
+                }
+                command
+                    .args(["--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"])
+                    .arg("--bind")
+                    .args([directory, directory])
+                    .arg("--chdir")
+                    .arg(directory)
+                    // No network, no access to other processes and no terminal to inject input into.
+                    .args(["--unshare-all", "--new-session", "--die-with-parent"])
+                    .args(["--", shell]);
+                command
+            }
+            Sandbox::Seatbelt => {
+                let mut command = Command::new("sandbox-exec");
+                command.arg("-D").arg(param("DIRECTORY", directory)).args([
+                    "-p",
+                    SANDBOX_EXEC_PROFILE,
+                    shell,
+                ]);
+                command
+            }
+            #[cfg(test)]
+            Sandbox::Unconfined => Command::new(shell),
+        }
+    }
+}
+
+/// Like bubblewrap, only system directories can be read. Metadata stays readable
+/// so that symlinks such as `/etc` and `/bin/sh` can be resolved.
+const SANDBOX_EXEC_PROFILE: &str = r#"(version 1)
+(allow default)
+(deny network*)
+(deny file-read*)
+(allow file-read-metadata)
+(allow file-read*
+    (subpath "/usr") (subpath "/bin") (subpath "/sbin") (subpath "/System")
+    (subpath "/private/etc") (subpath "/private/var/select") (subpath "/dev")
+    (subpath (param "DIRECTORY")))
+(deny file-write*)
+(allow file-write* (subpath (param "DIRECTORY")) (literal "/dev/null"))"#;
+
+fn param(name: &str, value: &Path) -> OsString {
+    let mut param = OsString::from(name);
+    param.push("=");
+    param.push(value);
+    param
+}
+
+fn find_program(name: impl AsRef<OsStr>) -> bool {
+    env::var_os("PATH")
+        .is_some_and(|path| env::split_paths(&path).any(|dir| dir.join(name.as_ref()).is_file()))
+}
+
+fn read_in_background(reader: Option<impl Read + Send + 'static>) -> mpsc::Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut reader) = reader {
+            _ = reader.read_to_end(&mut output);
+        }
+        _ = sender.send(String::from_utf8_lossy(&output).into_owned());
+    });
+    receiver
+}
+
+/// Returns `None` if the child was killed because it didn't exit in time.
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            kill(child);
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Kills the child's process group, which may be empty already.
+#[cfg(unix)]
+fn kill(child: &mut Child) {
+    use rustix::process::{kill_process_group, Pid, Signal};
+    _ = kill_process_group(Pid::from_child(child), Signal::Kill);
+}
+
+#[cfg(not(unix))]
+fn kill(child: &mut Child) {
+    _ = child.kill();
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn runner(timeout: Duration) -> ShellRunner {
+        ShellRunner {
+            timeout,
+            sandbox: Some(Sandbox::Unconfined),
+        }
+    }
+
+    #[test]
+    fn captures_output_and_failure() {
+        let output = runner(Duration::from_secs(10))
+            .run("sh", "echo out; echo err >&2; exit 3")
+            .unwrap();
+        assert_eq!("out\n", output.stdout);
+        assert_eq!("err\n", output.stderr);
+        assert_eq!(Some("exit status: 3".to_owned()), output.failure);
+    }
+
+    #[test]
+    fn does_not_run_other_languages() {
+        assert_eq!(
+            None,
+            runner(Duration::from_secs(10)).run("python", "print(1)")
+        );
+    }
+
+    #[test]
+    fn does_not_run_code_without_sandbox() {
+        let runner = ShellRunner {
+            timeout: Duration::from_secs(10),
+            sandbox: None,
+        };
+        let output = runner.run("sh", "touch escaped").unwrap();
+        assert_eq!(
+            Some(format!("not run: {}", Sandbox::MISSING)),
+            output.failure
+        );
+    }
+
+    #[test]
+    fn kills_code_that_runs_too_long() {
+        let output = runner(Duration::from_millis(50))
+            .run("sh", "sleep 5")
+            .unwrap();
+        assert_eq!(Some("timed out after 50ms".to_owned()), output.failure);
+    }
+
+    #[test]
+    fn kills_background_processes() {
+        let start = Instant::now();
+        let output = runner(Duration::from_millis(50))
+            .run("sh", "sleep 5 & echo hi")
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!("hi\n", output.stdout);
+    }
+
+    #[test]
+    fn isolates_code_with_bubblewrap() {
+        let directory = TempDir::new().unwrap();
+        let command = Sandbox::Bubblewrap.command(directory.path(), "sh");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!("bwrap", command.get_program());
+        assert!(args.contains(&OsStr::new("--unshare-all")));
+        assert!(args
+            .windows(3)
+            .any(|bind| bind == ["--ro-bind-try", "/usr", "/usr"]));
+        assert!(!args.contains(&OsStr::new("/")));
+        assert_eq!(Some(&OsStr::new("sh")), args.last());
+    }
+}
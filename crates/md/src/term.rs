@@ -1,5 +1,73 @@
+use matte::ImageProtocol;
 use std::env;
 
 pub(crate) fn term_is_set_and_sensible() -> bool {
     env::var_os("TERM").is_some_and(|t| t != "dumb")
 }
+
+/// Guesses the graphics protocol supported by the terminal from `TERM` and `TERM_PROGRAM`.
+pub(crate) fn image_protocol() -> Option<ImageProtocol> {
+    // Multiplexers don't forward graphics to the outer terminal.
+    let multiplexer = env::var_os("TMUX").is_some() || env::var_os("STY").is_some();
+    image_protocol_for(
+        &env::var("TERM").unwrap_or_default(),
+        &env::var("TERM_PROGRAM").unwrap_or_default(),
+        multiplexer,
+    )
+}
+
+fn image_protocol_for(term: &str, term_program: &str, multiplexer: bool) -> Option<ImageProtocol> {
+    if multiplexer {
+        return None;
+    }
+    match (term, term_program) {
+        (_, "iTerm.app" | "WezTerm") => Some(ImageProtocol::Iterm2),
+        ("xterm-kitty" | "xterm-ghostty", _) | (_, "ghostty") => Some(ImageProtocol::Kitty),
+        (term, _)
+            if ["foot", "mlterm", "yaft"]
+                .iter()
+                .any(|t| term.starts_with(t)) =>
+        {
+            Some(ImageProtocol::Sixel)
+        }
+        _ => None,
+    }
+}
+
+/// Whether the terminal advertises support for 24-bit colors via `COLORTERM`.
+pub(crate) fn supports_true_color() -> bool {
+    env::var("COLORTERM").is_ok_and(|c| c == "truecolor" || c == "24bit")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_image_protocols() {
+        let cases = [
+            ("xterm-256color", "iTerm.app", Some(ImageProtocol::Iterm2)),
+            ("xterm-256color", "WezTerm", Some(ImageProtocol::Iterm2)),
+            ("xterm-kitty", "", Some(ImageProtocol::Kitty)),
+            ("xterm-ghostty", "", Some(ImageProtocol::Kitty)),
+            ("xterm-256color", "ghostty", Some(ImageProtocol::Kitty)),
+            ("foot-extra", "", Some(ImageProtocol::Sixel)),
+            ("mlterm", "", Some(ImageProtocol::Sixel)),
+            ("xterm-256color", "Apple_Terminal", None),
+            ("", "", None),
+        ];
+        for (term, term_program, protocol) in cases {
+            assert_eq!(
+                protocol,
+                image_protocol_for(term, term_program, false),
+                "TERM={term} TERM_PROGRAM={term_program}"
+            );
+        }
+    }
+
+    #[test]
+    fn no_images_in_multiplexers() {
+        assert_eq!(None, image_protocol_for("xterm-kitty", "", true));
+        assert_eq!(None, image_protocol_for("tmux-256color", "iTerm.app", true));
+    }
+}